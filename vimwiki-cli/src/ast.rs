@@ -6,17 +6,28 @@ use std::collections::HashSet;
 use std::{
     ffi::OsStr,
     fs,
-    io::{self, Write},
+    io::{self, Read, Write},
     path::{Path, PathBuf},
 };
-use vimwiki::{HtmlConfig, HtmlWikiConfig, Language, Page};
+use vimwiki::{HtmlConfig, HtmlWikiConfig, Language, Page, SCHEMA_VERSION};
 use walkdir::WalkDir;
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct Ast {
+    /// Version of the schema describing the pages within the ast
+    pub version: u32,
     pub wikis: Vec<Wiki>,
 }
 
+impl Default for Ast {
+    fn default() -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            wikis: Vec::new(),
+        }
+    }
+}
+
 impl Ast {
     pub fn load(
         config: &HtmlConfig,
@@ -73,6 +84,38 @@ impl Ast {
         })
     }
 
+    /// Produces wikis suitable for wiki-level operations such as resolving
+    /// links between pages, borrowing the pages loaded within the ast
+    pub fn to_wikis(&self, config: &HtmlConfig) -> Vec<vimwiki::Wiki<'_>> {
+        self.wikis
+            .iter()
+            .map(|wiki| {
+                let wiki_config = config.find_wiki_by_index(wiki.index);
+                vimwiki::Wiki {
+                    index: wiki.index,
+                    name: wiki.name.as_ref().cloned(),
+                    path: wiki.path.to_path_buf(),
+                    ext: wiki_config
+                        .map(|c| c.ext.to_string())
+                        .unwrap_or_else(HtmlWikiConfig::default_ext),
+                    diary_rel_path: wiki_config
+                        .map(|c| c.diary_rel_path.to_path_buf())
+                        .unwrap_or_else(HtmlWikiConfig::default_diary_rel_path),
+                    pages: wiki
+                        .files
+                        .iter()
+                        .map(|f| {
+                            vimwiki::WikiPage::new(
+                                f.path.to_path_buf(),
+                                f.data.to_borrowed(),
+                            )
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
     /// Finds first file that matches a loaded wiki file path
     pub fn find_file_by_path<P: AsRef<Path>>(
         &self,
@@ -175,7 +218,10 @@ fn load_ast(
             .collect();
         debug!("Pruning cache down to {} files", checksums.len());
 
+        // Only cached pages live at the top of the cache directory, while
+        // other caches such as the search index live in subdirectories
         let iter = WalkDir::new(cache)
+            .max_depth(1)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file());
@@ -237,12 +283,9 @@ fn load_wiki_file(
         // If a checksum file exists for the current checksum, then we can
         // just load that as it should match what we want
         if cached_page_path.exists() {
-            let cached_page: io::Result<Page> =
-                fs::File::open(cached_page_path.as_path())
-                    .map(io::BufReader::new)
-                    .and_then(|reader| {
-                        serde_json::from_reader(reader).map_err(io::Error::from)
-                    });
+            // Cache files from other formats or schema versions fail to
+            // load and are replaced by parsing the page again
+            let cached_page = read_cached_page(cached_page_path.as_path());
 
             match cached_page {
                 Ok(page) => {
@@ -250,10 +293,10 @@ fn load_wiki_file(
                     Some(page)
                 }
                 Err(x) => {
-                    error!("{:?} :: cache corrupted: {}", path, x);
+                    warn!("{:?} :: discarding unusable cache: {}", path, x);
                     if let Err(x) = fs::remove_file(cached_page_path) {
                         error!(
-                            "{:?} :: failed to remove corrupted cache: {}",
+                            "{:?} :: failed to remove unusable cache: {}",
                             path, x
                         );
                     }
@@ -286,31 +329,11 @@ fn load_wiki_file(
     // Update our cache with the new file; old files get cleaned later
     if !has_cached_page {
         let cache_file_path = cache.join(checksum.as_str());
-        match fs::File::create(cache_file_path.as_path()) {
-            Ok(file) => {
-                let mut writer = io::BufWriter::new(file);
-                match serde_json::to_writer_pretty(&mut writer, &page) {
-                    Ok(()) => {
-                        if let Err(x) = writer.flush() {
-                            error!(
-                                "{:?} :: failed to write cache: {}",
-                                path, x
-                            );
-                        } else {
-                            debug!(
-                                "{:?} :: wrote cache to {:?}",
-                                path, cache_file_path
-                            );
-                        }
-                    }
-                    Err(x) => {
-                        error!("{:?} :: failed to write cache: {}", path, x);
-                    }
-                }
-            }
-            Err(x) => {
-                error!("{:?} :: open cache for write failed: {}", path, x);
+        match write_cached_page(cache_file_path.as_path(), &page) {
+            Ok(()) => {
+                debug!("{:?} :: wrote cache to {:?}", path, cache_file_path)
             }
+            Err(x) => error!("{:?} :: failed to write cache: {}", path, x),
         }
     }
 
@@ -320,3 +343,175 @@ fn load_wiki_file(
         data: page,
     })
 }
+
+/// Bytes at the start of every cache file, identifying it as a page encoded
+/// by this cli
+const CACHE_MAGIC: &[u8; 4] = b"VWPC";
+
+/// Version of the binary encoding of cache files, which is incremented
+/// whenever the encoding changes
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// Reads a page from a cache file, failing if the file was written using a
+/// different encoding or schema version
+fn read_cached_page(path: &Path) -> io::Result<Page<'static>> {
+    let mut reader = io::BufReader::new(fs::File::open(path)?);
+
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+    let expected = make_cache_header();
+    if header != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Cache header does not match current format",
+        ));
+    }
+
+    bincode::deserialize_from(reader)
+        .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))
+}
+
+/// Writes a page to a cache file using the current encoding, prefixed with
+/// a header describing the encoding and schema version
+fn write_cached_page(path: &Path, page: &Page) -> io::Result<()> {
+    let mut writer = io::BufWriter::new(fs::File::create(path)?);
+    writer.write_all(&make_cache_header())?;
+    bincode::serialize_into(&mut writer, page)
+        .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))?;
+    writer.flush()
+}
+
+fn make_cache_header() -> [u8; 12] {
+    let mut header = [0; 12];
+    header[..4].copy_from_slice(CACHE_MAGIC);
+    header[4..8].copy_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+    header[8..].copy_from_slice(&SCHEMA_VERSION.to_le_bytes());
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty directory for a test along with a page file
+    /// containing the given text, returning the path of the file and the
+    /// cache directory
+    fn make_test_dir(name: &str, text: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!(
+            "vimwiki-cli-ast-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("cache")).unwrap();
+
+        let path = dir.join("page.wiki");
+        fs::write(&path, text).unwrap();
+        (path, dir.join("cache"))
+    }
+
+    fn parse(text: &str) -> Page<'static> {
+        Language::from_vimwiki_str(text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned()
+    }
+
+    /// Writes a cache file for the page file that holds a different page,
+    /// overwriting the given range of the header with the given bytes
+    fn write_stale_cache(
+        path: &Path,
+        cache: &Path,
+        range: std::ops::Range<usize>,
+        bytes: &[u8],
+    ) -> PathBuf {
+        let text = fs::read_to_string(path).unwrap();
+        let checksum = format!("{:x}", Sha1::digest(text.as_bytes()));
+        let cache_path = cache.join(checksum);
+        write_cached_page(&cache_path, &parse("cached page")).unwrap();
+
+        let mut contents = fs::read(&cache_path).unwrap();
+        contents[range].copy_from_slice(bytes);
+        fs::write(&cache_path, contents).unwrap();
+        cache_path
+    }
+
+    #[test]
+    fn cached_page_should_round_trip() {
+        let (path, cache) = make_test_dir("round-trip", "= Header =\ntext\n");
+        let page = parse("= Header =\ntext\n");
+
+        let cache_path = cache.join("page");
+        write_cached_page(&cache_path, &page).unwrap();
+        assert_eq!(read_cached_page(&cache_path).unwrap(), page);
+
+        // A valid cache is used in place of parsing the file
+        write_stale_cache(&path, &cache, 0..4, CACHE_MAGIC);
+        let file = load_wiki_file(&path, &cache, false).unwrap();
+        assert_eq!(file.data, parse("cached page"));
+    }
+
+    #[test]
+    fn cached_page_should_round_trip_links_tables_and_placeholders() {
+        let text = "%title Some Page\n%date 2021-05-03\n%template custom\n\
+            %nohtml\n\
+            = Header =\n\
+            See [[other page#section|other]], [[diary:2021-05-02]], \
+            [[wn.notes:page]], [[file:image.png]] and https://example.com.\n\
+            \n\
+            | a | [[link]] |\n\
+            |---|----------|\n\
+            | b | >        |\n";
+        let (path, cache) = make_test_dir("round-trip-rich", text);
+        let page = parse(text);
+
+        let cache_path = cache.join("page");
+        write_cached_page(&cache_path, &page).unwrap();
+        assert_eq!(read_cached_page(&cache_path).unwrap(), page);
+
+        // The first load writes the cache that the second load reads from
+        let file = load_wiki_file(&path, &cache, false).unwrap();
+        assert_eq!(file.data, page);
+        let file = load_wiki_file(&path, &cache, false).unwrap();
+        assert_eq!(file.data, page);
+    }
+
+    #[test]
+    fn load_wiki_file_should_reparse_if_cache_has_wrong_magic_or_format() {
+        for (name, range, bytes) in [
+            ("magic", 0..4, b"XXXX".to_vec()),
+            (
+                "format",
+                4..8,
+                (CACHE_FORMAT_VERSION + 1).to_le_bytes().to_vec(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        {
+            let (path, cache) = make_test_dir(name, "some text\n");
+            let cache_path = write_stale_cache(&path, &cache, range, &bytes);
+            assert!(read_cached_page(&cache_path).is_err());
+
+            let file = load_wiki_file(&path, &cache, false).unwrap();
+            assert_eq!(file.data, parse("some text\n"));
+            assert_eq!(read_cached_page(&cache_path).unwrap(), file.data);
+        }
+    }
+
+    #[test]
+    fn load_wiki_file_should_reparse_if_cache_has_wrong_schema_version() {
+        let (path, cache) = make_test_dir("schema", "some text\n");
+        let cache_path = write_stale_cache(
+            &path,
+            &cache,
+            8..12,
+            &(SCHEMA_VERSION + 1).to_le_bytes(),
+        );
+        assert!(read_cached_page(&cache_path).is_err());
+
+        let file = load_wiki_file(&path, &cache, false).unwrap();
+        assert_eq!(file.data, parse("some text\n"));
+        assert_eq!(read_cached_page(&cache_path).unwrap(), file.data);
+    }
+}
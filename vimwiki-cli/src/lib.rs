@@ -8,7 +8,7 @@ use ast::Ast;
 use log::*;
 use std::path::PathBuf;
 use structopt::StructOpt;
-use vimwiki::{HtmlConfig, VimwikiConfig};
+use vimwiki::{HtmlConfig, LintConfig, VimwikiConfig};
 
 pub use opt::*;
 
@@ -41,6 +41,10 @@ pub fn run(opt: Opt) -> Result<(), ExitCodes> {
     }
 
     let res = match opt.subcommand {
+        Subcommand::Check(cmd) => {
+            let (config, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::check(cmd, opt.common, config, ast)
+        }
         Subcommand::Convert(cmd) => {
             let (config, ast) =
                 load_html_config_and_ast(&opt.common, &cmd.extra_paths)?;
@@ -50,6 +54,19 @@ pub fn run(opt: Opt) -> Result<(), ExitCodes> {
             let config = load_format_config(&opt.common)?;
             subcommand::format(cmd, opt.common, config)
         }
+        Subcommand::Generate(cmd) => {
+            let (config, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::generate(cmd, opt.common, config, ast)
+        }
+        Subcommand::Graph(cmd) => {
+            let (config, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::graph(cmd, opt.common, config, ast)
+        }
+        Subcommand::Schema(cmd) => subcommand::schema(cmd),
+        Subcommand::Search(cmd) => {
+            let (_, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::search(cmd, opt.common, ast)
+        }
         Subcommand::Serve(cmd) => {
             let (config, ast) =
                 load_html_config_and_ast(&opt.common, &cmd.extra_paths)?;
@@ -60,6 +77,27 @@ pub fn run(opt: Opt) -> Result<(), ExitCodes> {
                 load_html_config_and_ast(&opt.common, &cmd.extra_paths)?;
             subcommand::inspect(cmd, opt.common, config, ast)
         }
+        Subcommand::Lint(cmd) => {
+            let lint_config = load_lint_config(&opt.common)?;
+            let (config, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::lint(cmd, opt.common, config, lint_config, ast)
+        }
+        Subcommand::Pages(cmd) => {
+            let (_, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::pages(cmd, opt.common, ast)
+        }
+        Subcommand::Rename(cmd) => {
+            let (config, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::rename(cmd, opt.common, config, ast)
+        }
+        Subcommand::Site(cmd) => {
+            let (config, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::site(cmd, opt.common, config, ast)
+        }
+        Subcommand::Tags(cmd) => {
+            let (config, ast) = load_html_config_and_ast(&opt.common, &[])?;
+            subcommand::tags(cmd, opt.common, config, ast)
+        }
     };
 
     #[cfg(feature = "timekeeper")]
@@ -87,6 +125,17 @@ fn load_format_config(opt: &CommonOpt) -> Result<VimwikiConfig, ExitCodes> {
     }
 }
 
+fn load_lint_config(opt: &CommonOpt) -> Result<LintConfig, ExitCodes> {
+    if let Some(path) = opt.config.as_ref() {
+        utils::load_lint_config(path).map_err(|x| {
+            error!("Failed to load config: {}", x);
+            ExitCodes::FailedToLoadConfig
+        })
+    } else {
+        Ok(LintConfig::default())
+    }
+}
+
 fn load_html_config_and_ast(
     opt: &CommonOpt,
     extra_paths: &[PathBuf],
@@ -2,6 +2,7 @@ use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use std::path::PathBuf;
 use structopt::StructOpt;
+use vimwiki::{MarkdownFlavor, MetadataFilter};
 
 lazy_static! {
     static ref DEFAULT_CACHE_DIR: String =
@@ -88,27 +89,70 @@ impl CommonOpt {
 
 #[derive(Debug, StructOpt)]
 pub enum Subcommand {
+    Check(CheckSubcommand),
     Convert(ConvertSubcommand),
     Format(FormatSubcommand),
+    Generate(GenerateSubcommand),
+    Graph(GraphSubcommand),
     Inspect(InspectSubcommand),
+    Lint(LintSubcommand),
+    Pages(PagesSubcommand),
+    Rename(RenameSubcommand),
+    Schema(SchemaSubcommand),
+    Search(SearchSubcommand),
     Serve(ServeSubcommand),
+    Site(SiteSubcommand),
+    Tags(TagsSubcommand),
 }
 
 impl Subcommand {
     /// Returns extra paths to process
     pub fn extra_paths(&self) -> &[PathBuf] {
         match self {
+            Self::Check(_) => &[],
             Self::Convert(x) => &x.extra_paths,
             Self::Format(x) => &x.paths,
+            Self::Generate(_) => &[],
+            Self::Graph(_) => &[],
             Self::Inspect(x) => &x.extra_paths,
+            Self::Lint(_) => &[],
+            Self::Pages(_) => &[],
+            Self::Rename(_) => &[],
+            Self::Schema(_) => &[],
+            Self::Search(_) => &[],
             Self::Serve(x) => &x.extra_paths,
+            Self::Site(_) => &[],
+            Self::Tags(_) => &[],
         }
     }
 }
 
+/// Check the links within wikis for ones that point to missing pages,
+/// files, or anchors
+#[derive(Debug, StructOpt)]
+pub struct CheckSubcommand {}
+
 /// Convert vimwiki into something else
 #[derive(Debug, StructOpt)]
 pub struct ConvertSubcommand {
+    /// Format to convert into (html, gemtext, latex, markdown, org, pandoc
+    /// json, text, or ansi)
+    #[structopt(long, default_value = "html")]
+    pub to: ConvertFormat,
+
+    /// Flavor of markdown to produce (gfm or commonmark)
+    #[structopt(long, default_value = "gfm")]
+    pub markdown_flavor: MarkdownFlavor,
+
+    /// If provided, will write the placeholders of each page as front matter
+    /// when converting to markdown
+    #[structopt(long)]
+    pub front_matter: bool,
+
+    /// Number of columns to word-wrap to when converting to text or ansi
+    #[structopt(long, default_value = "80")]
+    pub width: usize,
+
     /// Write output to stdout instead of file system
     #[structopt(long)]
     pub stdout: bool,
@@ -118,6 +162,12 @@ pub struct ConvertSubcommand {
     #[structopt(long)]
     pub include_vimwiki_css: bool,
 
+    /// If provided, will write a json search index of each wiki's pages and
+    /// a page to search it at the root of the wiki's output directory when
+    /// converting to html
+    #[structopt(long)]
+    pub search_index: bool,
+
     /// Additional standalone files (or directories) to process
     #[structopt(name = "PATH", parse(from_os_str))]
     pub extra_paths: Vec<PathBuf>,
@@ -130,15 +180,233 @@ pub struct FormatSubcommand {
     #[structopt(short, long)]
     pub inline: bool,
 
+    /// Check if files are formatted without changing them, printing a diff
+    /// for each file that would change and failing if any would change
+    #[structopt(long, conflicts_with = "inline")]
+    pub check: bool,
+
+    /// Read text to format from stdin instead of files
+    #[structopt(long, conflicts_with = "inline")]
+    pub stdin: bool,
+
+    /// Name of the file whose text is provided via stdin, used when
+    /// reporting the file
+    #[structopt(long, parse(from_os_str), requires = "stdin")]
+    pub stdin_filename: Option<PathBuf>,
+
     /// Extensions to use when searching through directories
     #[structopt(long = "ext", default_value = "wiki")]
     pub extensions: Vec<String>,
 
+    /// Only format the block elements overlapping the lines START:END
+    /// (starting at 1 and inclusive), requiring a single file to process
+    #[structopt(long)]
+    pub range: Option<FormatRange>,
+
+    /// Treat the range as byte offsets START:END (starting at 0 and
+    /// exclusive of END) instead of lines
+    #[structopt(long, requires = "range")]
+    pub bytes: bool,
+
     /// Files (or directories) to process
     #[structopt(name = "PATH", parse(from_os_str))]
     pub paths: Vec<PathBuf>,
 }
 
+/// Generate sections of pages, updating existing sections in place
+#[derive(Debug, StructOpt)]
+pub enum GenerateSubcommand {
+    /// Generate a table of contents from the headers of pages
+    Toc(GenerateTocSubcommand),
+
+    /// Generate a list of links to every page within a wiki
+    Links(GenerateLinksSubcommand),
+
+    /// Generate lists of links to the locations of tags within a wiki
+    TagLinks(GenerateTagLinksSubcommand),
+
+    /// Generate an index of the diary pages of wikis grouped by year and month
+    Diary(GenerateDiarySubcommand),
+}
+
+/// Generate a table of contents for pages
+#[derive(Debug, StructOpt)]
+pub struct GenerateTocSubcommand {
+    /// Print the updated pages to stdout instead of writing them
+    #[structopt(long)]
+    pub stdout: bool,
+
+    /// Files (or directories) whose pages will have a table of contents
+    /// added or updated; if none are provided, only the pages within wikis
+    /// that already contain a table of contents are updated
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub paths: Vec<PathBuf>,
+}
+
+/// Generate a list of links to every page within a wiki
+#[derive(Debug, StructOpt)]
+pub struct GenerateLinksSubcommand {
+    /// Print the updated page to stdout instead of writing it
+    #[structopt(long)]
+    pub stdout: bool,
+
+    /// Text of the header that begins the generated section
+    #[structopt(long, default_value = "Generated Links")]
+    pub header: String,
+
+    /// If provided, will use the first header of each page as the
+    /// description of its link
+    #[structopt(long)]
+    pub captions: bool,
+
+    /// Page within a wiki where the links will be added or updated
+    #[structopt(name = "PAGE", parse(from_os_str))]
+    pub page: PathBuf,
+}
+
+/// Generate lists of links to the locations of tags within a wiki
+#[derive(Debug, StructOpt)]
+pub struct GenerateTagLinksSubcommand {
+    /// Print the updated page to stdout instead of writing it
+    #[structopt(long)]
+    pub stdout: bool,
+
+    /// Text of the header that begins the generated section
+    #[structopt(long, default_value = "Generated Tags")]
+    pub header: String,
+
+    /// Page within a wiki where the links will be added or updated
+    #[structopt(name = "PAGE", parse(from_os_str))]
+    pub page: PathBuf,
+
+    /// Tags to include; if none are provided, then all tags are included
+    #[structopt(name = "TAG")]
+    pub tags: Vec<String>,
+}
+
+/// Generate an index of the diary pages of wikis
+#[derive(Debug, StructOpt)]
+pub struct GenerateDiarySubcommand {
+    /// Print the updated pages to stdout instead of writing them
+    #[structopt(long)]
+    pub stdout: bool,
+
+    /// Text of the header that begins the generated section
+    #[structopt(long, default_value = "Diary")]
+    pub header: String,
+
+    /// Use the first header of each diary page as the description of its
+    /// link
+    #[structopt(long)]
+    pub captions: bool,
+}
+
+/// Check the pages of wikis against lint rules, which can be configured
+/// within the `lint` table of the config file
+#[derive(Debug, StructOpt)]
+pub struct LintSubcommand {
+    /// Apply the fixes offered by rules to each page
+    #[structopt(long)]
+    pub fix: bool,
+
+    /// Output the diagnostics as json, which are those that remain after
+    /// applying fixes when combined with --fix
+    #[structopt(long)]
+    pub json: bool,
+
+    /// List the available rules instead of checking pages
+    #[structopt(long)]
+    pub list_rules: bool,
+}
+
+/// List the pages of wikis alongside their metadata, which comes from the
+/// placeholders within each page such as `%title`, `%date`, or `%author`
+#[derive(Debug, StructOpt)]
+pub struct PagesSubcommand {
+    /// Output the pages as json
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Only include pages whose metadata matches, written as `name`,
+    /// `!name`, `name=value`, or `name!=value`
+    #[structopt(short, long, number_of_values = 1)]
+    pub filter: Vec<MetadataFilter>,
+
+    /// Name of the metadata entry to sort pages by, where pages without the
+    /// entry come last; pages are sorted by path otherwise
+    #[structopt(short, long)]
+    pub sort: Option<String>,
+
+    /// Reverse the order of the pages
+    #[structopt(short, long)]
+    pub reverse: bool,
+}
+
+/// Inspect the links between pages of wikis
+#[derive(Debug, StructOpt)]
+pub enum GraphSubcommand {
+    /// List the links from other pages to a page
+    Backlinks(GraphPageSubcommand),
+
+    /// List the links from a page to other pages
+    Links(GraphPageSubcommand),
+
+    /// List the pages that no other page links to
+    Orphans,
+
+    /// List the pages that do not link to any other page
+    DeadEnds,
+
+    /// Find the shortest chain of links from one page to another
+    Path(GraphPathSubcommand),
+}
+
+/// Select a single page within a wiki
+#[derive(Debug, StructOpt)]
+pub struct GraphPageSubcommand {
+    /// Page within a wiki
+    #[structopt(name = "PAGE", parse(from_os_str))]
+    pub page: PathBuf,
+}
+
+/// Select a pair of pages within wikis
+#[derive(Debug, StructOpt)]
+pub struct GraphPathSubcommand {
+    /// Page where the chain of links starts
+    #[structopt(name = "FROM", parse(from_os_str))]
+    pub from: PathBuf,
+
+    /// Page where the chain of links ends
+    #[structopt(name = "TO", parse(from_os_str))]
+    pub to: PathBuf,
+}
+
+/// Print the JSON Schema describing pages as serialized by other commands,
+/// or migrate a serialized page from an older schema version
+#[derive(Debug, StructOpt)]
+pub struct SchemaSubcommand {
+    /// Path to a serialized page to migrate to the current schema version
+    /// instead of printing the schema
+    #[structopt(long, parse(from_os_str))]
+    pub migrate: Option<PathBuf>,
+}
+
+/// Search the text of pages within wikis, maintaining an index within the
+/// cache directory that is updated as pages change
+///
+/// Queries support "quoted phrases", `tag:NAME` and `path:TEXT` filters, and
+/// `in:FIELD` to only match within a header, paragraph, list, or code
+#[derive(Debug, StructOpt)]
+pub struct SearchSubcommand {
+    /// Output the results as json
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Words, phrases, and filters to search with
+    #[structopt(name = "QUERY", required = true)]
+    pub query: Vec<String>,
+}
+
 /// Convert vimwiki into something else and serve it via http
 #[derive(Debug, StructOpt)]
 pub struct ServeSubcommand {
@@ -156,6 +424,12 @@ pub struct ServeSubcommand {
     pub extra_paths: Vec<PathBuf>,
 }
 
+/// Generate a static site for each wiki, converting every page into html
+/// alongside an index of all pages, a sitemap, and the files that pages
+/// reference, following the `site` settings of each wiki's config
+#[derive(Debug, StructOpt)]
+pub struct SiteSubcommand {}
+
 /// Inspect information that is available
 #[derive(Debug, StructOpt)]
 pub struct InspectSubcommand {
@@ -172,6 +446,117 @@ pub struct InspectSubcommand {
     pub extra_paths: Vec<PathBuf>,
 }
 
+/// Rename a wiki page, updating links across wikis that point to it
+#[derive(Debug, StructOpt)]
+pub struct RenameSubcommand {
+    /// Print the files and links that would change instead of applying them
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Path to the page being renamed
+    #[structopt(name = "OLD", parse(from_os_str))]
+    pub old: PathBuf,
+
+    /// New path for the page
+    #[structopt(name = "NEW", parse(from_os_str))]
+    pub new: PathBuf,
+}
+
+/// Search tags across wikis, listing where each tag appears
+#[derive(Debug, StructOpt)]
+pub struct TagsSubcommand {
+    /// Print the tag index as json instead of text
+    #[structopt(long, conflicts_with = "html")]
+    pub json: bool,
+
+    /// Write a page named tags.html to the html output directory of each
+    /// wiki that links to every location of each tag
+    #[structopt(long)]
+    pub html: bool,
+
+    /// Tags to search for; if none are provided, then all tags are listed
+    /// without their locations unless writing json or html
+    #[structopt(name = "TAG")]
+    pub tags: Vec<String>,
+}
+
+/// Represents the format to convert vimwiki into
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConvertFormat {
+    Html,
+    Gemtext,
+    Latex,
+    Markdown,
+    Org,
+    Pandoc,
+    Text,
+    Ansi,
+}
+
+impl ConvertFormat {
+    /// Returns the extension given to files written in this format
+    pub fn ext(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Gemtext => "gmi",
+            Self::Latex => "tex",
+            Self::Markdown => "md",
+            Self::Org => "org",
+            Self::Pandoc => "json",
+            Self::Text | Self::Ansi => "txt",
+        }
+    }
+}
+
+impl std::str::FromStr for ConvertFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "html" => Ok(Self::Html),
+            "gemtext" | "gemini" | "gmi" => Ok(Self::Gemtext),
+            "latex" | "tex" => Ok(Self::Latex),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "org" => Ok(Self::Org),
+            "pandoc" => Ok(Self::Pandoc),
+            "text" | "txt" => Ok(Self::Text),
+            "ansi" => Ok(Self::Ansi),
+            _ => Err(format!("Unknown format: {}", s)),
+        }
+    }
+}
+
+/// Represents a range of a file to format in the form START:END
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FormatRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl std::str::FromStr for FormatRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = match s.split_once(':') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (s.trim(), s.trim()),
+        };
+
+        let start = start
+            .parse::<usize>()
+            .map_err(|x| format!("Invalid range start {:?}: {}", start, x))?;
+        let end = end
+            .parse::<usize>()
+            .map_err(|x| format!("Invalid range end {:?}: {}", end, x))?;
+
+        if start > end {
+            return Err(format!("Range start {} is after end {}", start, end));
+        }
+
+        Ok(Self { start, end })
+    }
+}
+
 /// Represents either a wiki index or a wiki name
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum IndexOrName {
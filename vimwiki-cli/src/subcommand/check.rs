@@ -0,0 +1,33 @@
+use crate::{Ast, CheckSubcommand, CommonOpt};
+use std::io;
+use vimwiki::{check_links, HtmlConfig};
+
+pub fn check(
+    _cmd: CheckSubcommand,
+    _opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let wikis = ast.to_wikis(&config);
+    let broken = check_links(&wikis);
+
+    for link in broken.iter() {
+        println!(
+            "{}@{}: {} link {}: {}",
+            link.path.to_string_lossy(),
+            link.region.offset(),
+            link.kind,
+            link.uri,
+            link.reason
+        );
+    }
+
+    if broken.is_empty() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Found {} broken link(s)", broken.len()),
+        ))
+    }
+}
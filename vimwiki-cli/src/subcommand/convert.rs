@@ -1,6 +1,11 @@
-use crate::{css, Ast, CommonOpt, ConvertSubcommand};
+use crate::{css, Ast, CommonOpt, ConvertFormat, ConvertSubcommand};
 use log::*;
-use std::{ffi::OsStr, io, path::Path};
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+};
 use vimwiki::*;
 use walkdir::WalkDir;
 
@@ -10,6 +15,14 @@ pub fn convert(
     config: HtmlConfig,
     mut ast: Ast,
 ) -> io::Result<()> {
+    // Html templates can list the pages of a wiki and the backlinks of a
+    // page, so we gather those from the loaded wikis up front
+    let wiki_pages = if cmd.to == ConvertFormat::Html {
+        WikiPages::load(&config, &ast)
+    } else {
+        WikiPages::default()
+    };
+
     // Process all wikis that match the given filters if we aren't given
     // specific files/wikis to convert
     if cmd.extra_paths.is_empty() {
@@ -19,28 +32,56 @@ pub fn convert(
             })
         {
             process_path(
+                &cmd,
                 config.clone(),
                 &mut ast,
+                &wiki_pages,
                 wiki.path.as_path(),
-                opt.cache.as_path(),
-                opt.no_cache,
-                cmd.stdout,
+                &opt,
                 &wiki.ext,
             )?;
 
             // If writing to a file, we want to make sure there is a css
             // file generated if necessary
-            if !cmd.stdout && cmd.include_vimwiki_css {
+            if !cmd.stdout
+                && cmd.include_vimwiki_css
+                && cmd.to == ConvertFormat::Html
+            {
                 let css_path =
                     wiki.path_html.join(HtmlWikiConfig::default_css_name());
                 debug!("Writing css to {:?}", css_path);
                 std::fs::write(css_path, css::DEFAULT_STYLE_FILE)?;
             }
         }
+
+        // Search indexes cover entire wikis, so they are only written when
+        // converting wikis rather than standalone files
+        if !cmd.stdout && cmd.search_index && cmd.to == ConvertFormat::Html {
+            for wiki in ast.to_wikis(&config).iter().filter(|wiki| {
+                opt.filter_by_wiki_idx_and_name(
+                    wiki.index,
+                    wiki.name.as_deref(),
+                )
+            }) {
+                match config.find_wiki_by_index(wiki.index) {
+                    Some(wiki_config) => super::site::write_search(
+                        &config,
+                        wiki_config,
+                        wiki,
+                        wiki_pages
+                            .pages
+                            .get(&wiki.index)
+                            .cloned()
+                            .unwrap_or_default(),
+                    )?,
+                    None => warn!("Wiki {} is missing a config", wiki.index),
+                }
+            }
+        }
     }
 
     // Additionally, we process any directories & files provided adhoc
-    for path in cmd.extra_paths {
+    for path in cmd.extra_paths.iter() {
         // Need to make sure the path is legit
         let path = match path.canonicalize() {
             Ok(path) => path,
@@ -51,18 +92,21 @@ pub fn convert(
         };
 
         process_path(
+            &cmd,
             config.clone(),
             &mut ast,
+            &wiki_pages,
             path.as_path(),
-            opt.cache.as_path(),
-            opt.no_cache,
-            cmd.stdout,
+            &opt,
             &HtmlWikiConfig::default_ext(),
         )?;
 
         // If writing to a file, we want to make sure there is a css
         // file generated if necessary
-        if !cmd.stdout && cmd.include_vimwiki_css {
+        if !cmd.stdout
+            && cmd.include_vimwiki_css
+            && cmd.to == ConvertFormat::Html
+        {
             let wiki = config.runtime.to_tmp_wiki();
             let css_path = wiki.path_html.join("style.css");
             debug!("Writing css to {:?}", css_path);
@@ -73,19 +117,71 @@ pub fn convert(
     Ok(())
 }
 
+/// Pages of each wiki, arranged as a tree and as a diary, alongside the
+/// pages linking to each page
+#[derive(Default)]
+struct WikiPages {
+    pages: HashMap<usize, Vec<HtmlPageInfo>>,
+    trees: HashMap<usize, HtmlPageTree>,
+    diaries: HashMap<usize, Vec<HtmlPageInfo>>,
+    backlinks: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl WikiPages {
+    fn load(config: &HtmlConfig, ast: &Ast) -> Self {
+        let wikis = ast.to_wikis(config);
+        let graph = WikiGraph::from_wikis(&wikis);
+        let mut wiki_pages = Self::default();
+
+        for wiki in wikis.iter() {
+            let mut pages = Vec::new();
+            for page in wiki.pages.iter() {
+                pages.push(HtmlPageInfo {
+                    path: page.path.to_path_buf(),
+                    metadata: PageMetadata::from_page(&page.page),
+                });
+
+                let mut backlinks = graph
+                    .backlinks(&page.path)
+                    .into_iter()
+                    .map(|edge| edge.src.to_path_buf())
+                    .collect::<Vec<PathBuf>>();
+                backlinks.dedup();
+                wiki_pages
+                    .backlinks
+                    .insert(page.path.to_path_buf(), backlinks);
+            }
+
+            pages.sort_by(|a, b| a.path.cmp(&b.path));
+            if let Some(wiki_config) = config.find_wiki_by_index(wiki.index) {
+                wiki_pages.trees.insert(
+                    wiki.index,
+                    HtmlPageTree::from_pages(wiki_config, &pages),
+                );
+                wiki_pages
+                    .diaries
+                    .insert(wiki.index, find_diary_pages(wiki_config, &pages));
+            }
+            wiki_pages.pages.insert(wiki.index, pages);
+        }
+
+        wiki_pages
+    }
+}
+
 fn process_path(
+    cmd: &ConvertSubcommand,
     config: HtmlConfig,
     ast: &mut Ast,
+    wiki_pages: &WikiPages,
     input_path: &Path,
-    cache: &Path,
-    no_cache: bool,
-    stdout: bool,
+    opt: &CommonOpt,
     ext: &str,
 ) -> io::Result<()> {
     trace!(
         "process_path(_, input_path = {:?}, stdout = {}, ext = {})",
         input_path,
-        stdout,
+        cmd.stdout,
         ext
     );
 
@@ -109,16 +205,33 @@ fn process_path(
         config.map_runtime(|mut rt| {
             rt.page = page_path.to_path_buf();
             rt.wiki_index = wiki_index;
+            rt.pages = wiki_index
+                .and_then(|idx| wiki_pages.pages.get(&idx))
+                .cloned()
+                .unwrap_or_default();
+            rt.tree = wiki_index
+                .and_then(|idx| wiki_pages.trees.get(&idx))
+                .cloned()
+                .unwrap_or_default();
+            rt.diary = wiki_index
+                .and_then(|idx| wiki_pages.diaries.get(&idx))
+                .cloned()
+                .unwrap_or_default();
+            rt.backlinks = wiki_pages
+                .backlinks
+                .get(&page_path)
+                .cloned()
+                .unwrap_or_default();
             rt
         });
 
         process_file(
+            cmd,
             config,
             ast,
             page_path.as_path(),
-            cache,
-            no_cache,
-            stdout,
+            opt.cache.as_path(),
+            opt.no_cache,
         )?;
     }
 
@@ -126,61 +239,202 @@ fn process_path(
 }
 
 fn process_file(
+    cmd: &ConvertSubcommand,
     config: HtmlConfig,
     ast: &mut Ast,
     input_path: &Path,
     cache: &Path,
     no_cache: bool,
-    stdout: bool,
 ) -> io::Result<()> {
     trace!(
-        "process_file(_, input_path = {:?}, stdout = {})",
+        "process_file(_, input_path = {:?}, stdout = {}, to = {:?})",
         input_path,
-        stdout
+        cmd.stdout,
+        cmd.to
     );
 
-    // Go ahead and figure out the necessary wiki if we need it so that we
-    // don't need to clone our entire config later
-    let maybe_wiki = if !stdout {
-        config.find_wiki_by_path(input_path).cloned()
-    } else {
-        None
-    };
+    // Go ahead and figure out the necessary wiki so that we don't need to
+    // clone our entire config later
+    let maybe_wiki = config.find_wiki_by_path(input_path).cloned();
 
-    // If we already have a file loaded at this path, use it
-    let html = if let Some(file) = ast.find_file_by_path(input_path) {
+    // If we already have a file loaded at this path, use it, otherwise we
+    // need to load the file
+    let page = if let Some(file) = ast.find_file_by_path(input_path) {
         debug!("{:?} :: loaded from cache!", input_path);
-
-        file.data.to_html_page(config).map_err(|x| {
-            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
-        })?
-
-    // Otherwise, we need to load the file
+        &file.data
     } else {
-        let file = ast.load_file(input_path, cache, no_cache)?;
-
-        file.data.to_html_page(config).map_err(|x| {
-            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
-        })?
+        &ast.load_file(input_path, cache, no_cache)?.data
     };
-    debug!("{:?} :: html generated!", input_path);
+
+    let output = convert_page(cmd, config, page, input_path)?;
+    debug!("{:?} :: {} generated!", input_path, cmd.to.ext());
 
     // If told to print to stdout, do so
-    if stdout {
-        println!("{}", html);
+    if cmd.stdout {
+        println!("{}", output);
 
     // Otherwise, we generate files based on resolved output paths
     } else {
         let path = maybe_wiki
             .unwrap_or_default()
-            .make_output_path(input_path, "html");
+            .make_output_path(input_path, cmd.to.ext());
 
         info!("Writing to {:?}", path);
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        std::fs::write(path, html)?;
+        std::fs::write(path, output)?;
     }
 
     Ok(())
 }
+
+/// Converts a page into the format requested by the command, resolving
+/// links relative to the wiki containing the page
+fn convert_page(
+    cmd: &ConvertSubcommand,
+    config: HtmlConfig,
+    page: &Page,
+    input_path: &Path,
+) -> io::Result<String> {
+    let root_rel_path =
+        make_root_rel_path(&config.to_current_wiki(), input_path);
+    let diary_rel_path = config
+        .find_wiki_by_path(input_path)
+        .map(|wiki| wiki.diary_rel_path.to_path_buf())
+        .unwrap_or_else(HtmlWikiConfig::default_diary_rel_path)
+        .to_string_lossy()
+        .to_string();
+
+    let output = match cmd.to {
+        ConvertFormat::Html => page.to_html_page(config).map_err(|x| {
+            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+        })?,
+        ConvertFormat::Gemtext => page
+            .to_gemtext_string(GemtextConfig {
+                root_rel_path,
+                diary_rel_path,
+                ..Default::default()
+            })
+            .map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?,
+        ConvertFormat::Latex => page
+            .to_latex_string(LatexConfig {
+                root_rel_path,
+                diary_rel_path,
+                ..Default::default()
+            })
+            .map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?,
+        ConvertFormat::Markdown => page
+            .to_markdown_string(MarkdownConfig {
+                flavor: cmd.markdown_flavor,
+                front_matter: cmd.front_matter,
+                root_rel_path,
+                diary_rel_path,
+                ..Default::default()
+            })
+            .map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?,
+        ConvertFormat::Org => page
+            .to_org_string(OrgConfig {
+                root_rel_path,
+                diary_rel_path,
+                ..Default::default()
+            })
+            .map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?,
+        ConvertFormat::Pandoc => {
+            let pandoc = page
+                .to_pandoc(PandocConfig {
+                    root_rel_path,
+                    diary_rel_path,
+                    ..Default::default()
+                })
+                .map_err(|x| {
+                    io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+                })?;
+            serde_json::to_string(&pandoc)?
+        }
+        ConvertFormat::Text | ConvertFormat::Ansi => page
+            .to_text_string(TextConfig {
+                width: cmd.width,
+                ansi: cmd.to == ConvertFormat::Ansi,
+                ..Default::default()
+            })
+            .map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?,
+    };
+
+    Ok(output)
+}
+
+/// Produces the path from a page to the root of its wiki such as `../../`,
+/// which is empty for pages at the root or outside of the wiki
+fn make_root_rel_path(wiki: &HtmlWikiConfig, page: &Path) -> String {
+    page.strip_prefix(wiki.path.as_path())
+        .ok()
+        .and_then(Path::parent)
+        .map(|dir| "../".repeat(dir.components().count()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_page_should_use_diary_path_of_wiki() {
+        let config = HtmlConfig {
+            wikis: vec![HtmlWikiConfig {
+                path: PathBuf::from("/wiki"),
+                diary_rel_path: PathBuf::from("journal"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let page: Page = Language::from_vimwiki_str("[[diary:2021-03-05]]")
+            .parse()
+            .unwrap();
+
+        for to in [
+            ConvertFormat::Gemtext,
+            ConvertFormat::Latex,
+            ConvertFormat::Markdown,
+            ConvertFormat::Org,
+            ConvertFormat::Pandoc,
+        ]
+        .iter()
+        .copied()
+        {
+            let cmd = ConvertSubcommand {
+                to,
+                markdown_flavor: MarkdownFlavor::default(),
+                front_matter: false,
+                width: 80,
+                stdout: true,
+                include_vimwiki_css: false,
+                search_index: false,
+                extra_paths: Vec::new(),
+            };
+            let output = convert_page(
+                &cmd,
+                config.clone(),
+                &page,
+                Path::new("/wiki/page.wiki"),
+            )
+            .unwrap();
+            assert!(
+                output.contains("journal/2021-03-05"),
+                "{:?} output missing diary path: {}",
+                cmd.to,
+                output
+            );
+        }
+    }
+}
@@ -1,15 +1,83 @@
-use crate::{CommonOpt, FormatSubcommand};
+use crate::{CommonOpt, FormatRange, FormatSubcommand};
 use log::*;
-use std::{collections::HashSet, ffi::OsStr, io, path::Path};
+use similar::TextDiff;
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    io::{self, Read},
+    ops::Range,
+    path::{Path, PathBuf},
+};
 use vimwiki::*;
 use walkdir::WalkDir;
 
+/// Represents what to do with the formatted text of a file
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum FormatMode {
+    /// Print the formatted text to stdout
+    Print,
+
+    /// Overwrite the file with the formatted text
+    Inline,
+
+    /// Print a diff if the formatted text differs from the file
+    Check,
+}
+
 pub fn format(
     cmd: FormatSubcommand,
     _opt: CommonOpt,
     config: VimwikiConfig,
 ) -> io::Result<()> {
     let extensions: HashSet<String> = cmd.extensions.into_iter().collect();
+    let mode = if cmd.check {
+        FormatMode::Check
+    } else if cmd.inline {
+        FormatMode::Inline
+    } else {
+        FormatMode::Print
+    };
+
+    if cmd.stdin && !cmd.paths.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Cannot format paths when reading from stdin",
+        ));
+    }
+
+    // Formatting a range only makes sense for a single file
+    if cmd.range.is_some()
+        && !(cmd.stdin || (cmd.paths.len() == 1 && cmd.paths[0].is_file()))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Formatting a range requires exactly one file",
+        ));
+    }
+    let bytes = cmd.bytes;
+    let range = cmd.range.map(|range| (range, bytes));
+
+    // Keep track of how many files would be changed when checking
+    let mut changed = 0;
+
+    if cmd.stdin {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+
+        let name = cmd
+            .stdin_filename
+            .unwrap_or_else(|| PathBuf::from("<stdin>"));
+        let formatted =
+            format_text(config.clone(), name.as_path(), &text, range)?;
+
+        if mode == FormatMode::Check {
+            if print_diff(name.as_path(), &text, &formatted) {
+                changed += 1;
+            }
+        } else {
+            print!("{}", formatted);
+        }
+    }
 
     for path in cmd.paths {
         // Need to make sure the path is legit
@@ -24,7 +92,9 @@ pub fn format(
         // If path is to a file, we want to process it directly regardless of
         // the extension
         if path.is_file() {
-            process_file(config.clone(), path.as_path(), cmd.inline)?;
+            if process_file(config.clone(), path.as_path(), mode, range)? {
+                changed += 1;
+            }
 
         // Otherwise, we walk the directory
         } else {
@@ -37,7 +107,14 @@ pub fn format(
                     entry.path().extension().and_then(OsStr::to_str)
                 {
                     if extensions.contains(ext) {
-                        process_file(config.clone(), entry.path(), cmd.inline)?;
+                        if process_file(
+                            config.clone(),
+                            entry.path(),
+                            mode,
+                            None,
+                        )? {
+                            changed += 1;
+                        }
                     } else {
                         warn!(
                             "{:?} :: skipped due to unrecognized extension ({})!",
@@ -55,18 +132,29 @@ pub fn format(
         }
     }
 
+    if changed > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} file(s) would be reformatted", changed),
+        ));
+    }
+
     Ok(())
 }
 
+/// Formats the file at the given path, returning true if checking and the
+/// file would be changed by formatting
 fn process_file(
     config: VimwikiConfig,
     input_path: &Path,
-    inplace: bool,
-) -> io::Result<()> {
+    mode: FormatMode,
+    range: Option<(FormatRange, bool)>,
+) -> io::Result<bool> {
     trace!(
-        "process_file(_, input_path = {:?}, inplace = {})",
+        "process_file(_, input_path = {:?}, mode = {:?}, range = {:?})",
         input_path,
-        inplace
+        mode,
+        range
     );
 
     // Load the file's text
@@ -74,32 +162,151 @@ fn process_file(
 
     debug!("{:?} :: file loaded!", input_path);
 
+    let formatted = format_text(config, input_path, &text, range)?;
+
+    match mode {
+        // If indicated, we replace the file's contents inline
+        FormatMode::Inline => {
+            info!("Writing to {:?}", input_path);
+            std::fs::write(input_path, formatted)?;
+        }
+
+        // If checking, we only report the differences
+        FormatMode::Check => {
+            return Ok(print_diff(input_path, &text, &formatted));
+        }
+
+        // Otherwise, print to stdout
+        FormatMode::Print => println!("{}", formatted),
+    }
+
+    Ok(false)
+}
+
+/// Formats the text of a file, either in full or only the elements within
+/// the range
+fn format_text(
+    config: VimwikiConfig,
+    path: &Path,
+    text: &str,
+    range: Option<(FormatRange, bool)>,
+) -> io::Result<String> {
     // Convert file to a vimwiki page ast
     let page =
-        Language::from_vimwiki_str(&text)
+        Language::from_vimwiki_str(text)
             .parse::<Page>()
             .map_err(|x| {
                 io::Error::new(io::ErrorKind::InvalidData, x.to_string())
             })?;
 
-    debug!("{:?} :: page parsed!", input_path);
+    debug!("{:?} :: page parsed!", path);
+
+    // Convert page back to vimwiki text, either in full or only the
+    // elements within the range
+    let text = match range {
+        Some((range, bytes)) => {
+            let range = to_byte_range(text, range, bytes);
+            let edit =
+                format_range(text, &page, range, config).map_err(|x| {
+                    io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+                })?;
+
+            FileEdits {
+                path: path.to_path_buf(),
+                edits: edit.into_iter().collect(),
+            }
+            .apply(text)
+        }
+        None => page.to_vimwiki_string(config).map_err(|x| {
+            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+        })?,
+    };
 
-    // Convert page back to vimwiki text
-    let text = page.to_vimwiki_string(config).map_err(|x| {
-        io::Error::new(io::ErrorKind::InvalidData, x.to_string())
-    })?;
+    debug!("{:?} :: vimwiki generated!", path);
 
-    debug!("{:?} :: vimwiki generated!", input_path);
+    Ok(text)
+}
 
-    // If indicated, we replace the file's contents inline
-    if inplace {
-        info!("Writing to {:?}", input_path);
-        std::fs::write(input_path, text)?;
+/// Prints a unified diff between the original and formatted text of a file,
+/// returning true if there were any differences
+fn print_diff(path: &Path, original: &str, formatted: &str) -> bool {
+    if original == formatted {
+        return false;
+    }
 
-    // Otherwise, print to stdout
+    // Show paths relative to the current directory where possible, using
+    // the a/ and b/ prefixes of git so the diff can be applied as a patch
+    let path = std::env::current_dir()
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path);
+    let (old_name, new_name) = if path.is_relative() {
+        (
+            format!("a/{}", path.to_string_lossy()),
+            format!("b/{}", path.to_string_lossy()),
+        )
     } else {
-        println!("{}", text);
+        (
+            path.to_string_lossy().to_string(),
+            path.to_string_lossy().to_string(),
+        )
+    };
+
+    print!(
+        "{}",
+        TextDiff::from_lines(original, formatted)
+            .unified_diff()
+            .header(&old_name, &new_name)
+    );
+
+    true
+}
+
+/// Converts a range provided by the user into a range of bytes within text,
+/// where lines start at 1 and are inclusive
+fn to_byte_range(text: &str, range: FormatRange, bytes: bool) -> Range<usize> {
+    if bytes {
+        let clamp = |offset: usize| {
+            let mut offset = offset.min(text.len());
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+        clamp(range.start)..clamp(range.end)
+    } else {
+        line_range_to_bytes(text, range.start.saturating_sub(1)..range.end)
     }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_byte_range_should_treat_lines_as_one_based_and_inclusive() {
+        let text = "one\ntwo\nthree\n";
+        let range = |start, end| FormatRange { start, end };
+        assert_eq!(to_byte_range(text, range(1, 1), false), 0..4);
+        assert_eq!(to_byte_range(text, range(2, 3), false), 4..14);
+        assert_eq!(to_byte_range(text, range(0, 1), false), 0..4);
+        assert_eq!(to_byte_range(text, range(3, 10), false), 8..14);
+    }
+
+    #[test]
+    fn to_byte_range_should_clamp_byte_offsets_to_char_boundaries() {
+        // The 'é' spans bytes 1..3
+        let text = "aéb\n";
+        let range = |start, end| FormatRange { start, end };
+        assert_eq!(to_byte_range(text, range(0, 3), true), 0..3);
+        assert_eq!(to_byte_range(text, range(2, 4), true), 1..4);
+        assert_eq!(to_byte_range(text, range(1, 100), true), 1..5);
+    }
+
+    #[test]
+    fn print_diff_should_return_true_only_if_text_changed() {
+        let path = Path::new("page.wiki");
+        assert!(!print_diff(path, "= Header =\n", "= Header =\n"));
+        assert!(print_diff(path, "=Header=\n", "= Header =\n"));
+    }
 }
@@ -0,0 +1,253 @@
+use crate::{
+    Ast, CommonOpt, GenerateDiarySubcommand, GenerateLinksSubcommand,
+    GenerateSubcommand, GenerateTagLinksSubcommand, GenerateTocSubcommand,
+};
+use log::*;
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+};
+use vimwiki::*;
+use walkdir::WalkDir;
+
+pub fn generate(
+    cmd: GenerateSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    match cmd {
+        GenerateSubcommand::Toc(cmd) => toc(cmd, config, ast),
+        GenerateSubcommand::Links(cmd) => links(cmd, config, ast),
+        GenerateSubcommand::TagLinks(cmd) => tag_links(cmd, config, ast),
+        GenerateSubcommand::Diary(cmd) => diary(cmd, opt, config, ast),
+    }
+}
+
+fn toc(
+    cmd: GenerateTocSubcommand,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let name = config.header.table_of_contents.as_str();
+
+    // Without explicit paths, we only refresh pages that already have a
+    // table of contents rather than adding one to every page in a wiki
+    let (paths, force) = if cmd.paths.is_empty() {
+        let paths = ast
+            .wikis
+            .iter()
+            .flat_map(|wiki| wiki.files.iter().map(|f| f.path.to_path_buf()))
+            .collect();
+        (paths, false)
+    } else {
+        (collect_paths(&config, cmd.paths)?, true)
+    };
+
+    for path in paths {
+        update_section(
+            path.as_path(),
+            |page| make_toc(page, name),
+            SectionPlacement::Start,
+            force,
+            cmd.stdout,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn links(
+    cmd: GenerateLinksSubcommand,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let path = resolve_page_path(cmd.page.as_path())?;
+    let wikis = ast.to_wikis(&config);
+    let wiki = find_wiki_by_path(&wikis, &path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} is not within a wiki", path),
+        )
+    })?;
+
+    update_section(
+        path.as_path(),
+        |_| make_links_index(wiki, &path, &cmd.header, cmd.captions),
+        SectionPlacement::End,
+        true,
+        cmd.stdout,
+    )
+}
+
+fn tag_links(
+    cmd: GenerateTagLinksSubcommand,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let path = resolve_page_path(cmd.page.as_path())?;
+    let wikis = ast.to_wikis(&config);
+    let wiki = find_wiki_by_path(&wikis, &path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} is not within a wiki", path),
+        )
+    })?;
+
+    let mut index = TagIndex::from_wikis(std::slice::from_ref(wiki));
+    if !cmd.tags.is_empty() {
+        index.retain(|tag| cmd.tags.iter().any(|t| t == tag.as_str()));
+    }
+
+    update_section(
+        path.as_path(),
+        |_| make_tag_links(wiki, &index, &path, &cmd.header),
+        SectionPlacement::End,
+        true,
+        cmd.stdout,
+    )
+}
+
+fn diary(
+    cmd: GenerateDiarySubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    for wiki in ast.to_wikis(&config).iter() {
+        if !opt.filter_by_wiki_idx_and_name(wiki.index, wiki.name.as_deref()) {
+            continue;
+        }
+
+        let path = wiki.make_diary_index_path();
+        if let (Some(parent), false) = (path.parent(), cmd.stdout) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        update_section(
+            path.as_path(),
+            |_| make_diary_index(wiki, &cmd.header, cmd.captions),
+            SectionPlacement::End,
+            true,
+            cmd.stdout,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Resolves the path of a page that may not exist yet into an absolute path
+fn resolve_page_path(path: &Path) -> io::Result<PathBuf> {
+    if path.exists() {
+        path.canonicalize()
+    } else if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
+
+/// Resolves each path into the files it represents, walking directories for
+/// files with the extension of the wiki that contains them
+fn collect_paths(
+    config: &HtmlConfig,
+    paths: Vec<PathBuf>,
+) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        // Need to make sure the path is legit
+        let path = match path.canonicalize() {
+            Ok(path) => path,
+            Err(x) => {
+                error!("{:?} failed to canonicalize: {}", path, x);
+                return Err(x);
+            }
+        };
+
+        // If path is to a file, we want to process it directly regardless of
+        // the extension
+        if path.is_file() {
+            files.push(path);
+            continue;
+        }
+
+        let ext = config
+            .find_wiki_by_path(path.as_path())
+            .map(|wiki| wiki.ext.to_string())
+            .unwrap_or_else(HtmlWikiConfig::default_ext);
+        files.extend(
+            WalkDir::new(path)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.file_type().is_file()
+                        && e.path().extension().and_then(OsStr::to_str)
+                            == Some(ext.as_str())
+                })
+                .map(|e| e.path().to_path_buf()),
+        );
+    }
+
+    Ok(files)
+}
+
+/// Updates the generated section of the page at the given path, adding the
+/// section if it is missing only when forced and treating a page that does
+/// not exist yet as empty
+fn update_section<F>(
+    path: &Path,
+    make_fragment: F,
+    placement: SectionPlacement,
+    force: bool,
+    stdout: bool,
+) -> io::Result<()>
+where
+    F: FnOnce(&Page) -> Page<'static>,
+{
+    let text = if path.exists() {
+        std::fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    let page =
+        Language::from_vimwiki_str(&text)
+            .parse::<Page>()
+            .map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?;
+
+    let fragment = make_fragment(&page);
+    if !force && find_section(&text, &page, &fragment).is_none() {
+        debug!("{:?} :: skipped as section is missing!", path);
+        return Ok(());
+    }
+
+    let edit = make_section_edit(
+        &text,
+        &page,
+        &fragment,
+        placement,
+        VimwikiConfig::default(),
+    )
+    .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x.to_string()))?;
+
+    let new_text = match edit {
+        Some(edit) => FileEdits {
+            path: path.to_path_buf(),
+            edits: vec![edit],
+        }
+        .apply(&text),
+        None => return Ok(()),
+    };
+
+    if stdout {
+        println!("{}", new_text);
+    } else if new_text != text {
+        info!("Writing to {:?}", path);
+        std::fs::write(path, new_text)?;
+    }
+
+    Ok(())
+}
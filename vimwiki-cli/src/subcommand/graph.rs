@@ -0,0 +1,78 @@
+use crate::{Ast, CommonOpt, GraphSubcommand};
+use log::*;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use vimwiki::{HtmlConfig, WikiGraph};
+
+pub fn graph(
+    cmd: GraphSubcommand,
+    _opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let graph = WikiGraph::from_wikis(&ast.to_wikis(&config));
+
+    match cmd {
+        GraphSubcommand::Backlinks(cmd) => {
+            let path = canonicalize(&cmd.page)?;
+            for edge in graph.backlinks(&path) {
+                println!(
+                    "{}@{}",
+                    edge.src.to_string_lossy(),
+                    edge.region.offset()
+                );
+            }
+        }
+        GraphSubcommand::Links(cmd) => {
+            let path = canonicalize(&cmd.page)?;
+            for edge in graph.outgoing(&path) {
+                println!(
+                    "{}{}",
+                    edge.dst.to_string_lossy(),
+                    edge.anchor
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default()
+                );
+            }
+        }
+        GraphSubcommand::Orphans => {
+            for path in graph.orphans() {
+                println!("{}", path.to_string_lossy());
+            }
+        }
+        GraphSubcommand::DeadEnds => {
+            for path in graph.dead_ends() {
+                println!("{}", path.to_string_lossy());
+            }
+        }
+        GraphSubcommand::Path(cmd) => {
+            let from = canonicalize(&cmd.from)?;
+            let to = canonicalize(&cmd.to)?;
+            match graph.shortest_path(&from, &to) {
+                Some(pages) => {
+                    for path in pages {
+                        println!("{}", path.to_string_lossy());
+                    }
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("No links lead from {:?} to {:?}", from, to),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+    path.canonicalize().map_err(|x| {
+        error!("{:?} failed to canonicalize: {}", path, x);
+        x
+    })
+}
@@ -0,0 +1,103 @@
+use crate::{Ast, CommonOpt, LintSubcommand};
+use log::*;
+use std::{collections::BTreeMap, io, path::PathBuf};
+use vimwiki::{
+    Diagnostic, FileEdits, HtmlConfig, LintConfig, LintRegistry, Severity,
+    TextEdit,
+};
+
+pub fn lint(
+    cmd: LintSubcommand,
+    _opt: CommonOpt,
+    config: HtmlConfig,
+    lint_config: LintConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let registry = LintRegistry::default();
+
+    if cmd.list_rules {
+        for rule in registry.rules() {
+            let status = if lint_config.is_enabled(rule.name()) {
+                ""
+            } else {
+                " (disabled)"
+            };
+            println!(
+                "{} [{}]{}: {}",
+                rule.name(),
+                rule.default_severity(),
+                status,
+                rule.description()
+            );
+        }
+        return Ok(());
+    }
+
+    let wikis = ast.to_wikis(&config);
+    let mut diagnostics = registry.lint(&wikis, &lint_config);
+
+    if cmd.fix {
+        diagnostics = apply_fixes(diagnostics)?;
+    }
+
+    if cmd.json {
+        let stdout = io::stdout();
+        serde_json::to_writer_pretty(stdout, &diagnostics)
+            .map_err(io::Error::from)?;
+    } else {
+        for d in diagnostics.iter() {
+            println!(
+                "{}@{}: {} [{}] {}",
+                d.path.to_string_lossy(),
+                d.region.offset(),
+                d.severity,
+                d.rule,
+                d.message
+            );
+        }
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Found {} error(s)", errors),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Applies the fixes of the diagnostics to each page, skipping any fix that
+/// overlaps with a fix applied before it, and returns the diagnostics that
+/// were not fixed
+fn apply_fixes(diagnostics: Vec<Diagnostic>) -> io::Result<Vec<Diagnostic>> {
+    let mut fixes: BTreeMap<PathBuf, Vec<TextEdit>> = BTreeMap::new();
+    let mut remaining = Vec::new();
+    for d in diagnostics {
+        let edits = fixes.entry(d.path.to_path_buf()).or_default();
+        match d.fix.as_ref() {
+            Some(fix) if !edits.iter().any(|e| is_overlapping(e, fix)) => {
+                edits.push(fix.clone())
+            }
+            _ => remaining.push(d),
+        }
+    }
+
+    for (path, edits) in fixes.into_iter().filter(|(_, e)| !e.is_empty()) {
+        let text = std::fs::read_to_string(path.as_path())?;
+        info!("Applying {} fix(es) to {:?}", edits.len(), path);
+        let edits = FileEdits { path, edits };
+        std::fs::write(edits.path.as_path(), edits.apply(&text))?;
+    }
+
+    Ok(remaining)
+}
+
+fn is_overlapping(a: &TextEdit, b: &TextEdit) -> bool {
+    a.region.offset() < b.region.offset() + b.region.len()
+        && b.region.offset() < a.region.offset() + a.region.len()
+}
@@ -1,9 +1,29 @@
+mod check;
 mod convert;
 mod format;
+mod generate;
+mod graph;
 mod inspect;
+mod lint;
+mod pages;
+mod rename;
+mod schema;
+mod search;
 mod serve;
+mod site;
+mod tags;
 
+pub use check::check;
 pub use convert::convert;
 pub use format::format;
+pub use generate::generate;
+pub use graph::graph;
 pub use inspect::inspect;
+pub use lint::lint;
+pub use pages::pages;
+pub use rename::rename;
+pub use schema::schema;
+pub use search::search;
 pub use serve::serve;
+pub use site::site;
+pub use tags::tags;
@@ -0,0 +1,58 @@
+use crate::{Ast, CommonOpt, PagesSubcommand};
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+use vimwiki::PageMetadata;
+
+/// Represents a page alongside its metadata when printed as json
+#[derive(Serialize)]
+struct PageEntry<'a> {
+    path: &'a Path,
+    metadata: PageMetadata,
+}
+
+pub fn pages(
+    cmd: PagesSubcommand,
+    _opt: CommonOpt,
+    ast: Ast,
+) -> io::Result<()> {
+    let mut entries: Vec<PageEntry> = ast
+        .wikis
+        .iter()
+        .flat_map(|w| w.files.iter())
+        .map(|file| PageEntry {
+            path: file.path.as_path(),
+            metadata: PageMetadata::from_page(&file.data),
+        })
+        .filter(|entry| cmd.filter.iter().all(|f| f.matches(&entry.metadata)))
+        .collect();
+
+    entries.sort_by(|a, b| match cmd.sort.as_deref() {
+        Some(name) => a
+            .metadata
+            .cmp_by(&b.metadata, name)
+            .then_with(|| a.path.cmp(b.path)),
+        None => a.path.cmp(b.path),
+    });
+    if cmd.reverse {
+        entries.reverse();
+    }
+
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    if cmd.json {
+        serde_json::to_writer_pretty(&mut handle, &entries)?;
+        writeln!(handle)?;
+    } else {
+        for entry in entries.iter() {
+            writeln!(handle, "{}", entry.path.to_string_lossy())?;
+            for (name, value) in entry.metadata.iter() {
+                writeln!(handle, "  {} = {}", name, value)?;
+            }
+        }
+    }
+
+    Ok(())
+}
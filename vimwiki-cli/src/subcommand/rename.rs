@@ -0,0 +1,91 @@
+use crate::{Ast, CommonOpt, RenameSubcommand};
+use log::*;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use vimwiki::{rename_page, HtmlConfig};
+
+pub fn rename(
+    cmd: RenameSubcommand,
+    _opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    // Need to make sure the page being renamed is legit
+    let old = match cmd.old.canonicalize() {
+        Ok(path) => path,
+        Err(x) => {
+            error!("{:?} failed to canonicalize: {}", cmd.old, x);
+            return Err(x);
+        }
+    };
+
+    // Refuse to replace an existing file, which may not be a wiki page
+    if cmd.new.exists() {
+        error!("{:?} already exists", cmd.new);
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{:?} already exists", cmd.new),
+        ));
+    }
+
+    // New path does not exist yet, so we resolve it against its nearest
+    // existing ancestor to land in the same path space as the wikis
+    let new = canonicalize_new_path(&cmd.new)?;
+
+    let wikis = ast.to_wikis(&config);
+    let all_edits = rename_page(&wikis, &old, &new).map_err(|x| {
+        io::Error::new(io::ErrorKind::InvalidInput, x.to_string())
+    })?;
+
+    if cmd.dry_run {
+        for edits in all_edits.iter() {
+            println!("{}", edits.path.to_string_lossy());
+            for edit in edits.edits.iter() {
+                println!(
+                    "  @{}..{} => {}",
+                    edit.region.offset(),
+                    edit.region.offset() + edit.region.len(),
+                    edit.new_text
+                );
+            }
+        }
+        println!("{} -> {}", old.to_string_lossy(), new.to_string_lossy());
+        return Ok(());
+    }
+
+    for edits in all_edits {
+        let text = fs::read_to_string(edits.path.as_path())?;
+        info!("Updating {} link(s) in {:?}", edits.edits.len(), edits.path);
+        fs::write(edits.path.as_path(), edits.apply(&text))?;
+    }
+
+    if let Some(parent) = new.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    info!("Moving {:?} to {:?}", old, new);
+    fs::rename(old, new)
+}
+
+/// Canonicalizes a path that does not exist yet by canonicalizing its
+/// nearest existing ancestor and joining the rest of the path onto it
+fn canonicalize_new_path(path: &Path) -> io::Result<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+
+    for ancestor in path.ancestors().skip(1) {
+        if ancestor.exists() {
+            let rest = path.strip_prefix(ancestor).map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidInput, x.to_string())
+            })?;
+            return Ok(ancestor.canonicalize()?.join(rest));
+        }
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,24 @@
+use crate::SchemaSubcommand;
+use std::{
+    fs,
+    io::{self, Write},
+};
+use vimwiki::{json_schema, migrate, Page};
+
+pub fn schema(cmd: SchemaSubcommand) -> io::Result<()> {
+    let value = match cmd.migrate {
+        Some(path) => {
+            let reader = io::BufReader::new(fs::File::open(path)?);
+            let value = serde_json::from_reader(reader)?;
+            migrate(value).map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?
+        }
+        None => serde_json::to_value(json_schema::<Page>())?,
+    };
+
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    serde_json::to_writer_pretty(&mut handle, &value)?;
+    writeln!(handle)
+}
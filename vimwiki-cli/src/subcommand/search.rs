@@ -0,0 +1,110 @@
+use crate::{Ast, CommonOpt, SearchSubcommand};
+use log::*;
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+use vimwiki::{SearchIndex, SearchQuery};
+
+pub fn search(
+    cmd: SearchSubcommand,
+    opt: CommonOpt,
+    ast: Ast,
+) -> io::Result<()> {
+    let query: SearchQuery = cmd
+        .query
+        .join(" ")
+        .parse()
+        .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
+
+    let index_path = opt.cache.join("search").join("index.json");
+    let mut index = if opt.no_cache {
+        SearchIndex::new()
+    } else {
+        load_index(&index_path)
+    };
+
+    // Only reindex pages whose contents have changed since they were last
+    // indexed, dropping any pages that are no longer part of a wiki
+    let mut is_dirty = false;
+    let mut paths = HashSet::new();
+    for file in ast.wikis.iter().flat_map(|w| w.files.iter()) {
+        paths.insert(file.path.as_path());
+        if index.checksum(&file.path) != Some(file.checksum.as_str()) {
+            debug!("{:?} :: reindexing", file.path);
+            index.update_page(
+                &file.path,
+                Some(file.checksum.to_string()),
+                &file.data,
+            );
+            is_dirty = true;
+        }
+    }
+
+    let cnt = index.len();
+    index.retain_pages(|path| paths.contains(path));
+    is_dirty |= cnt != index.len();
+
+    if is_dirty && !opt.no_cache {
+        if let Err(x) = save_index(&index_path, &index) {
+            error!("Failed to save search index @ {:?}: {}", index_path, x);
+        }
+    }
+
+    let results = index.search(&query);
+    if cmd.json {
+        let stdout = io::stdout();
+        let mut handle = stdout.lock();
+        serde_json::to_writer_pretty(&mut handle, &results)?;
+        writeln!(handle)?;
+    } else {
+        for result in results.iter() {
+            println!("{} ({:.3})", result.path.to_string_lossy(), result.score);
+            for hit in result.hits.iter() {
+                println!(
+                    "  {}@{} [{}]",
+                    result.path.to_string_lossy(),
+                    hit.region.offset(),
+                    hit.field
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads the search index from the cache, starting over with an empty index
+/// if it is missing or corrupt
+fn load_index(path: &Path) -> SearchIndex {
+    if !path.exists() {
+        debug!("No search index found at {:?}", path);
+        return SearchIndex::new();
+    }
+
+    let index: io::Result<SearchIndex> = fs::File::open(path)
+        .map(io::BufReader::new)
+        .and_then(|reader| {
+            serde_json::from_reader(reader).map_err(io::Error::from)
+        });
+
+    match index {
+        Ok(index) => index,
+        Err(x) => {
+            error!("Search index corrupted @ {:?}: {}", path, x);
+            SearchIndex::new()
+        }
+    }
+}
+
+fn save_index(path: &Path, index: &SearchIndex) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file = fs::File::create(path)?;
+    serde_json::to_writer(io::BufWriter::new(file), index)
+        .map_err(io::Error::from)
+}
@@ -0,0 +1,248 @@
+use crate::{css, Ast, CommonOpt, SiteSubcommand};
+use log::*;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use vimwiki::*;
+
+pub fn site(
+    _cmd: SiteSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let wikis = ast.to_wikis(&config);
+    let graph = WikiGraph::from_wikis(&wikis);
+
+    for wiki in wikis.iter().filter(|wiki| {
+        opt.filter_by_wiki_idx_and_name(wiki.index, wiki.name.as_deref())
+    }) {
+        match config.find_wiki_by_index(wiki.index) {
+            Some(wiki_config) => {
+                generate_site(&config, wiki_config, wiki, &graph)?
+            }
+            None => warn!("Wiki {} is missing a config", wiki.index),
+        }
+    }
+
+    Ok(())
+}
+
+fn generate_site(
+    config: &HtmlConfig,
+    wiki_config: &HtmlWikiConfig,
+    wiki: &Wiki<'_>,
+    graph: &WikiGraph,
+) -> io::Result<()> {
+    let site = &wiki_config.site;
+
+    // Pages marked with %nohtml, and drafts unless configured otherwise,
+    // are left out of the site entirely
+    let pages = wiki
+        .pages
+        .iter()
+        .map(|page| HtmlPageInfo {
+            path: page.path.to_path_buf(),
+            metadata: PageMetadata::from_page(&page.page),
+        })
+        .filter(|page| {
+            !page.metadata.is_no_html()
+                && (site.include_drafts || !page.metadata.is_draft())
+        })
+        .collect::<Vec<HtmlPageInfo>>();
+    let is_published =
+        |path: &Path| pages.iter().any(|page| page.path.as_path() == path);
+
+    // The tree of pages and the diary are the same for every page, so they
+    // are built once for the wiki
+    let tree = HtmlPageTree::from_pages(wiki_config, &pages);
+    let diary = find_diary_pages(wiki_config, &pages);
+
+    for page in wiki.pages.iter().filter(|page| is_published(&page.path)) {
+        let mut backlinks = graph
+            .backlinks(&page.path)
+            .into_iter()
+            .map(|edge| edge.src.to_path_buf())
+            .filter(|src| is_published(src))
+            .collect::<Vec<PathBuf>>();
+        backlinks.dedup();
+
+        let mut config = config.clone();
+        config.map_runtime(|mut rt| {
+            rt.page = page.path.to_path_buf();
+            rt.wiki_index = Some(wiki.index);
+            rt.pages = pages.clone();
+            rt.tree = tree.clone();
+            rt.diary = diary.clone();
+            rt.backlinks = backlinks;
+            rt
+        });
+
+        let html = page.page.to_html_page(config).map_err(|x| {
+            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+        })?;
+        write_file(
+            &wiki_config.make_output_path(&page.path, "html"),
+            html.as_bytes(),
+        )?;
+    }
+
+    // The index of pages is treated as a page at the root of the wiki so
+    // that its links and template resolve like any other page
+    let mut sitemap_pages = pages.clone();
+    let index_path = wiki_config
+        .path
+        .join(format!("{}.{}", site.index_name, wiki_config.ext));
+    if wiki.find_page_by_path(&index_path).is_some() {
+        warn!(
+            "{:?} already exists, so skipping generating an index of pages",
+            index_path
+        );
+    } else {
+        let mut config = config.clone();
+        config.map_runtime(|mut rt| {
+            rt.page = index_path.to_path_buf();
+            rt.wiki_index = Some(wiki.index);
+            rt.pages = pages.clone();
+            rt.tree = tree.clone();
+            rt.diary = diary.clone();
+            rt.backlinks = Vec::new();
+            rt
+        });
+
+        let index = HtmlSiteIndex {
+            title: site.index_title.to_string(),
+        };
+        let html = index.to_html_page(config).map_err(|x| {
+            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+        })?;
+        write_file(
+            &wiki_config.make_output_path(&index_path, "html"),
+            html.as_bytes(),
+        )?;
+
+        sitemap_pages.push(HtmlPageInfo {
+            path: index_path,
+            ..Default::default()
+        });
+    }
+
+    if site.sitemap {
+        let sitemap =
+            HtmlSitemap::from_pages(config, wiki_config, &sitemap_pages);
+        write_file(
+            &wiki_config.path_html.join("sitemap.xml"),
+            sitemap.to_xml_string().as_bytes(),
+        )?;
+    }
+
+    if site.search {
+        write_search(config, wiki_config, wiki, pages.clone())?;
+    }
+
+    if site.copy_assets {
+        let mut assets = wiki
+            .pages
+            .iter()
+            .filter(|page| is_published(&page.path))
+            .flat_map(|page| find_page_assets(wiki_config, page))
+            .collect::<Vec<PathBuf>>();
+        assets.sort();
+        assets.dedup();
+
+        for asset in assets {
+            let dst = match wiki_config.path_within(&asset) {
+                Some(path) => wiki_config.path_html.join(path),
+                None => continue,
+            };
+
+            if !asset.is_file() {
+                warn!("{:?} is referenced but missing, so skipping", asset);
+                continue;
+            }
+
+            debug!("Copying {:?} to {:?}", asset, dst);
+            if let Some(parent) = dst.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&asset, &dst)?;
+        }
+    }
+
+    // Only provide vimwiki's style.css when the wiki does not have its own
+    let css_path = wiki_config.path_html.join(&wiki_config.css_name);
+    if !css_path.exists() {
+        write_file(&css_path, css::DEFAULT_STYLE_FILE.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Writes the search index of a wiki's pages alongside the page to search it
+/// at the root of the wiki's html output, only indexing the pages of the
+/// wiki that are also provided as page info
+pub(super) fn write_search(
+    config: &HtmlConfig,
+    wiki_config: &HtmlWikiConfig,
+    wiki: &Wiki<'_>,
+    pages: Vec<HtmlPageInfo>,
+) -> io::Result<()> {
+    let site = &wiki_config.site;
+
+    let indexed = wiki
+        .pages
+        .iter()
+        .filter(|page| pages.iter().any(|x| x.path == page.path))
+        .cloned()
+        .collect::<Vec<WikiPage<'_>>>();
+    let index = HtmlSearchIndex::from_pages(wiki_config, &indexed);
+    write_file(
+        &wiki_config.path_html.join(&site.search_index_name),
+        serde_json::to_string(&index)?.as_bytes(),
+    )?;
+
+    // Like the index of pages, the search page is treated as a page at the
+    // root of the wiki
+    let search_path = wiki_config
+        .path
+        .join(format!("{}.{}", site.search_name, wiki_config.ext));
+    if wiki.find_page_by_path(&search_path).is_some() {
+        warn!(
+            "{:?} already exists, so skipping generating a search page",
+            search_path
+        );
+        return Ok(());
+    }
+
+    let mut config = config.clone();
+    config.map_runtime(|mut rt| {
+        rt.page = search_path.to_path_buf();
+        rt.wiki_index = Some(wiki.index);
+        rt.tree = HtmlPageTree::from_pages(wiki_config, &pages);
+        rt.diary = find_diary_pages(wiki_config, &pages);
+        rt.pages = pages;
+        rt.backlinks = Vec::new();
+        rt
+    });
+
+    let search = HtmlSearchPage {
+        title: site.search_title.to_string(),
+        index_name: site.search_index_name.to_string(),
+    };
+    let html = search.to_html_page(config).map_err(|x| {
+        io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+    })?;
+    write_file(
+        &wiki_config.make_output_path(&search_path, "html"),
+        html.as_bytes(),
+    )
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    info!("Writing to {:?}", path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)
+}
@@ -0,0 +1,77 @@
+use crate::{Ast, CommonOpt, TagsSubcommand};
+use log::*;
+use std::io;
+use vimwiki::{HtmlConfig, TagIndex, ToHtmlPage};
+
+pub fn tags(
+    cmd: TagsSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let wikis = ast.to_wikis(&config);
+    let mut index = TagIndex::from_wikis(&wikis);
+    if !cmd.tags.is_empty() {
+        index.retain(|tag| cmd.tags.iter().any(|t| t == tag.as_str()));
+    }
+
+    if cmd.json {
+        let stdout = io::stdout();
+        return serde_json::to_writer_pretty(stdout, &index)
+            .map_err(io::Error::from);
+    }
+
+    if cmd.html {
+        for (idx, wiki) in
+            config.wikis.iter().enumerate().filter(|(idx, wiki)| {
+                opt.filter_by_wiki_idx_and_name(*idx, wiki.name.as_deref())
+            })
+        {
+            let mut config = config.clone();
+            config.map_runtime(|mut rt| {
+                rt.page = wiki.path.join(format!("tags.{}", wiki.ext));
+                rt.wiki_index = Some(idx);
+                rt
+            });
+
+            let html = index.to_html_page(config).map_err(|x| {
+                io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+            })?;
+
+            let path = wiki.path_html.join("tags.html");
+            info!("Writing to {:?}", path);
+            std::fs::create_dir_all(wiki.path_html.as_path())?;
+            std::fs::write(path, html)?;
+        }
+        return Ok(());
+    }
+
+    // When not searching for specific tags, we only list the tags themselves
+    if cmd.tags.is_empty() {
+        for tag in index.tags() {
+            println!("{}", tag);
+        }
+        return Ok(());
+    }
+
+    for (tag, locations) in index.iter() {
+        println!("{}", tag);
+        for location in locations {
+            match location.header.as_ref() {
+                Some(header) => println!(
+                    "  {}@{} ({})",
+                    location.path.to_string_lossy(),
+                    location.region.offset(),
+                    header
+                ),
+                None => println!(
+                    "  {}@{}",
+                    location.path.to_string_lossy(),
+                    location.region.offset()
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
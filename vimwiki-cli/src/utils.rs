@@ -4,7 +4,7 @@ use std::{
     io,
     path::{Path, PathBuf},
 };
-use vimwiki::{HtmlConfig, HtmlWikiConfig, VimwikiConfig};
+use vimwiki::{HtmlConfig, HtmlWikiConfig, LintConfig, VimwikiConfig};
 
 /// Attempts to load a vimwiki format config from a file
 pub fn load_format_config(path: &Path) -> io::Result<VimwikiConfig> {
@@ -16,6 +16,21 @@ pub fn load_format_config(path: &Path) -> io::Result<VimwikiConfig> {
     Ok(config)
 }
 
+/// Attempts to load a lint config from the `lint` table of a file, using the
+/// default config if the file has no such table
+pub fn load_lint_config(path: &Path) -> io::Result<LintConfig> {
+    trace!("load_lint_config(path = {:?})", path);
+
+    let config_string = std::fs::read_to_string(path)?;
+    let value: toml::Value = toml::from_str(config_string.as_str())?;
+    let config: LintConfig = match value.get("lint") {
+        Some(x) => x.clone().try_into()?,
+        None => LintConfig::default(),
+    };
+
+    Ok(config)
+}
+
 /// Attempts to load an html config from a file, attempting to load wikis from
 /// vim/neovim if no wikis are defined or if merge = true
 pub fn load_html_config(
@@ -16,6 +16,7 @@ use std::{borrow::Cow, fmt, iter::FromIterator};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Blockquote<'a> {
     /// Represents the lines of text contained within the blockquote include
     /// potential blank lines
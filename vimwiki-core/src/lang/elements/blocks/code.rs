@@ -15,6 +15,7 @@ use std::{borrow::Cow, collections::HashMap, fmt, iter::FromIterator};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CodeBlock<'a> {
     /// Represents the language associated with the code block if it has one
     pub language: Option<Cow<'a, str>>,
@@ -41,6 +42,23 @@ impl<'a> CodeBlock<'a> {
             lines: iter.into_iter().map(Into::into).collect(),
         }
     }
+
+    /// Returns the language of the code block, falling back to the brush of
+    /// its class metadata when it has no language
+    pub fn language_or_brush(&self) -> Option<&str> {
+        self.language.as_deref().or_else(|| self.brush())
+    }
+
+    /// Returns the brush of the code block's class metadata if it has one,
+    /// such as `python` from `class="brush: python"`
+    pub fn brush(&self) -> Option<&str> {
+        self.metadata.get("class").and_then(|value| {
+            value
+                .strip_prefix("brush:")
+                .map(str::trim)
+                .filter(|x| !x.is_empty())
+        })
+    }
 }
 
 impl CodeBlock<'_> {
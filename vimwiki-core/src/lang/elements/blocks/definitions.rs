@@ -32,6 +32,7 @@ use std::{
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 #[display(fmt = "{}", _0)]
 #[into_iterator(owned, ref, ref_mut)]
@@ -144,10 +145,17 @@ pub type Definition<'a> = DefinitionListValue<'a>;
     Deserialize,
     IntoIterator,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DefinitionList<'a> {
     /// Represents the inner mapping of terms to definitions
     #[into_iterator(owned, ref, ref_mut)]
     #[serde(with = "serde_with::rust::map_as_tuple_list")]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(
+            with = "Vec<(Located<Term<'a>>, Vec<Located<Definition<'a>>>)>"
+        )
+    )]
     pub mapping: HashMap<Located<Term<'a>>, Vec<Located<Definition<'a>>>>,
 }
 
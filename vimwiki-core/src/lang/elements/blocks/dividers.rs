@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 #[derive(
     Constructor, Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Divider;
 
 impl StrictEq for Divider {
@@ -21,6 +21,7 @@ use std::fmt;
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Header<'a> {
     /// Represents the content contained within the header
     #[index]
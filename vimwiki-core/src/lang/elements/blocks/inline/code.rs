@@ -16,6 +16,7 @@ use std::borrow::Cow;
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 pub struct CodeInline<'a>(
     /// Represents the text contained within the inline code snippet
@@ -18,6 +18,7 @@ use std::{borrow::Cow, iter::FromIterator};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Comment<'a> {
     Line(LineComment<'a>),
     MultiLine(MultiLineComment<'a>),
@@ -60,6 +61,7 @@ impl<'a> StrictEq for Comment<'a> {
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 #[display(fmt = "{}", "_0.trim()")]
 pub struct LineComment<'a>(
@@ -128,6 +130,7 @@ impl<'a> StrictEq for LineComment<'a> {
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 #[display(fmt = "{}", "_0.join(\"\n\")")]
 #[into_iterator(owned, ref, ref_mut)]
@@ -22,6 +22,7 @@ use uriparse::{Fragment, FragmentError};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[into_iterator(owned, ref, ref_mut)]
 pub struct Anchor<'a>(
     /// Represents the individual parts of the anchor
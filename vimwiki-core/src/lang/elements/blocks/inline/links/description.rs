@@ -19,6 +19,7 @@ use uriparse::{URIReference, URIReferenceError};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Description<'a> {
     Text(Cow<'a, str>),
     TransclusionLink(Box<LinkData<'a>>),
@@ -31,6 +31,7 @@ pub use data::LinkData;
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Link<'a> {
     /// Represents a traditional link within a wiki
     #[display(fmt = "{}", data)]
@@ -15,6 +15,7 @@ use std::borrow::Cow;
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 pub struct MathInline<'a>(
     /// Represents the text contained within the inline math snippet
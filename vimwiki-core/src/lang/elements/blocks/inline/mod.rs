@@ -25,6 +25,7 @@ pub use typefaces::*;
 #[derive(
     Clone, Debug, Display, From, Eq, PartialEq, Hash, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum InlineElement<'a> {
     Text(Text<'a>),
     DecoratedText(DecoratedText<'a>),
@@ -111,6 +112,7 @@ impl<'a> StrictEq for InlineElement<'a> {
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[into_iterator(owned, ref, ref_mut)]
 pub struct InlineElementContainer<'a>(Vec<Located<InlineElement<'a>>>);
 
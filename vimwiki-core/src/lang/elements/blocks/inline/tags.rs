@@ -34,6 +34,7 @@ use std::{borrow::Cow, fmt, iter::FromIterator};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 #[into_iterator(owned, ref, ref_mut)]
 pub struct Tags<'a>(
@@ -142,6 +143,7 @@ impl<'a> StrictEq for Tags<'a> {
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 pub struct Tag<'a>(Cow<'a, str>);
 
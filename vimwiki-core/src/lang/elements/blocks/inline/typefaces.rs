@@ -23,6 +23,7 @@ use std::{borrow::Cow, fmt};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Text<'a>(Cow<'a, str>);
 
 impl<'a> Text<'a> {
@@ -95,6 +96,7 @@ impl<'a> StrictEq for Text<'a> {
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum DecoratedTextContent<'a> {
     Text(Text<'a>),
     DecoratedText(DecoratedText<'a>),
@@ -198,6 +200,7 @@ impl<'a> StrictEq for DecoratedTextContent<'a> {
 #[derive(
     Clone, Debug, Eq, PartialEq, Hash, IsVariant, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum DecoratedText<'a> {
     Bold(Vec<Located<DecoratedTextContent<'a>>>),
     Italic(Vec<Located<DecoratedTextContent<'a>>>),
@@ -410,6 +413,7 @@ impl<'a> StrictEq for DecoratedText<'a> {
 #[derive(
     Copy, Clone, Debug, Display, Eq, PartialEq, Hash, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Keyword {
     #[display(fmt = "TODO")]
     Todo,
@@ -23,6 +23,7 @@ use std::borrow::Cow;
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ListItem<'a> {
     /// Represents the type of list item
     pub ty: ListItemType<'a>,
@@ -239,6 +240,7 @@ impl<'a> ListItem<'a> {
 
 /// Represents a suffix such as . or ) used after beginning of list item
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ListItemSuffix {
     None,
     Period,
@@ -269,6 +271,7 @@ impl StrictEq for ListItemSuffix {
 }
 
 #[derive(Clone, Debug, From, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ListItemType<'a> {
     Ordered(OrderedListItemType),
     Unordered(UnorderedListItemType<'a>),
@@ -326,6 +329,7 @@ impl<'a> StrictEq for ListItemType<'a> {
 
 /// Represents the type associated with an unordered item
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum UnorderedListItemType<'a> {
     /// -
     Hyphen,
@@ -397,6 +401,7 @@ impl<'a> Default for UnorderedListItemType<'a> {
 
 /// Represents the type associated with an ordered item
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum OrderedListItemType {
     /// 1
     Number,
@@ -484,6 +489,7 @@ impl StrictEq for OrderedListItemType {
 
 /// Represents the todo status for a list item
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ListItemTodoStatus {
     /// Flags list item as a TODO item that has not been completed
     Incomplete,
@@ -515,6 +521,7 @@ impl StrictEq for ListItemTodoStatus {
 #[derive(
     Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ListItemAttributes {
     /// The TODO status for a list item, if it has been associated with TODO
     pub todo_status: Option<ListItemTodoStatus>,
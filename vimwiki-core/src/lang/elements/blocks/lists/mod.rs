@@ -29,6 +29,7 @@ pub use item::*;
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct List<'a> {
     /// Represents items contained within the list
     #[index]
@@ -182,6 +183,7 @@ impl<'a> StrictEq for List<'a> {
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[as_ref(forward)]
 #[into_iterator(owned, ref, ref_mut)]
 pub struct ListItemContents<'a>(Vec<Located<BlockElement<'a>>>);
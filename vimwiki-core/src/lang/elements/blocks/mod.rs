@@ -32,6 +32,7 @@ pub use tables::*;
 #[derive(
     Clone, Debug, From, Eq, PartialEq, Serialize, Deserialize, IsVariant,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum BlockElement<'a> {
     Blockquote(Blockquote<'a>),
     CodeBlock(CodeBlock<'a>),
@@ -20,6 +20,7 @@ use std::{fmt, iter::FromIterator};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Paragraph<'a> {
     /// Represents the lines of content contained within the paragraph
     #[index]
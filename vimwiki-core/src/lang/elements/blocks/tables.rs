@@ -22,6 +22,7 @@ use std::{num::ParseIntError, str::FromStr};
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[display(fmt = "{},{}", row, col)]
 pub struct CellPos {
     /// Represents the row number of a cell starting from 0
@@ -70,11 +71,16 @@ impl FromStr for CellPos {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, IntoIterator, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Table<'a> {
     /// Represents the table's data (cells) as a mapping between a cell's
     /// position and its actual content (private)
     #[into_iterator(owned, ref, ref_mut)]
     #[serde(with = "serde_with::rust::map_as_tuple_list")]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "Vec<(CellPos, Located<Cell<'a>>)>")
+    )]
     cells: HashMap<CellPos, Located<Cell<'a>>>,
 
     /// Represents the total rows contained in the table (private)
@@ -356,6 +362,7 @@ impl<'a> StrictEq for Table<'a> {
 #[derive(
     Clone, Debug, From, Eq, PartialEq, Hash, Serialize, Deserialize, IsVariant,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Cell<'a> {
     Content(InlineElementContainer<'a>),
     Span(CellSpan),
@@ -434,6 +441,7 @@ impl<'a> StrictEq for Cell<'a> {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum CellSpan {
     FromLeft,
     FromAbove,
@@ -447,6 +455,7 @@ impl StrictEq for CellSpan {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ColumnAlign {
     None,
     Left,
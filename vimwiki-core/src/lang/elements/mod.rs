@@ -26,6 +26,7 @@ pub use utils::{
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Page<'a> {
     /// Comprised of the elements within a page
     #[index]
@@ -101,6 +102,7 @@ impl<'a> StrictEq for Page<'a> {
 /// Represents a `BlockElement`, an `InlineElement`, or one of a handful of
 /// special inbetween types like `ListItem`
 #[derive(Clone, Debug, From, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Element<'a> {
     Block(BlockElement<'a>),
     Inline(InlineElement<'a>),
@@ -199,6 +201,7 @@ impl<'a> Element<'a> {
 /// Represents a some element that is a descendant of a `BlockElement`, but
 /// is not an `InlineElement` such as `ListItem`
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum InlineBlockElement<'a> {
     ListItem(ListItem<'a>),
     Term(Term<'a>),
@@ -52,6 +52,7 @@ pub trait IntoChildren {
     Serialize,
     Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[display(fmt = "{}", inner)]
 pub struct Located<T> {
     #[deref]
@@ -6,6 +6,7 @@ use std::ops::{Range, RangeInclusive, RangeTo, RangeToInclusive};
 #[derive(
     Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Region {
     /// Position within some byte array this region begins
     offset: usize,
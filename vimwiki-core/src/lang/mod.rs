@@ -1,6 +1,7 @@
 pub mod elements;
 pub mod output;
 pub mod parsers;
+pub mod schema;
 
 use derive_more::Display;
 use elements::*;
@@ -0,0 +1,55 @@
+use super::{GemtextConfig, GemtextFormatter, GemtextOutputError, Output};
+
+pub trait ToGemtextString {
+    fn to_gemtext_string(
+        &self,
+        config: GemtextConfig,
+    ) -> Result<String, GemtextOutputError>;
+}
+
+impl<T: Output<GemtextFormatter>> ToGemtextString for T {
+    fn to_gemtext_string(
+        &self,
+        config: GemtextConfig,
+    ) -> Result<String, GemtextOutputError> {
+        let mut formatter = GemtextFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_content())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GemtextOutputResult;
+
+    struct TestOutput<F: Fn(&mut GemtextFormatter) -> GemtextOutputResult>(F);
+    impl<F: Fn(&mut GemtextFormatter) -> GemtextOutputResult>
+        Output<GemtextFormatter> for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    fn _text(
+        text: impl Into<String>,
+    ) -> impl Fn(&mut GemtextFormatter) -> GemtextOutputResult {
+        let text = text.into();
+        move |f: &mut GemtextFormatter| {
+            use std::fmt::Write;
+            write!(f, "{}", text.as_str())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_gemtext_string_should_produce_a_string_representing_only_the_gemtext_of_the_output(
+    ) {
+        let output = TestOutput(_text("# I am some gemtext output"));
+        let result =
+            output.to_gemtext_string(GemtextConfig::default()).unwrap();
+        assert_eq!(result, "# I am some gemtext output");
+    }
+}
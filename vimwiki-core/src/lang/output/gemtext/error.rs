@@ -0,0 +1,11 @@
+use derive_more::{Display, Error, From};
+
+pub type GemtextOutputResult = Result<(), GemtextOutputError>;
+
+#[derive(Debug, From, Display, Error)]
+pub enum GemtextOutputError {
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
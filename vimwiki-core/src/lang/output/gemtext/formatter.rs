@@ -0,0 +1,122 @@
+use super::{GemtextConfig, GemtextOutputError, OutputFormatter};
+use std::{
+    fmt::{self, Write},
+    mem,
+};
+
+/// Represents the formatter to use to write gemtext output that includes
+/// various options that can be set as well as a context for use when writing
+/// output
+#[derive(Clone, Default)]
+pub struct GemtextFormatter {
+    /// Represents the configuration associated with the formatter
+    config: GemtextConfig,
+
+    /// Contains the content to be injected into a template
+    content: String,
+
+    /// Links (target and description) encountered while writing the current
+    /// block, which gemtext can only express as lines of their own
+    links: Vec<(String, String)>,
+}
+
+impl OutputFormatter for GemtextFormatter {
+    type Error = GemtextOutputError;
+}
+
+impl Write for GemtextFormatter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.content.write_str(s)
+    }
+}
+
+impl GemtextFormatter {
+    pub fn new(config: GemtextConfig) -> Self {
+        Self {
+            config,
+            content: String::new(),
+            links: Vec::new(),
+        }
+    }
+
+    pub fn clone_without_content(&self) -> Self {
+        Self {
+            content: String::new(),
+            links: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns true if the next character written will start a new line
+    #[inline]
+    pub fn is_at_line_start(&self) -> bool {
+        self.content.is_empty() || self.content.ends_with('\n')
+    }
+
+    /// Queues a link to be written on its own line once the current block
+    /// has been written, skipping links already queued
+    pub fn push_link(
+        &mut self,
+        target: impl Into<String>,
+        description: impl Into<String>,
+    ) {
+        let link = (target.into(), description.into());
+        if !self.links.contains(&link) {
+            self.links.push(link);
+        }
+    }
+
+    /// Removes and returns the links queued so far
+    #[inline]
+    pub fn take_links(&mut self) -> Vec<(String, String)> {
+        mem::take(&mut self.links)
+    }
+
+    /// Represents the config contained within the formatter
+    #[inline]
+    pub fn config(&self) -> &GemtextConfig {
+        &self.config
+    }
+
+    /// Represents the content contained within the formatter
+    #[inline]
+    pub fn get_content(&self) -> &str {
+        self.content.as_str()
+    }
+
+    /// Consumes the formatter and returns the content followed by a line for
+    /// each of the queued links
+    pub fn into_content(mut self) -> String {
+        for (target, description) in mem::take(&mut self.links) {
+            if !self.is_at_line_start() {
+                self.content.push('\n');
+            }
+
+            self.content.push_str("=> ");
+            self.content.push_str(&target);
+            if !description.is_empty() {
+                self.content.push(' ');
+                self.content.push_str(&description);
+            }
+            self.content.push('\n');
+        }
+
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_content_should_write_queued_links_after_content() {
+        let mut f = GemtextFormatter::default();
+        write!(f, "some text").unwrap();
+        f.push_link("a.gmi", "a");
+        f.push_link("b.gmi", "");
+        f.push_link("a.gmi", "a");
+
+        assert_eq!(f.into_content(), "some text\n=> a.gmi a\n=> b.gmi\n");
+    }
+}
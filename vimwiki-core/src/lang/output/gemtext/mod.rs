@@ -0,0 +1,747 @@
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::GemtextFormatter;
+
+mod convert;
+pub use convert::ToGemtextString;
+
+mod error;
+pub use error::{GemtextOutputError, GemtextOutputResult};
+
+use crate::lang::{
+    elements::*,
+    output::{make_page_link_target, Output, OutputFormatter},
+};
+use std::fmt::Write;
+use unicode_width::UnicodeWidthStr;
+
+/// Maximum level of a heading supported by gemtext
+const MAX_HEADING_LEVEL: usize = 3;
+
+impl<'a> Output<GemtextFormatter> for Page<'a> {
+    /// Writes a page in gemtext, following each block element with lines for
+    /// the links found within it as gemtext has no inline links
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        for element in self.elements.iter() {
+            let mut formatter = f.clone_without_content();
+            element.fmt(&mut formatter)?;
+            let text = formatter.into_content();
+
+            // Separate each block element that produces output with a blank
+            // line
+            if !text.is_empty() {
+                if !f.get_content().is_empty() {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Blockquote<'a> {
+    /// Writes a blockquote in gemtext, joining each group of lines into a
+    /// single quote line
+    ///
+    /// ```gemtext
+    /// > line 1 line 2
+    /// ```
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        for lines in self.line_groups() {
+            let lines =
+                lines.iter().map(|line| line.trim()).collect::<Vec<&str>>();
+            writeln!(f, "> {}", lines.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for DefinitionList<'a> {
+    /// Writes a definition list in gemtext as each term followed by its
+    /// definitions as list items
+    ///
+    /// ```gemtext
+    /// term
+    /// * definition 1
+    /// * definition 2
+    /// ```
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        for (term, defs) in self.iter() {
+            let term = render_inline(f, term.as_inner().as_inner())?;
+            writeln!(f, "{}", escape_line(&term))?;
+
+            for def in defs {
+                let def = render_inline(f, def.as_inner().as_inner())?;
+                writeln!(f, "* {}", def)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<GemtextFormatter> for Divider {
+    /// Writes a divider in gemtext as a line of dashes as gemtext has no
+    /// dedicated divider
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        writeln!(f, "---")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Header<'a> {
+    /// Writes a header in gemtext, clamping its level to the three levels
+    /// supported by gemtext and dropping any centering
+    ///
+    /// ```gemtext
+    /// ## header
+    /// ```
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        let text = render_inline(f, &self.content)?;
+        writeln!(
+            f,
+            "{} {}",
+            "#".repeat(self.level.clamp(1, MAX_HEADING_LEVEL)),
+            text
+        )?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for List<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        for item in self {
+            item.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for ListItem<'a> {
+    /// Writes a list item in gemtext, which has no nesting, so sublists are
+    /// flattened into the same list and ordered items keep their number
+    ///
+    /// ```gemtext
+    /// * [ ] incomplete
+    /// * [x] complete
+    /// * 1. item
+    /// ```
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        write!(f, "* ")?;
+
+        if let ListItemType::Ordered(_) = &self.ty {
+            write!(
+                f,
+                "{}{} ",
+                self.pos + 1,
+                if self.suffix == ListItemSuffix::Paren {
+                    ')'
+                } else {
+                    '.'
+                }
+            )?;
+        }
+
+        if let Some(todo_status) = self.attributes.todo_status.as_ref() {
+            todo_status.fmt(f)?;
+            write!(f, " ")?;
+        }
+
+        let mut contents = self.contents.iter().peekable();
+        if let Some(BlockElement::Paragraph(x)) =
+            contents.peek().map(|x| x.as_inner())
+        {
+            let text = render_paragraph(f, x)?;
+            write!(f, "{}", text)?;
+            contents.next();
+        }
+        writeln!(f)?;
+
+        for content in contents {
+            content.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Output<GemtextFormatter> for ListItemTodoStatus {
+    /// Writes a todo status as a checkbox, where only complete items are
+    /// checked
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        match self {
+            Self::Complete => write!(f, "[x]")?,
+            _ => write!(f, "[ ]")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for MathBlock<'a> {
+    /// Writes a math block in gemtext as preformatted text
+    ///
+    /// ````gemtext
+    /// ```math
+    /// \begin{align}
+    /// some math
+    /// \end{align}
+    /// ```
+    /// ````
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        writeln!(f, "```math")?;
+
+        if let Some(env) = self.environment.as_ref() {
+            writeln!(f, r"\begin{{{}}}", env)?;
+        }
+
+        for line in self {
+            writeln!(f, "{}", line)?;
+        }
+
+        if let Some(env) = self.environment.as_ref() {
+            writeln!(f, r"\end{{{}}}", env)?;
+        }
+
+        writeln!(f, "```")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Placeholder<'a> {
+    /// Writes the title placeholder in gemtext as a top-level heading,
+    /// dropping all other placeholders as gemtext has no metadata
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        if let Self::Title(x) = self {
+            writeln!(f, "# {}", x)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for CodeBlock<'a> {
+    /// Writes a code block in gemtext as preformatted text, using the
+    /// language or brush as the alt text
+    ///
+    /// ````gemtext
+    /// ```python
+    /// some code
+    /// ```
+    /// ````
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        let language = self.language_or_brush();
+
+        writeln!(f, "```{}", language.unwrap_or_default())?;
+        for line in self {
+            writeln!(f, "{}", line)?;
+        }
+        writeln!(f, "```")?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Paragraph<'a> {
+    /// Writes a paragraph in gemtext as a single line, which clients wrap
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        let text = render_paragraph(f, self)?;
+        if !text.is_empty() {
+            writeln!(f, "{}", escape_line(&text))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Table<'a> {
+    /// Writes a table in gemtext as preformatted text, padding cells to the
+    /// display width of their column according to its alignment and
+    /// separating the rows above the divider with a rule, where cells covered
+    /// by spans are left empty
+    ///
+    /// ````gemtext
+    /// ```
+    /// a | b
+    /// --+--
+    /// c | d
+    /// ```
+    /// ````
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        let col_cnt = self.col_cnt();
+        let divider = self.get_divider_row_index();
+
+        let mut rows = Vec::new();
+        for row in 0..self.row_cnt() {
+            if divider == Some(row) {
+                rows.push(None);
+                continue;
+            }
+
+            let mut cells = Vec::new();
+            for col in 0..col_cnt {
+                cells.push(
+                    match self.get_cell(row, col).map(|x| x.as_inner()) {
+                        Some(Cell::Content(x)) => render_inline(f, x)?,
+                        _ => String::new(),
+                    },
+                );
+            }
+            rows.push(Some(cells));
+        }
+
+        let widths = (0..col_cnt)
+            .map(|col| {
+                rows.iter()
+                    .flatten()
+                    .map(|cells| cells[col].width())
+                    .max()
+                    .unwrap_or_default()
+                    .max(1)
+            })
+            .collect::<Vec<usize>>();
+
+        writeln!(f, "```")?;
+        for row in rows.iter() {
+            let line = match row {
+                Some(cells) => cells
+                    .iter()
+                    .zip(widths.iter())
+                    .enumerate()
+                    .map(|(col, (text, width))| {
+                        let padding = width.saturating_sub(text.width());
+                        let (left, right) = match self.get_column_alignment(col)
+                        {
+                            ColumnAlign::Center => {
+                                (padding / 2, padding - padding / 2)
+                            }
+                            ColumnAlign::Right => (padding, 0),
+                            _ => (0, padding),
+                        };
+                        format!(
+                            "{}{}{}",
+                            " ".repeat(left),
+                            text,
+                            " ".repeat(right)
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" | "),
+                None => widths
+                    .iter()
+                    .map(|width| "-".repeat(*width))
+                    .collect::<Vec<String>>()
+                    .join("-+-"),
+            };
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        writeln!(f, "```")?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        write!(f, "{}", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for DecoratedText<'a> {
+    /// Writes decorated text in gemtext as plain text as gemtext has no
+    /// inline styling
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        for content in self.as_contents_slice() {
+            content.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<GemtextFormatter> for Keyword {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        write!(f, "{}", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Link<'a> {
+    /// Writes the text of a link in gemtext, queuing the link itself to be
+    /// written on its own line after the current block
+    ///
+    /// ### Wiki/Interwiki/Diary Link
+    ///
+    /// For `[[url#anchor|descr]]` in vimwiki, where anchors are dropped as
+    /// gemini clients do not scroll to them:
+    ///
+    /// ```gemtext
+    /// descr
+    /// => url.gmi descr
+    /// ```
+    ///
+    /// Links to anchors within the same page only write their text
+    ///
+    /// ### Raw Link
+    ///
+    /// For `https://example.com`:
+    ///
+    /// ```gemtext
+    /// https://example.com
+    /// => https://example.com
+    /// ```
+    ///
+    /// ### Transclusion Link
+    ///
+    /// For `{{path/to/img.png|descr}}`:
+    ///
+    /// ```gemtext
+    /// descr
+    /// => path/to/img.png descr
+    /// ```
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        let target = make_gemtext_link_target(self, f.config());
+
+        if let Self::Raw { data } = self {
+            let uri = data.uri_ref.to_string();
+            write!(f, "{}", uri)?;
+            f.push_link(uri, "");
+            return Ok(());
+        }
+
+        let description = match self.to_description_or_fallback() {
+            Some(Description::Text(x)) => x.trim().to_string(),
+            Some(Description::TransclusionLink(data)) => {
+                let link = Link::Transclusion { data: *data };
+                link.fmt(f)?;
+                String::new()
+            }
+            None => String::new(),
+        };
+
+        write!(f, "{}", description)?;
+        if let Some(target) = target {
+            f.push_link(target, description);
+        }
+
+        Ok(())
+    }
+}
+
+/// Produces the target of a link in gemtext, dropping anchors as gemini
+/// clients do not scroll to them, or nothing for links within the same page
+fn make_gemtext_link_target(
+    link: &Link<'_>,
+    config: &GemtextConfig,
+) -> Option<String> {
+    let target = make_page_link_target(
+        link,
+        &config.page_ext,
+        &config.diary_rel_path,
+        &config.root_rel_path,
+    );
+
+    match link {
+        Link::Wiki { data } if data.scheme().is_some() => Some(target),
+        Link::Wiki { data } if data.is_local_anchor() => None,
+        Link::Wiki { .. }
+        | Link::IndexedInterWiki { .. }
+        | Link::NamedInterWiki { .. }
+        | Link::Diary { .. } => {
+            target.split('#').next().map(ToString::to_string)
+        }
+        Link::Raw { .. } | Link::Transclusion { .. } => Some(target),
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Tags<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        write!(f, ":")?;
+
+        for tag in self {
+            write!(f, "{}:", tag.as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for CodeInline<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        write!(f, "`{}`", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for MathInline<'a> {
+    fn fmt(&self, f: &mut GemtextFormatter) -> GemtextOutputResult {
+        write!(f, "${}$", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<GemtextFormatter> for Comment<'a> {
+    /// Drops comments as gemtext has no way to hide text
+    fn fmt(&self, _f: &mut GemtextFormatter) -> GemtextOutputResult {
+        Ok(())
+    }
+}
+
+/// Renders inline content as trimmed text, moving any links found within it
+/// into the given formatter
+fn render_inline(
+    f: &mut GemtextFormatter,
+    content: &InlineElementContainer<'_>,
+) -> Result<String, GemtextOutputError> {
+    let mut formatter = f.clone_without_content();
+    content.fmt(&mut formatter)?;
+
+    for (target, description) in formatter.take_links() {
+        f.push_link(target, description);
+    }
+
+    Ok(formatter.into_content().trim().to_string())
+}
+
+/// Renders the lines of a paragraph as a single line of text
+fn render_paragraph(
+    f: &mut GemtextFormatter,
+    paragraph: &Paragraph<'_>,
+) -> Result<String, GemtextOutputError> {
+    let mut lines = Vec::new();
+    for line in paragraph.lines.iter() {
+        let text = render_inline(f, line)?;
+        if !text.is_empty() {
+            lines.push(text);
+        }
+    }
+
+    Ok(lines.join(" "))
+}
+
+/// Indents a line of text by a space if it would otherwise be read as a
+/// line type other than text
+fn escape_line(text: &str) -> String {
+    let is_special = ["=>", "#", "* ", ">", "```"]
+        .iter()
+        .any(|x| text.starts_with(x));
+
+    if is_special {
+        format!(" {}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+    use similar_asserts::assert_str_eq;
+
+    fn to_gemtext(text: &str) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_gemtext_string(GemtextConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn header_should_be_clamped_to_three_levels() {
+        let text = indoc! {"
+            = One =
+            ==== Four ====
+            ====== Six ======
+        "};
+
+        assert_str_eq!(
+            to_gemtext(text),
+            indoc! {"
+                # One
+
+                ### Four
+
+                ### Six
+            "}
+        );
+    }
+
+    #[test]
+    fn paragraph_should_be_followed_by_its_links() {
+        let text = indoc! {"
+            See [[some page#Part One|descr]] and [[#Top]] or
+            https://example.com for *more* `info`.
+
+            Second [[diary:2021-01-02]] {{img.png|alt}}
+        "};
+
+        assert_str_eq!(
+            to_gemtext(text),
+            indoc! {"
+                See descr and #Top or https://example.com/ for more `info`.
+                => some%20page.gmi descr
+                => https://example.com/
+
+                Second diary:2021-01-02 alt
+                => diary/2021-01-02.gmi diary:2021-01-02
+                => img.png alt
+            "}
+        );
+    }
+
+    #[test]
+    fn list_should_be_flattened_and_followed_by_its_links() {
+        let text = indoc! {"
+            * [ ] todo [[page]]
+            * [X] done
+                1) sub
+        "};
+
+        assert_str_eq!(
+            to_gemtext(text),
+            indoc! {"
+                * [ ] todo page
+                * [x] done
+                * 1) sub
+                => page.gmi page
+            "}
+        );
+    }
+
+    #[test]
+    fn code_and_math_blocks_should_be_preformatted() {
+        let text = indoc! {"
+            {{{python
+            some code
+            }}}
+            {{$%align%
+            x = y
+            }}$
+        "};
+
+        assert_str_eq!(
+            to_gemtext(text),
+            indoc! {r"
+                ```python
+                some code
+                ```
+
+                ```math
+                \begin{align}
+                x = y
+                \end{align}
+                ```
+            "}
+        );
+    }
+
+    #[test]
+    fn table_should_be_aligned_preformatted_text() {
+        let text = indoc! {r"
+            | a  | long | z |
+            |----|:----:|--:|
+            | c  | d    | 1 |
+        "};
+
+        assert_str_eq!(
+            to_gemtext(text),
+            indoc! {"
+                ```
+                a | long | z
+                --+------+--
+                c |  d   | 1
+                ```
+            "}
+        );
+    }
+
+    #[test]
+    fn paragraph_should_be_escaped_when_starting_like_other_lines() {
+        assert_str_eq!(to_gemtext("=> not a link\n"), " => not a link\n");
+    }
+}
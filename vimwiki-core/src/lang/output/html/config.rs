@@ -1,7 +1,9 @@
 use super::utils::{deserialize_absolute_path, make_path_relative};
+use crate::wiki::PageMetadata;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     convert::TryFrom,
     path::{Component, Path, PathBuf},
 };
@@ -270,6 +272,22 @@ pub struct HtmlRuntimeConfig {
 
     /// Path to the page's file that is being processed
     pub page: PathBuf,
+
+    /// Pages of the wiki containing the page being processed, made
+    /// available to templates (e.g. to build navigation)
+    pub pages: Vec<HtmlPageInfo>,
+
+    /// Pages of the wiki containing the page being processed arranged by
+    /// directory, built once per wiki from the same pages as `pages`
+    pub tree: HtmlPageTree,
+
+    /// Diary pages of the wiki containing the page being processed ordered
+    /// by date, built once per wiki via `find_diary_pages`
+    pub diary: Vec<HtmlPageInfo>,
+
+    /// Paths to the pages that link to the page being processed, made
+    /// available to templates
+    pub backlinks: Vec<PathBuf>,
 }
 
 impl HtmlRuntimeConfig {
@@ -296,7 +314,63 @@ impl Default for HtmlRuntimeConfig {
             //       being included, otherwise trying to map the runtime
             //       page (default) to a tmp wiki (default) will fail
             page: HtmlWikiConfig::default_path().join("index.wiki"),
+            pages: Vec::new(),
+            tree: HtmlPageTree::default(),
+            diary: Vec::new(),
+            backlinks: Vec::new(),
+        }
+    }
+}
+
+/// Represents a page of a wiki as made available to templates
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HtmlPageInfo {
+    /// Path to the page's file
+    pub path: PathBuf,
+
+    /// Metadata described by the page's placeholders
+    pub metadata: PageMetadata,
+}
+
+/// Represents the pages of a wiki arranged as a tree by directory, where
+/// each node is named after a directory or a page (without extension)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HtmlPageTree {
+    /// Page at this point in the tree, if any
+    pub page: Option<HtmlPageInfo>,
+
+    /// Directories and pages beneath this point in the tree by name
+    pub children: BTreeMap<String, HtmlPageTree>,
+}
+
+impl HtmlPageTree {
+    /// Arranges the given pages by their path within the wiki, skipping
+    /// pages that are not within the wiki
+    pub fn from_pages(wiki: &HtmlWikiConfig, pages: &[HtmlPageInfo]) -> Self {
+        let mut tree = Self::default();
+
+        for page in pages {
+            let path = match wiki.path_within(&page.path) {
+                Some(path) => path.with_extension(""),
+                None => continue,
+            };
+
+            let mut node = &mut tree;
+            for component in path.iter() {
+                node = node
+                    .children
+                    .entry(component.to_string_lossy().to_string())
+                    .or_default();
+            }
+            node.page = Some(page.clone());
         }
+
+        tree
+    }
+
+    /// Returns true if the tree has no pages
+    pub fn is_empty(&self) -> bool {
+        self.page.is_none() && self.children.is_empty()
     }
 }
 
@@ -334,6 +408,11 @@ pub struct HtmlWikiConfig {
     /// Path for diary directory relative to this wiki's path
     #[serde(default = "HtmlWikiConfig::default_diary_rel_path")]
     pub diary_rel_path: PathBuf,
+
+    /// Configuration settings that apply when generating a site from the
+    /// wiki
+    #[serde(default)]
+    pub site: HtmlSiteConfig,
 }
 
 impl Default for HtmlWikiConfig {
@@ -345,6 +424,7 @@ impl Default for HtmlWikiConfig {
             css_name: Self::default_css_name(),
             ext: Self::default_ext(),
             diary_rel_path: Self::default_diary_rel_path(),
+            site: HtmlSiteConfig::default(),
         }
     }
 }
@@ -474,6 +554,115 @@ impl HtmlWikiConfig {
     }
 }
 
+/// Represents configuration options related to generating a site from a wiki
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HtmlSiteConfig {
+    /// Name of the page (without extension) generated at the root of the
+    /// wiki's html output that lists every page of the wiki
+    #[serde(default = "HtmlSiteConfig::default_index_name")]
+    pub index_name: String,
+
+    /// Title of the page that lists every page of the wiki
+    #[serde(default = "HtmlSiteConfig::default_index_title")]
+    pub index_title: String,
+
+    /// If true, a sitemap.xml is generated at the root of the wiki's html
+    /// output using the base url of links
+    #[serde(default = "HtmlSiteConfig::default_sitemap")]
+    pub sitemap: bool,
+
+    /// If true, local files referenced by pages (such as images) are copied
+    /// into the wiki's html output
+    #[serde(default = "HtmlSiteConfig::default_copy_assets")]
+    pub copy_assets: bool,
+
+    /// If true, pages marked as drafts are included in the site
+    #[serde(default = "HtmlSiteConfig::default_include_drafts")]
+    pub include_drafts: bool,
+
+    /// If true, a json search index of every page and a page to search it
+    /// are generated at the root of the wiki's html output
+    #[serde(default = "HtmlSiteConfig::default_search")]
+    pub search: bool,
+
+    /// Name of the page (without extension) generated at the root of the
+    /// wiki's html output that searches the wiki
+    #[serde(default = "HtmlSiteConfig::default_search_name")]
+    pub search_name: String,
+
+    /// Title of the page that searches the wiki
+    #[serde(default = "HtmlSiteConfig::default_search_title")]
+    pub search_title: String,
+
+    /// Name of the json search index file generated at the root of the
+    /// wiki's html output
+    #[serde(default = "HtmlSiteConfig::default_search_index_name")]
+    pub search_index_name: String,
+}
+
+impl Default for HtmlSiteConfig {
+    fn default() -> Self {
+        Self {
+            index_name: Self::default_index_name(),
+            index_title: Self::default_index_title(),
+            sitemap: Self::default_sitemap(),
+            copy_assets: Self::default_copy_assets(),
+            include_drafts: Self::default_include_drafts(),
+            search: Self::default_search(),
+            search_name: Self::default_search_name(),
+            search_title: Self::default_search_title(),
+            search_index_name: Self::default_search_index_name(),
+        }
+    }
+}
+
+impl HtmlSiteConfig {
+    #[inline]
+    pub fn default_index_name() -> String {
+        String::from("pages")
+    }
+
+    #[inline]
+    pub fn default_index_title() -> String {
+        String::from("Pages")
+    }
+
+    #[inline]
+    pub const fn default_sitemap() -> bool {
+        true
+    }
+
+    #[inline]
+    pub const fn default_copy_assets() -> bool {
+        true
+    }
+
+    #[inline]
+    pub const fn default_include_drafts() -> bool {
+        false
+    }
+
+    #[inline]
+    pub const fn default_search() -> bool {
+        false
+    }
+
+    #[inline]
+    pub fn default_search_name() -> String {
+        String::from("search")
+    }
+
+    #[inline]
+    pub fn default_search_title() -> String {
+        String::from("Search")
+    }
+
+    #[inline]
+    pub fn default_search_index_name() -> String {
+        String::from("search_index.json")
+    }
+}
+
 /// Represents configuration options related to lists
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HtmlListConfig {
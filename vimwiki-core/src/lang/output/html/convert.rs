@@ -1,5 +1,10 @@
-use super::{utils, HtmlConfig, HtmlFormatter, HtmlOutputError, Output};
-use chrono::Local;
+use super::{
+    site, HtmlConfig, HtmlFormatter, HtmlOutputError, HtmlPageInfo,
+    HtmlTemplate, HtmlTemplateValue, Output,
+};
+use crate::utils;
+use chrono::{Local, NaiveDate};
+use voca_rs::escape;
 
 pub trait ToHtmlString {
     /// Converts to individual HTML dom string
@@ -63,54 +68,186 @@ impl<T: Output<HtmlFormatter>> ToHtmlPage for T {
             .transpose()
             .map_err(|source| HtmlOutputError::TemplateNotLoaded { source })?
             .unwrap_or_else(|| formatter.config().template.text.to_string());
+        let template = HtmlTemplate::parse(&template)?;
+
+        // NOTE: Content is provided as html through the context rather than
+        //       substituted into the template text, so placeholders within
+        //       the content are never replaced
+        let context = make_context(&formatter, title, date);
+
+        // Included templates are found relative to the template directory,
+        // using the template extension when the name does not have one
+        let config = formatter.config();
+        let html = template.render(&context, |name| {
+            let mut path = config.template.dir.join(name);
+            if path.extension().is_none() {
+                path.set_extension(&config.template.ext);
+            }
+            std::fs::read_to_string(path)
+        })?;
+
+        Ok(html)
+    }
+}
 
-        // Fill in template variables
-        // NOTE: Content is filled in last so we don't replace parts of content
-        //       with template variable contents as template variables only
-        //       apply to the template and not the content itself
-        let template = template
-            .replace("%title%", &title)
-            .replace("%date%", &date.to_string())
-            .replace(
-                "%root_path%",
-                {
-                    let path_str = utils::path_to_uri_string(
-                        formatter
-                            .config()
-                            .to_active_page_path_to_wiki_root()
-                            .as_path(),
-                    );
-
-                    if path_str.is_empty() {
-                        String::new()
-                    } else {
-                        format!("{}/", path_str)
-                    }
-                }
-                .as_str(),
-            )
-            .replace(
-                "%wiki_path%",
-                &utils::path_to_uri_string(
-                    formatter.config().as_active_page_path_within_wiki(),
+/// Builds the values available to a page template
+fn make_context(
+    formatter: &HtmlFormatter,
+    title: String,
+    date: NaiveDate,
+) -> HtmlTemplateValue {
+    let config = formatter.config();
+    let wiki = config.to_current_wiki();
+    let root_path = site::make_root_path(config);
+
+    let pages = config
+        .runtime
+        .pages
+        .iter()
+        .map(|x| make_page_value(config, &root_path, x))
+        .collect::<Vec<HtmlTemplateValue>>();
+
+    // Backlinks reuse the details of the wiki's pages when available
+    let backlinks = config
+        .runtime
+        .backlinks
+        .iter()
+        .map(|path| {
+            let page = config
+                .runtime
+                .pages
+                .iter()
+                .find(|x| &x.path == path)
+                .cloned()
+                .unwrap_or_else(|| HtmlPageInfo {
+                    path: path.to_path_buf(),
+                    ..Default::default()
+                });
+            make_page_value(config, &root_path, &page)
+        })
+        .collect::<Vec<HtmlTemplateValue>>();
+
+    // Tags listed by the %tags placeholder come before tags within the text
+    let mut tags = formatter.metadata().tags().to_vec();
+    for tag in formatter.tags() {
+        if !tags.contains(tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    let page = HtmlPageInfo {
+        path: config.active_page().to_path_buf(),
+        metadata: formatter.metadata().clone(),
+    };
+    let (prev, next) = site::find_diary_neighbors(config);
+
+    HtmlTemplateValue::new_map()
+        .with("title", title)
+        .with("date", date.to_string())
+        .with("root_path", root_path.as_str())
+        .with(
+            "wiki_path",
+            utils::path_to_uri_string(config.as_active_page_path_within_wiki()),
+        )
+        .with("css", wiki.css_name.as_str())
+        .with("encoding", "utf-8")
+        .with(
+            "content",
+            HtmlTemplateValue::Html(formatter.get_content().to_string()),
+        )
+        .with(
+            "toc",
+            HtmlTemplateValue::Html(make_toc_html(formatter.toc())),
+        )
+        .with("metadata", formatter.metadata())
+        .with("tags", tags)
+        .with("page", make_page_value(config, &root_path, &page))
+        .with("pages", pages)
+        .with(
+            "tree",
+            HtmlTemplateValue::Html(site::make_page_tree_html(
+                config, &root_path,
+            )),
+        )
+        .with("backlinks", backlinks)
+        .with(
+            "diary",
+            HtmlTemplateValue::new_map()
+                .with(
+                    "prev",
+                    prev.map(|x| make_page_value(config, &root_path, x)),
+                )
+                .with(
+                    "next",
+                    next.map(|x| make_page_value(config, &root_path, x)),
                 ),
-            )
-            .replace(
-                "%css%",
-                formatter.config().to_current_wiki().css_name.as_str(),
-            )
-            .replace("%encoding%", "utf-8")
-            .replace("%content%", formatter.get_content());
+        )
+        .with(
+            "wiki",
+            HtmlTemplateValue::new_map()
+                .with("name", wiki.name)
+                .with("index", config.runtime.wiki_index),
+        )
+}
+
+/// Describes a page of the wiki to a template, including its path within the
+/// wiki (without extension), its url relative to the page being processed,
+/// its title, its date, and its metadata
+fn make_page_value(
+    config: &HtmlConfig,
+    root_path: &str,
+    page: &HtmlPageInfo,
+) -> HtmlTemplateValue {
+    let (path, url) =
+        site::make_page_path_and_url(config, root_path, &page.path);
+
+    HtmlTemplateValue::new_map()
+        .with("path", path)
+        .with("url", url)
+        .with("title", site::make_page_title(page))
+        .with("date", page.metadata.date().map(|x| x.to_string()))
+        .with("metadata", &page.metadata)
+        .with("is_current", page.path == config.active_page())
+}
+
+/// Writes the headers of a page as nested lists of links to each header
+fn make_toc_html(toc: &[(usize, String, String)]) -> String {
+    let mut html = String::new();
+    let mut levels: Vec<usize> = Vec::new();
+
+    for (level, text, id) in toc {
+        while levels.last().map_or(false, |x| x > level) {
+            html.push_str("</li></ul>");
+            levels.pop();
+        }
+
+        if levels.last().map_or(true, |x| x < level) {
+            html.push_str("<ul>");
+            levels.push(*level);
+        } else {
+            html.push_str("</li>");
+        }
 
-        Ok(template)
+        html.push_str(&format!(
+            r##"<li><a href="#{}">{}</a>"##,
+            id,
+            escape::escape_html(text)
+        ));
     }
+
+    for _ in levels {
+        html.push_str("</li></ul>");
+    }
+
+    html
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        HtmlOutputResult, HtmlRuntimeConfig, HtmlTemplateConfig, HtmlWikiConfig,
+        HtmlOutputResult, HtmlPageInfo, HtmlRuntimeConfig, HtmlTemplateConfig,
+        HtmlWikiConfig,
     };
     use chrono::NaiveDate;
     use std::path::PathBuf;
@@ -241,6 +378,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "to", "a", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -257,6 +395,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "to", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -273,6 +412,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -294,6 +434,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "to", "a", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -365,4 +506,71 @@ mod tests {
         let result = output.to_html_page(config).unwrap();
         assert_eq!(result, "<html>some output content</html>");
     }
+
+    #[test]
+    fn to_html_page_should_support_conditionals_and_loops_over_pages() {
+        let output = TestOutput(|f| {
+            f.metadata_mut()
+                .insert("draft", crate::wiki::MetadataValue::Bool(true));
+            f.insert_tag("one");
+            Ok(())
+        });
+        let template = HtmlTemplateConfig::from_text(
+            "{% if metadata.draft %}draft {% endif %}{{ tags | join }}\
+             {% for p in pages %} <a href=\"{{ p.url }}\">{{ p.title }}</a>\
+             {% if p.is_current %}*{% endif %}{% endfor %}",
+        );
+        let page = |path: &[&str]| HtmlPageInfo {
+            path: path.iter().collect(),
+            ..Default::default()
+        };
+        let config = HtmlConfig {
+            template,
+            wikis: vec![HtmlWikiConfig {
+                path: ["some", "path"].iter().collect(),
+                ..Default::default()
+            }],
+            runtime: HtmlRuntimeConfig {
+                wiki_index: Some(0),
+                page: ["some", "path", "to", "file.wiki"].iter().collect(),
+                pages: vec![
+                    page(&["some", "path", "index.wiki"]),
+                    page(&["some", "path", "to", "file.wiki"]),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = output.to_html_page(config).unwrap();
+        assert_eq!(
+            result,
+            r#"draft one <a href="../index.html">index</a> <a href="../to/file.html">file</a>*"#
+        );
+    }
+
+    #[test]
+    fn to_html_page_should_provide_nested_toc_of_headers() {
+        let output = TestOutput(|f| {
+            f.push_toc_entry(1, "One", "One");
+            f.push_toc_entry(2, "Two & more", "One-Two & more");
+            f.push_toc_entry(1, "Three", "Three");
+            Ok(())
+        });
+        let template = HtmlTemplateConfig::from_text("{{ toc }}");
+        let config = HtmlConfig {
+            template,
+            ..Default::default()
+        };
+
+        let result = output.to_html_page(config).unwrap();
+        assert_eq!(
+            result,
+            concat!(
+                r##"<ul><li><a href="#One">One</a>"##,
+                r##"<ul><li><a href="#One-Two & more">Two &amp; more</a>"##,
+                r##"</li></ul></li><li><a href="#Three">Three</a></li></ul>"##,
+            )
+        );
+    }
 }
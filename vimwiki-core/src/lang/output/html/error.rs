@@ -1,4 +1,4 @@
-use super::LinkResolutionError;
+use super::{HtmlTemplateError, LinkResolutionError};
 use derive_more::{Display, Error};
 use uriparse::{PathError, RelativeReferenceError, URIReferenceError};
 
@@ -42,6 +42,11 @@ pub enum HtmlOutputError {
         source: std::io::Error,
     },
 
+    Template {
+        #[error(source)]
+        source: HtmlTemplateError,
+    },
+
     Fmt {
         #[error(source)]
         source: std::fmt::Error,
@@ -83,3 +88,9 @@ impl From<syntect::LoadingError> for HtmlOutputError {
         Self::SyntaxOrThemeNotLoaded { source }
     }
 }
+
+impl From<HtmlTemplateError> for HtmlOutputError {
+    fn from(source: HtmlTemplateError) -> Self {
+        Self::Template { source }
+    }
+}
@@ -1,4 +1,5 @@
 use super::{HtmlConfig, HtmlOutputError, OutputFormatter};
+use crate::wiki::PageMetadata;
 use chrono::NaiveDate;
 use std::{
     borrow::Cow,
@@ -29,6 +30,16 @@ pub struct HtmlFormatter {
     /// Contains the template to be used for the page
     template: Option<PathBuf>,
 
+    /// Contains the metadata described by the placeholders of the page
+    metadata: PageMetadata,
+
+    /// Contains the tags found within the page in the order first seen
+    tags: Vec<String>,
+
+    /// Contains the level, text, and id of each header of the page, which
+    /// excludes the table of contents header
+    toc: Vec<(usize, String, String)>,
+
     /// Contains the content to be injected into a template
     content: String,
 }
@@ -52,6 +63,9 @@ impl HtmlFormatter {
             title: None,
             date: None,
             template: None,
+            metadata: PageMetadata::new(),
+            tags: Vec::new(),
+            toc: Vec::new(),
             content: String::new(),
         }
     }
@@ -144,6 +158,42 @@ impl HtmlFormatter {
         self.template.take()
     }
 
+    pub fn metadata(&self) -> &PageMetadata {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut PageMetadata {
+        &mut self.metadata
+    }
+
+    /// Adds a tag to those found within the page if not already added
+    pub fn insert_tag(&mut self, tag: impl Into<String>) {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Adds a header to the table of contents of the page
+    pub fn push_toc_entry(
+        &mut self,
+        level: usize,
+        text: impl Into<String>,
+        id: impl Into<String>,
+    ) {
+        self.toc.push((level, text.into(), id.into()));
+    }
+
+    /// Returns the level, text, and id of each header in the table of
+    /// contents of the page
+    pub fn toc(&self) -> &[(usize, String, String)] {
+        &self.toc
+    }
+
     pub fn get_content(&self) -> &str {
         self.content.as_str()
     }
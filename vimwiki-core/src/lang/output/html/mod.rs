@@ -10,12 +10,26 @@ pub use convert::{ToHtmlPage, ToHtmlString};
 mod error;
 pub use error::{HtmlOutputError, HtmlOutputResult};
 
+mod search;
+pub use search::{HtmlSearchDocument, HtmlSearchIndex, HtmlSearchPage};
+
+mod site;
+pub use site::{
+    find_diary_pages, find_page_assets, HtmlSiteIndex, HtmlSitemap,
+};
+
+mod template;
+pub use template::{HtmlTemplate, HtmlTemplateError, HtmlTemplateValue};
+
 mod utils;
 pub use utils::LinkResolutionError;
 
-use crate::lang::{
-    elements::*,
-    output::{Output, OutputFormatter},
+use crate::{
+    lang::{
+        elements::*,
+        output::{Output, OutputFormatter},
+    },
+    wiki::{TagIndex, TagLocation},
 };
 use lazy_static::lazy_static;
 use std::{borrow::Cow, collections::HashMap, fmt::Write};
@@ -30,11 +44,11 @@ use voca_rs::escape;
 
 lazy_static! {
     /// Default syntax set for languages
-    static ref DEFAULT_SYNTAX_SET: SyntaxSet =
+    pub(crate) static ref DEFAULT_SYNTAX_SET: SyntaxSet =
         SyntaxSet::load_defaults_nonewlines();
 
     /// Default theme highlight set for languages
-    static ref DEFAULT_THEME_SET: ThemeSet = ThemeSet::load_defaults();
+    pub(crate) static ref DEFAULT_THEME_SET: ThemeSet = ThemeSet::load_defaults();
 }
 
 impl<'a> Output<HtmlFormatter> for Page<'a> {
@@ -251,6 +265,11 @@ impl<'a> Output<HtmlFormatter> for Header<'a> {
             //       we are a top-level header then this would be the same
             //       as unique_header_id
             write!(f, r##"<a href="#{}">"##, unique_complete_header_id)?;
+            f.push_toc_entry(
+                self.level,
+                raw_content.trim(),
+                unique_complete_header_id.to_string(),
+            );
             self.content.fmt(f)?;
             write!(f, "</a></h{}>", self.level)?;
 
@@ -450,6 +469,10 @@ impl<'a> Output<HtmlFormatter> for Placeholder<'a> {
             _ => {}
         }
 
+        // NOTE: Placeholders with invalid values are left out of the
+        //       metadata rather than failing the entire page
+        let _ = f.metadata_mut().insert_placeholder(self);
+
         Ok(())
     }
 }
@@ -1145,6 +1168,7 @@ impl<'a> Output<HtmlFormatter> for Tags<'a> {
     /// ```
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
         for tag in self {
+            f.insert_tag(tag.as_str());
             let id = utils::normalize_id(tag.as_str());
             let unique_id = f.ensure_unique_id(&id);
 
@@ -1254,6 +1278,93 @@ impl<'a> Output<HtmlFormatter> for MultiLineComment<'a> {
     }
 }
 
+impl Output<HtmlFormatter> for TagIndex {
+    /// Writes an index of tags in HTML, where each tag is followed by links
+    /// to the locations within wikis where the tag appears
+    ///
+    /// ### Example
+    ///
+    /// ```html
+    /// <div class="tags">
+    /// <h2 id="tag1">tag1</h2>
+    /// <ul>
+    /// <li><a href="page.html#tag1">page</a></li>
+    /// <li><a href="other/page.html#tag1">other/page#Header 1</a></li>
+    /// </ul>
+    /// </div>
+    /// ```
+    ///
+    /// Locations that are not within a configured wiki are written as plain
+    /// paths without links
+    fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
+        writeln!(f, "<div class=\"tags\">")?;
+        for (tag, locations) in self.iter() {
+            let id = utils::normalize_id(tag.as_str());
+            let unique_id = f.ensure_unique_id(&id);
+            writeln!(
+                f,
+                "<h2 id=\"{}\">{}</h2>",
+                unique_id,
+                escape::escape_html(tag.as_str())
+            )?;
+
+            writeln!(f, "<ul>")?;
+            for location in locations {
+                write!(f, "<li>")?;
+                match make_tag_location_link(f.config(), &id, location) {
+                    Some(link) => link.fmt(f)?,
+                    None => write!(
+                        f,
+                        "{}",
+                        escape::escape_html(&location.path.to_string_lossy())
+                    )?,
+                }
+                writeln!(f, "</li>")?;
+            }
+            writeln!(f, "</ul>")?;
+        }
+        write!(f, "</div>")?;
+
+        Ok(())
+    }
+}
+
+/// Produces a link to the location of a tag within a wiki, or none if the
+/// location is not within any configured wiki
+fn make_tag_location_link(
+    config: &HtmlConfig,
+    id: &str,
+    location: &TagLocation,
+) -> Option<Link<'static>> {
+    let index = config.find_wiki_index_by_path(&location.path)?;
+    let path = config
+        .find_wiki_by_index(index)?
+        .path_within(&location.path)?
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<String>>()
+        .join("/");
+
+    let description = match location.header.as_ref() {
+        Some(header) => format!("{}#{}", path, header),
+        None => path.to_string(),
+    };
+
+    let uri = format!(
+        "{}#{}",
+        LinkData::encode_uri(&path),
+        LinkData::encode_uri(id)
+    );
+    Link::try_new_indexed_interwiki_link(
+        index as u32,
+        uri.as_str(),
+        Description::from(description),
+    )
+    .ok()
+    .map(Link::into_owned)
+}
+
 fn build_complete_id(
     f: &mut HtmlFormatter,
     max_level: usize,
@@ -1308,6 +1419,7 @@ mod tests {
                     wiki.as_ref(),
                     page.as_ref(),
                 ]),
+                ..Default::default()
             },
             ..Default::default()
         }
@@ -2981,6 +3093,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tag_index_should_output_links_to_each_tag_location() {
+        let mut index = TagIndex::new();
+        index.update_page(
+            &make_path_from_pieces(vec!["wiki", "wiki", "a.wiki"]),
+            &crate::Language::from_vimwiki_str(":One:\n= Header =\n:two:\n")
+                .parse::<Page>()
+                .unwrap(),
+        );
+        index.update_page(
+            &make_path_from_pieces(vec!["other", "b.wiki"]),
+            &crate::Language::from_vimwiki_str(":two:\n")
+                .parse::<Page>()
+                .unwrap(),
+        );
+
+        let mut f = HtmlFormatter::new(test_html_config("wiki", "index.wiki"));
+        index.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {r#"
+                <div class="tags">
+                <h2 id="one">One</h2>
+                <ul>
+                <li><a href="a.html#one">a</a></li>
+                </ul>
+                <h2 id="two">two</h2>
+                <ul>
+                <li><a href="a.html#two">a#Header</a></li>
+                <li>/other/b.wiki</li>
+                </ul>
+                </div>"#}
+        );
+    }
+
     #[test]
     fn code_inline_should_output_code_tag() {
         let code_inline = CodeInline::from("some code");
@@ -0,0 +1,310 @@
+use super::{
+    site::{make_page_title, make_root_path},
+    HtmlFormatter, HtmlOutputResult, HtmlPageInfo, HtmlWikiConfig, Output,
+};
+use crate::{
+    utils,
+    wiki::{make_segments, PageMetadata, SearchField, WikiPage},
+};
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt::Write};
+use voca_rs::escape;
+
+/// Script of the search page that loads the search index and ranks its
+/// documents against the query as it is typed
+const SEARCH_SCRIPT: &str = r#"(function () {
+  var el = document.getElementById("search");
+  var input = el.querySelector("input");
+  var results = el.querySelector(".search-results");
+  var weights = { title: 10, headers: 3, body: 1 };
+  var docs = [];
+
+  var tokenize = function (text) {
+    return String(text).toLowerCase().split(/[^\p{L}\p{N}]+/u).filter(Boolean);
+  };
+
+  var score = function (doc, terms) {
+    var total = 0;
+    for (var i = 0; i < terms.length; i++) {
+      var found = 0;
+      for (var field in weights) {
+        doc.tokens[field].forEach(function (word) {
+          if (word.indexOf(terms[i]) === 0) found += weights[field];
+        });
+      }
+      if (!found) return 0;
+      total += found;
+    }
+    return total;
+  };
+
+  var render = function () {
+    var terms = tokenize(input.value);
+    results.innerHTML = "";
+    docs.map(function (doc) { return [score(doc, terms), doc]; })
+      .filter(function (x) { return x[0] > 0; })
+      .sort(function (a, b) { return b[0] - a[0]; })
+      .forEach(function (x) {
+        var li = document.createElement("li");
+        var a = document.createElement("a");
+        var p = document.createElement("p");
+        a.href = el.dataset.root + x[1].url;
+        a.textContent = x[1].title;
+        p.textContent = x[1].body.slice(0, 200);
+        li.appendChild(a);
+        li.appendChild(p);
+        results.appendChild(li);
+      });
+  };
+
+  fetch(el.dataset.root + el.dataset.index)
+    .then(function (res) { return res.json(); })
+    .then(function (index) {
+      docs = index.documents.map(function (doc) {
+        doc.tokens = {
+          title: tokenize(doc.title),
+          headers: tokenize(doc.headers.join(" ")),
+          body: tokenize(doc.body)
+        };
+        return doc;
+      });
+      input.value = new URLSearchParams(location.search).get("q") || "";
+      input.addEventListener("input", render);
+      render();
+    });
+})();"#;
+
+/// Represents a single page within a search index
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct HtmlSearchDocument {
+    /// Url of the page relative to the root of the wiki's html output
+    pub url: String,
+
+    /// Title of the page
+    pub title: String,
+
+    /// Plain text of each header within the page in document order
+    pub headers: Vec<String>,
+
+    /// Plain text of the rest of the page's content
+    pub body: String,
+}
+
+/// Represents an index of the plain text of each page of a wiki that can be
+/// searched client-side
+///
+/// Serializes as the fields to index, the field used to reference each
+/// document, and the documents themselves, which can be added as-is to a
+/// lunr or elasticlunr index
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HtmlSearchIndex {
+    #[serde(rename = "ref")]
+    reference: String,
+    fields: Vec<String>,
+    documents: Vec<HtmlSearchDocument>,
+}
+
+impl Default for HtmlSearchIndex {
+    fn default() -> Self {
+        Self {
+            reference: String::from("url"),
+            fields: vec![
+                String::from("title"),
+                String::from("headers"),
+                String::from("body"),
+            ],
+            documents: Vec::new(),
+        }
+    }
+}
+
+impl HtmlSearchIndex {
+    /// Creates a search index for the given pages of a wiki, skipping any
+    /// page that is not within the wiki
+    pub fn from_pages(wiki: &HtmlWikiConfig, pages: &[WikiPage<'_>]) -> Self {
+        let mut documents = pages
+            .iter()
+            .filter_map(|page| {
+                let path = wiki.path_within(&page.path)?;
+                let title = make_page_title(&HtmlPageInfo {
+                    path: page.path.to_path_buf(),
+                    metadata: PageMetadata::from_page(&page.page),
+                });
+
+                let mut headers = Vec::new();
+                let mut body = Vec::new();
+                for (field, _, text) in
+                    make_segments(&page.page, &mut BTreeSet::new())
+                {
+                    let text = text.trim().to_string();
+                    if text.is_empty() {
+                        continue;
+                    } else if field == SearchField::Header {
+                        headers.push(text);
+                    } else {
+                        body.push(text);
+                    }
+                }
+
+                Some(HtmlSearchDocument {
+                    url: utils::path_to_uri_string(
+                        &path.with_extension("html"),
+                    )
+                    .replace(' ', "%20"),
+                    title,
+                    headers,
+                    body: body.join("\n"),
+                })
+            })
+            .collect::<Vec<HtmlSearchDocument>>();
+        documents.sort_by(|a, b| a.url.cmp(&b.url));
+
+        Self {
+            documents,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the documents of the index ordered by url
+    pub fn documents(&self) -> &[HtmlSearchDocument] {
+        &self.documents
+    }
+
+    /// Returns true if the index has no documents
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Returns the total number of documents
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+}
+
+/// Represents the page of a generated site that searches the pages of the
+/// wiki using a search index found at the root of the wiki's html output
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HtmlSearchPage {
+    /// Title of the page
+    pub title: String,
+
+    /// Name of the search index file relative to the root of the wiki's
+    /// html output
+    pub index_name: String,
+}
+
+impl Output<HtmlFormatter> for HtmlSearchPage {
+    /// Writes a search form alongside the script that fills in the results
+    ///
+    /// ### Example
+    ///
+    /// ```html
+    /// <div id="search" class="search" data-root="" data-index="search_index.json">
+    /// <form class="search-form" method="get">
+    /// <input type="search" name="q" placeholder="Search" autofocus>
+    /// </form>
+    /// <ul class="search-results"></ul>
+    /// </div>
+    /// <script>...</script>
+    /// ```
+    fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
+        f.set_title(&self.title);
+
+        let root_path = make_root_path(f.config());
+        writeln!(
+            f,
+            "<div id=\"search\" class=\"search\" data-root=\"{}\" data-index=\"{}\">",
+            escape::escape_html(&root_path),
+            escape::escape_html(&self.index_name),
+        )?;
+        writeln!(f, "<form class=\"search-form\" method=\"get\">")?;
+        writeln!(
+            f,
+            "<input type=\"search\" name=\"q\" placeholder=\"{}\" autofocus>",
+            escape::escape_html(&self.title),
+        )?;
+        writeln!(f, "</form>")?;
+        writeln!(f, "<ul class=\"search-results\"></ul>")?;
+        writeln!(f, "</div>")?;
+        write!(f, "<script>\n{}\n</script>", SEARCH_SCRIPT)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page};
+    use std::path::PathBuf;
+
+    fn make_page(path: &str, text: &str) -> WikiPage<'static> {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        WikiPage::new(path, page.into_owned())
+    }
+
+    #[test]
+    fn from_pages_should_collect_plain_text_of_pages_within_wiki() {
+        let wiki = HtmlWikiConfig {
+            path: PathBuf::from("/wiki"),
+            ..Default::default()
+        };
+        let index = HtmlSearchIndex::from_pages(
+            &wiki,
+            &[
+                make_page(
+                    "/wiki/sub/my page.wiki",
+                    "%title Some Page\n= Header =\nSome *bold* text.\n\n\
+                     - an item\n\n    A quote.\n",
+                ),
+                make_page("/wiki/index.wiki", "Hello"),
+                make_page("/other/outside.wiki", "Elsewhere"),
+            ],
+        );
+
+        assert_eq!(
+            index.documents(),
+            &[
+                HtmlSearchDocument {
+                    url: String::from("index.html"),
+                    title: String::from("index"),
+                    headers: Vec::new(),
+                    body: String::from("Hello"),
+                },
+                HtmlSearchDocument {
+                    url: String::from("sub/my%20page.html"),
+                    title: String::from("Some Page"),
+                    headers: vec![String::from("Header")],
+                    body: String::from("Some bold text.\nan item\nA quote."),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn search_index_should_serialize_in_lunr_compatible_form() {
+        let index = HtmlSearchIndex {
+            documents: vec![HtmlSearchDocument {
+                url: String::from("index.html"),
+                title: String::from("index"),
+                headers: vec![String::from("Header")],
+                body: String::from("Hello"),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&index).unwrap(),
+            serde_json::json!({
+                "ref": "url",
+                "fields": ["title", "headers", "body"],
+                "documents": [{
+                    "url": "index.html",
+                    "title": "index",
+                    "headers": ["Header"],
+                    "body": "Hello",
+                }],
+            })
+        );
+    }
+}
@@ -0,0 +1,460 @@
+use super::{
+    utils::make_path_relative, HtmlConfig, HtmlFormatter, HtmlOutputResult,
+    HtmlPageInfo, HtmlPageTree, HtmlWikiConfig, Output,
+};
+use crate::{
+    lang::elements::Link,
+    utils::{self, normalize_path},
+    wiki::WikiPage,
+};
+use chrono::NaiveDate;
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+use uriparse::Scheme;
+use voca_rs::escape;
+
+/// Represents the page of a generated site that lists every page of the wiki
+/// containing the active page, arranged as a tree by directory
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HtmlSiteIndex {
+    /// Title of the page
+    pub title: String,
+}
+
+impl Output<HtmlFormatter> for HtmlSiteIndex {
+    /// Writes the pages of the wiki in HTML, where each directory is a
+    /// nested list
+    ///
+    /// ### Example
+    ///
+    /// ```html
+    /// <div class="site-index">
+    /// <ul class="tree">
+    /// <li><a href="index.html" class="current">index</a></li>
+    /// <li>sub<ul><li><a href="sub/page.html">Some Page</a></li></ul></li>
+    /// </ul>
+    /// </div>
+    /// ```
+    fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
+        f.set_title(&self.title);
+
+        let root_path = make_root_path(f.config());
+        let tree = make_page_tree_html(f.config(), &root_path);
+        writeln!(f, "<div class=\"site-index\">")?;
+        writeln!(f, "{}", tree)?;
+        write!(f, "</div>")?;
+
+        Ok(())
+    }
+}
+
+/// Represents a sitemap listing the url of each page of a wiki alongside
+/// the date the page was last modified
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HtmlSitemap {
+    entries: Vec<(String, Option<NaiveDate>)>,
+}
+
+impl HtmlSitemap {
+    /// Creates a sitemap for the given pages of a wiki, where the url of
+    /// each page is formed from the base url of links and the page's path
+    /// within the wiki
+    pub fn from_pages(
+        config: &HtmlConfig,
+        wiki: &HtmlWikiConfig,
+        pages: &[HtmlPageInfo],
+    ) -> Self {
+        let base_url = config.link.base_url.to_string();
+        let base_url = base_url.trim_end_matches('/');
+
+        let mut entries = pages
+            .iter()
+            .filter_map(|page| {
+                let path = wiki.path_within(&page.path)?;
+                let url = format!(
+                    "{}/{}",
+                    base_url,
+                    utils::path_to_uri_string(&path.with_extension("html"))
+                );
+                Some((url, page.metadata.date()))
+            })
+            .collect::<Vec<(String, Option<NaiveDate>)>>();
+        entries.sort();
+
+        Self { entries }
+    }
+
+    /// Returns true if the sitemap has no urls
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total number of urls
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Produces the xml of the sitemap following the sitemaps.org protocol
+    pub fn to_xml_string(&self) -> String {
+        let mut xml = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "\n",
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+            "\n",
+        ));
+
+        for (url, lastmod) in self.entries.iter() {
+            xml.push_str("<url><loc>");
+            xml.push_str(&escape::escape_html(&url.replace(' ', "%20")));
+            xml.push_str("</loc>");
+            if let Some(lastmod) = lastmod {
+                xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod));
+            }
+            xml.push_str("</url>\n");
+        }
+
+        xml.push_str("</urlset>\n");
+        xml
+    }
+}
+
+/// Finds the local files within the wiki that are referenced by a page,
+/// such as transcluded images, returning the absolute path of each file
+pub fn find_page_assets(
+    wiki: &HtmlWikiConfig,
+    page: &WikiPage<'_>,
+) -> Vec<PathBuf> {
+    let root = normalize_path(&wiki.path);
+
+    let mut assets = page
+        .to_links()
+        .into_iter()
+        .filter_map(|link| match link.into_inner() {
+            Link::Transclusion { data } if data.is_local() => {
+                let path = data.to_path_buf();
+
+                // Links with the file: scheme are absolute paths on the
+                // local machine while others are absolute from the root of
+                // the wiki or relative to the page
+                let path = if matches!(data.scheme(), Some(Scheme::File)) {
+                    path
+                } else if path.has_root() {
+                    wiki.path.join(make_path_relative(path))
+                } else {
+                    page.path
+                        .parent()
+                        .map(Path::to_path_buf)
+                        .unwrap_or_default()
+                        .join(path)
+                };
+
+                Some(normalize_path(&path)).filter(|x| x.starts_with(&root))
+            }
+            _ => None,
+        })
+        .collect::<Vec<PathBuf>>();
+    assets.sort();
+    assets.dedup();
+    assets
+}
+
+/// Produces the relative path from the active page to the root of its wiki
+/// in the form of a uri ending with `/`, or an empty string if the page is
+/// at the root
+pub(crate) fn make_root_path(config: &HtmlConfig) -> String {
+    let path_str = utils::path_to_uri_string(
+        config.to_active_page_path_to_wiki_root().as_path(),
+    );
+
+    if path_str.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", path_str)
+    }
+}
+
+/// Produces the path of a page within the wiki of the active page (without
+/// an extension) alongside the url to the page relative to the active page
+pub(crate) fn make_page_path_and_url(
+    config: &HtmlConfig,
+    root_path: &str,
+    path: &Path,
+) -> (String, String) {
+    let wiki_path = config
+        .to_current_wiki()
+        .path_within(path)
+        .unwrap_or(path)
+        .with_extension("");
+    let url = format!(
+        "{}{}",
+        root_path,
+        utils::path_to_uri_string(&wiki_path.with_extension("html"))
+    );
+
+    (utils::path_to_uri_string(&wiki_path), url)
+}
+
+/// Produces the title of a page from its metadata, falling back to the name
+/// of its file
+pub(crate) fn make_page_title(page: &HtmlPageInfo) -> String {
+    page.metadata
+        .title()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| {
+            page.path
+                .file_stem()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+}
+
+/// Finds the diary pages among the given pages of a wiki ordered by date,
+/// where each path is normalized so it can be compared with the active page
+pub fn find_diary_pages(
+    wiki: &HtmlWikiConfig,
+    pages: &[HtmlPageInfo],
+) -> Vec<HtmlPageInfo> {
+    let diary_dir = normalize_path(&wiki.path.join(&wiki.diary_rel_path));
+
+    let mut entries = pages
+        .iter()
+        .filter_map(|page| {
+            let path = normalize_path(&page.path);
+            if path.parent() != Some(diary_dir.as_path()) {
+                return None;
+            }
+
+            let stem = path.file_stem()?.to_str()?;
+            let date = NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()?;
+            Some((
+                date,
+                HtmlPageInfo {
+                    path,
+                    metadata: page.metadata.clone(),
+                },
+            ))
+        })
+        .collect::<Vec<(NaiveDate, HtmlPageInfo)>>();
+    entries.sort_by_key(|(date, _)| *date);
+
+    entries.into_iter().map(|(_, page)| page).collect()
+}
+
+/// Finds the diary pages of the active page's wiki that come immediately
+/// before and after the active page by date, or nothing if the active page
+/// is not a diary page
+pub(crate) fn find_diary_neighbors(
+    config: &HtmlConfig,
+) -> (Option<&HtmlPageInfo>, Option<&HtmlPageInfo>) {
+    let diary = &config.runtime.diary;
+    let active = normalize_path(config.active_page());
+    match diary.iter().position(|page| page.path == active) {
+        Some(idx) => (
+            idx.checked_sub(1).map(|idx| &diary[idx]),
+            diary.get(idx + 1),
+        ),
+        None => (None, None),
+    }
+}
+
+/// Writes the pages of the active page's wiki as nested lists by directory
+/// with links relative to the active page
+pub(crate) fn make_page_tree_html(
+    config: &HtmlConfig,
+    root_path: &str,
+) -> String {
+    let mut html = String::new();
+    write_page_tree(config, root_path, &config.runtime.tree, true, &mut html);
+    html
+}
+
+fn write_page_tree(
+    config: &HtmlConfig,
+    root_path: &str,
+    tree: &HtmlPageTree,
+    is_root: bool,
+    html: &mut String,
+) {
+    html.push_str(if is_root {
+        r#"<ul class="tree">"#
+    } else {
+        "<ul>"
+    });
+    for (name, node) in tree.children.iter() {
+        html.push_str("<li>");
+        match node.page.as_ref() {
+            Some(page) => {
+                let (_, url) =
+                    make_page_path_and_url(config, root_path, &page.path);
+                let class = if page.path == config.active_page() {
+                    r#" class="current""#
+                } else {
+                    ""
+                };
+                html.push_str(&format!(
+                    r#"<a href="{}"{}>{}</a>"#,
+                    url,
+                    class,
+                    escape::escape_html(&make_page_title(page)),
+                ));
+            }
+            None => html.push_str(&escape::escape_html(name)),
+        }
+
+        if !node.children.is_empty() {
+            write_page_tree(config, root_path, node, false, html);
+        }
+        html.push_str("</li>");
+    }
+    html.push_str("</ul>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        HtmlRuntimeConfig, Language, MetadataValue, Page, PageMetadata,
+    };
+    use std::convert::TryFrom;
+
+    fn page(path: &str, title: Option<&str>) -> HtmlPageInfo {
+        let mut metadata = PageMetadata::new();
+        if let Some(title) = title {
+            metadata.insert("title", MetadataValue::Text(title.to_string()));
+        }
+
+        HtmlPageInfo {
+            path: Path::new("/wiki").join(path),
+            metadata,
+        }
+    }
+
+    fn make_config(active: &str, pages: Vec<HtmlPageInfo>) -> HtmlConfig {
+        let wiki = HtmlWikiConfig {
+            path: PathBuf::from("/wiki"),
+            path_html: PathBuf::from("/html"),
+            ..Default::default()
+        };
+        HtmlConfig {
+            runtime: HtmlRuntimeConfig {
+                wiki_index: Some(0),
+                page: Path::new("/wiki").join(active),
+                tree: HtmlPageTree::from_pages(&wiki, &pages),
+                diary: find_diary_pages(&wiki, &pages),
+                pages,
+                ..Default::default()
+            },
+            wikis: vec![wiki],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn make_page_tree_html_should_nest_pages_by_directory() {
+        let config = make_config(
+            "sub/b.wiki",
+            vec![
+                page("index.wiki", None),
+                page("sub/b.wiki", Some("B & more")),
+                page("sub.wiki", Some("Sub")),
+                page("other/c.wiki", None),
+            ],
+        );
+
+        assert_eq!(
+            make_page_tree_html(&config, "../"),
+            concat!(
+                r#"<ul class="tree">"#,
+                r#"<li><a href="../index.html">index</a></li>"#,
+                r#"<li>other<ul><li><a href="../other/c.html">c</a></li></ul></li>"#,
+                r#"<li><a href="../sub.html">Sub</a><ul>"#,
+                r#"<li><a href="../sub/b.html" class="current">B &amp; more</a></li>"#,
+                r#"</ul></li></ul>"#,
+            )
+        );
+    }
+
+    #[test]
+    fn find_diary_neighbors_should_order_diary_pages_by_date() {
+        let pages = vec![
+            page("diary/2021-06-03.wiki", None),
+            page("diary/2021-06-01.wiki", None),
+            page("diary/diary.wiki", None),
+            page("diary/2021-06-02.wiki", None),
+        ];
+
+        let config = make_config("diary/2021-06-02.wiki", pages.clone());
+        let (prev, next) = find_diary_neighbors(&config);
+        assert_eq!(prev, Some(&pages[1]));
+        assert_eq!(next, Some(&pages[0]));
+
+        let config = make_config("diary/2021-06-01.wiki", pages.clone());
+        assert_eq!(find_diary_neighbors(&config), (None, Some(&pages[3])));
+
+        let config = make_config("diary/diary.wiki", pages);
+        assert_eq!(find_diary_neighbors(&config), (None, None));
+    }
+
+    #[test]
+    fn sitemap_should_list_absolute_urls_of_pages() {
+        let mut config = make_config("index.wiki", Vec::new());
+        config.link.base_url =
+            uriparse::URI::try_from("https://example.com/wiki/")
+                .unwrap()
+                .into_owned();
+
+        let mut dated = page("my page.wiki", None);
+        dated.metadata.insert(
+            "date",
+            MetadataValue::Date(NaiveDate::from_ymd(2021, 6, 1)),
+        );
+        let sitemap = HtmlSitemap::from_pages(
+            &config,
+            &config.wikis[0],
+            &[
+                page("index.wiki", None),
+                dated,
+                page("/other/outside.wiki", None),
+            ],
+        );
+
+        assert_eq!(
+            sitemap.to_xml_string(),
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                "\n",
+                r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+                "\n",
+                "<url><loc>https://example.com/wiki/index.html</loc></url>\n",
+                "<url><loc>https://example.com/wiki/my%20page.html</loc>",
+                "<lastmod>2021-06-01</lastmod></url>\n",
+                "</urlset>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn find_page_assets_should_return_local_transclusions_within_wiki() {
+        let page: Page = Language::from_vimwiki_str(
+            "{{img/a.png}} {{/img/b.png}} {{../../c.png}} \
+             {{https://example.com/d.png}} [[img/e.png]] {{img/a.png}}",
+        )
+        .parse()
+        .unwrap();
+        let page = WikiPage::new("/wiki/sub/page.wiki", page);
+        let wiki = HtmlWikiConfig {
+            path: PathBuf::from("/wiki"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            find_page_assets(&wiki, &page),
+            vec![
+                PathBuf::from("/wiki/img/b.png"),
+                PathBuf::from("/wiki/sub/img/a.png"),
+            ]
+        );
+    }
+}
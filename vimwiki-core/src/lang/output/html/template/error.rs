@@ -0,0 +1,32 @@
+use derive_more::{Display, Error};
+
+/// Represents a problem encountered when parsing or rendering a template
+#[derive(Debug, Display, Error)]
+pub enum HtmlTemplateError {
+    /// Represents a template that is not written correctly
+    #[display(fmt = "Line {}: {}", line, message)]
+    Syntax {
+        #[error(not(source))]
+        line: usize,
+        message: String,
+    },
+
+    /// Represents a filter that does not exist or was given the wrong
+    /// arguments
+    #[display(fmt = "Unknown filter: {}", _0)]
+    UnknownFilter(#[error(not(source))] String),
+
+    /// Represents a template included by another template that could not be
+    /// loaded
+    #[display(fmt = "Failed to include {}: {}", name, source)]
+    IncludeNotLoaded {
+        name: String,
+        #[error(source)]
+        source: std::io::Error,
+    },
+
+    /// Represents templates that include each other too many times, which is
+    /// usually the case when templates include themselves
+    #[display(fmt = "Includes nested too deeply at {}", _0)]
+    IncludeTooDeep(#[error(not(source))] String),
+}
@@ -0,0 +1,411 @@
+mod error;
+mod parser;
+mod value;
+
+pub use error::HtmlTemplateError;
+pub use value::HtmlTemplateValue;
+
+use parser::{BinaryOp, Expr, Node};
+use std::{borrow::Cow, collections::BTreeMap, io};
+use voca_rs::escape;
+
+/// Maximum number of templates that can be included within one another
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Represents a parsed template used to produce html pages
+///
+/// Supports the following syntax:
+///
+/// * `{{ expr }}` writes the value of an expression, escaping it unless it
+///   is html or passed through the `safe` filter
+/// * `{% if expr %}...{% elif expr %}...{% else %}...{% endif %}`
+/// * `{% for x in expr %}...{% else %}...{% endfor %}`, where maps can be
+///   iterated with `{% for key, value in expr %}` and `loop.index`,
+///   `loop.index0`, `loop.first`, `loop.last`, and `loop.length` are
+///   available within the loop
+/// * `{% include "name" %}` renders another template in place
+/// * `{# comment #}` is dropped
+/// * `%title%`, `%content%`, and the other vimwiki placeholders are written
+///   as-is so existing vimwiki templates continue to work
+///
+/// Expressions support dotted paths (`page.title`), text and integer
+/// literals, `true`, `false`, `none`, `and`, `or`, `not`, `==`, `!=`, `in`,
+/// `not in`, and filters (`x | join(", ")`) that include `safe`, `escape`,
+/// `upper`, `lower`, `length`, `first`, `last`, `join`, and `default`. Tags
+/// starting or ending with `-` (`{%- if x -%}`) trim whitespace on that side
+#[derive(Clone, Debug, PartialEq)]
+pub struct HtmlTemplate {
+    nodes: Vec<Node>,
+}
+
+impl HtmlTemplate {
+    /// Parses the text of a template
+    pub fn parse(text: &str) -> Result<Self, HtmlTemplateError> {
+        Ok(Self {
+            nodes: parser::parse(text)?,
+        })
+    }
+
+    /// Renders the template using the given context, which is expected to
+    /// be a map, and loader that provides the text of included templates
+    pub fn render<F>(
+        &self,
+        context: &HtmlTemplateValue,
+        load: F,
+    ) -> Result<String, HtmlTemplateError>
+    where
+        F: Fn(&str) -> io::Result<String>,
+    {
+        let mut renderer = Renderer {
+            context,
+            scopes: Vec::new(),
+            load: &load,
+            includes: Vec::new(),
+            output: String::new(),
+        };
+        renderer.render_nodes(&self.nodes)?;
+        Ok(renderer.output)
+    }
+}
+
+struct Renderer<'a> {
+    context: &'a HtmlTemplateValue,
+    scopes: Vec<BTreeMap<String, HtmlTemplateValue>>,
+    load: &'a dyn Fn(&str) -> io::Result<String>,
+    includes: Vec<String>,
+    output: String,
+}
+
+impl<'a> Renderer<'a> {
+    fn render_nodes(
+        &mut self,
+        nodes: &[Node],
+    ) -> Result<(), HtmlTemplateError> {
+        for node in nodes {
+            self.render_node(node)?;
+        }
+        Ok(())
+    }
+
+    fn render_node(&mut self, node: &Node) -> Result<(), HtmlTemplateError> {
+        match node {
+            Node::Text(x) => self.output.push_str(x),
+            Node::Expr(expr) => {
+                let value = self.eval(expr);
+                self.output.push_str(&value.to_html_string());
+            }
+            Node::If {
+                branches,
+                otherwise,
+            } => {
+                let nodes = branches
+                    .iter()
+                    .find(|(condition, _)| self.eval(condition).is_truthy())
+                    .map(|(_, nodes)| nodes)
+                    .unwrap_or(otherwise);
+                self.render_nodes(nodes)?;
+            }
+            Node::For {
+                key,
+                name,
+                iterable,
+                body,
+                otherwise,
+            } => {
+                let entries = match self.eval(iterable).into_owned() {
+                    HtmlTemplateValue::List(x) => {
+                        x.into_iter().map(|x| (None, x)).collect()
+                    }
+                    HtmlTemplateValue::Map(x) => x
+                        .into_iter()
+                        .map(|(k, v)| match key {
+                            Some(_) => (Some(HtmlTemplateValue::Text(k)), v),
+                            None => (None, HtmlTemplateValue::Text(k)),
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+
+                if entries.is_empty() {
+                    return self.render_nodes(otherwise);
+                }
+
+                let length = entries.len();
+                for (i, (k, v)) in entries.into_iter().enumerate() {
+                    let mut scope = BTreeMap::new();
+                    if let (Some(key), Some(k)) = (key, k) {
+                        scope.insert(key.to_string(), k);
+                    }
+                    scope.insert(name.to_string(), v);
+                    scope.insert(
+                        String::from("loop"),
+                        HtmlTemplateValue::new_map()
+                            .with("index", i + 1)
+                            .with("index0", i)
+                            .with("first", i == 0)
+                            .with("last", i + 1 == length)
+                            .with("length", length),
+                    );
+
+                    self.scopes.push(scope);
+                    let result = self.render_nodes(body);
+                    self.scopes.pop();
+                    result?;
+                }
+            }
+            Node::Include(name) => {
+                if self.includes.len() >= MAX_INCLUDE_DEPTH {
+                    return Err(HtmlTemplateError::IncludeTooDeep(
+                        name.to_string(),
+                    ));
+                }
+
+                let text = (self.load)(name).map_err(|source| {
+                    HtmlTemplateError::IncludeNotLoaded {
+                        name: name.to_string(),
+                        source,
+                    }
+                })?;
+                let template = HtmlTemplate::parse(&text)?;
+
+                self.includes.push(name.to_string());
+                let result = self.render_nodes(&template.nodes);
+                self.includes.pop();
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Looks up the value at the given path, first within the variables of
+    /// the enclosing loops and then within the context, resolving to none
+    /// if it does not exist
+    fn lookup(&self, path: &[String]) -> Cow<'_, HtmlTemplateValue> {
+        let (first, rest) = match path.split_first() {
+            Some(x) => x,
+            None => return Cow::Owned(HtmlTemplateValue::None),
+        };
+
+        let mut value = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(first))
+            .or_else(|| self.context.get(first));
+        for name in rest {
+            value = value.and_then(|x| x.get(name));
+        }
+
+        value
+            .map(Cow::Borrowed)
+            .unwrap_or(Cow::Owned(HtmlTemplateValue::None))
+    }
+
+    fn eval(&self, expr: &Expr) -> Cow<'_, HtmlTemplateValue> {
+        match expr {
+            Expr::Literal(x) => Cow::Owned(x.clone()),
+            Expr::Path(x) => self.lookup(x),
+            Expr::Not(x) => Cow::Owned((!self.eval(x).is_truthy()).into()),
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = self.eval(lhs);
+                let value = match op {
+                    BinaryOp::And if !lhs.is_truthy() => return lhs,
+                    BinaryOp::Or if lhs.is_truthy() => return lhs,
+                    BinaryOp::And | BinaryOp::Or => return self.eval(rhs),
+                    BinaryOp::Eq => is_equal(&lhs, &self.eval(rhs)),
+                    BinaryOp::Ne => !is_equal(&lhs, &self.eval(rhs)),
+                    BinaryOp::In => self.eval(rhs).contains(&lhs),
+                    BinaryOp::NotIn => !self.eval(rhs).contains(&lhs),
+                };
+                Cow::Owned(value.into())
+            }
+            Expr::Filter { expr, name, args } => {
+                let value = self.eval(expr);
+                let arg = args.first().map(|x| self.eval(x));
+                Cow::Owned(apply_filter(&value, name, arg.as_deref()))
+            }
+        }
+    }
+}
+
+/// Returns true if the values are equal, treating text and html with the
+/// same characters as equal
+fn is_equal(a: &HtmlTemplateValue, b: &HtmlTemplateValue) -> bool {
+    use HtmlTemplateValue::{Html, Text};
+    match (a, b) {
+        (Text(a) | Html(a), Text(b) | Html(b)) => a == b,
+        (a, b) => a == b,
+    }
+}
+
+fn apply_filter(
+    value: &HtmlTemplateValue,
+    name: &str,
+    arg: Option<&HtmlTemplateValue>,
+) -> HtmlTemplateValue {
+    use HtmlTemplateValue::{Html, Text};
+
+    // Applies a function to the characters of the value, keeping html as html
+    let map_text = |f: fn(&str) -> String| match value {
+        Html(x) => Html(f(x)),
+        x => Text(f(&x.to_string())),
+    };
+
+    match name {
+        "safe" => Html(value.to_string()),
+        "escape" => Html(escape::escape_html(&value.to_string())),
+        "upper" => map_text(|x| x.to_uppercase()),
+        "lower" => map_text(|x| x.to_lowercase()),
+        "length" => value.len().into(),
+        "first" => value.get("0").cloned().unwrap_or_default(),
+        "last" => value
+            .len()
+            .checked_sub(1)
+            .and_then(|i| value.get(&i.to_string()))
+            .cloned()
+            .unwrap_or_default(),
+        "join" => match value {
+            HtmlTemplateValue::List(x) => {
+                let sep = arg.map(ToString::to_string);
+                let sep = sep.as_deref().unwrap_or(", ");
+                Text(
+                    x.iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<String>>()
+                        .join(sep),
+                )
+            }
+            x => x.clone(),
+        },
+        "default" if !value.is_truthy() => arg.cloned().unwrap_or_default(),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str, context: &HtmlTemplateValue) -> String {
+        HtmlTemplate::parse(text)
+            .unwrap()
+            .render(context, |name| {
+                Err(io::Error::new(io::ErrorKind::NotFound, name))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn render_should_escape_text_but_not_html() {
+        let context = HtmlTemplateValue::new_map()
+            .with("a", "<b>")
+            .with("b", HtmlTemplateValue::Html(String::from("<i>x</i>")));
+        assert_eq!(
+            render("{{ a }}{{ b }}{{ a | safe }}", &context),
+            "&lt;b&gt;<i>x</i><b>"
+        );
+    }
+
+    #[test]
+    fn render_should_support_vimwiki_placeholders() {
+        let context = HtmlTemplateValue::new_map()
+            .with("title", "Some & Title")
+            .with("content", HtmlTemplateValue::Html(String::from("<p/>")));
+        assert_eq!(
+            render("<title>%title%</title>%content%%other%", &context),
+            "<title>Some & Title</title><p/>%other%"
+        );
+    }
+
+    #[test]
+    fn render_should_pick_first_true_branch_of_if() {
+        let template = "{% if a %}A{% elif b == 'x' %}B{% else %}C{% endif %}";
+        let context = |a: bool, b: &str| {
+            HtmlTemplateValue::new_map().with("a", a).with("b", b)
+        };
+        assert_eq!(render(template, &context(true, "x")), "A");
+        assert_eq!(render(template, &context(false, "x")), "B");
+        assert_eq!(render(template, &context(false, "y")), "C");
+    }
+
+    #[test]
+    fn render_should_loop_over_lists_and_maps() {
+        let context = HtmlTemplateValue::new_map()
+            .with("items", vec!["a", "b", "c"])
+            .with("map", HtmlTemplateValue::new_map().with("k", 1i64))
+            .with("empty", Vec::<String>::new());
+        assert_eq!(
+            render(
+                "{% for x in items %}{{ loop.index }}{{ x }}\
+                 {% if not loop.last %},{% endif %}{% endfor %}",
+                &context
+            ),
+            "1a,2b,3c"
+        );
+        assert_eq!(
+            render(
+                "{% for k, v in map %}{{ k }}={{ v }}{% endfor %}",
+                &context
+            ),
+            "k=1"
+        );
+        assert_eq!(
+            render("{% for x in empty %}x{% else %}none{% endfor %}", &context),
+            "none"
+        );
+    }
+
+    #[test]
+    fn render_should_apply_filters_and_operators() {
+        let context = HtmlTemplateValue::new_map()
+            .with("tags", vec!["a", "b"])
+            .with("name", "wiki");
+        assert_eq!(
+            render(
+                "{{ tags | join(' ') | upper }} {{ tags | length }} \
+                 {{ missing | default('x') }} {{ 'a' in tags }} \
+                 {{ 'c' not in tags and name }}",
+                &context
+            ),
+            "A B 2 x true wiki"
+        );
+    }
+
+    #[test]
+    fn render_should_trim_whitespace_next_to_dashes() {
+        let context = HtmlTemplateValue::new_map().with("a", true);
+        assert_eq!(
+            render("<a>\n  {%- if a -%}\n  x\n  {%- endif %}\n</a>", &context),
+            "<a>x\n</a>"
+        );
+    }
+
+    #[test]
+    fn render_should_render_included_templates_with_same_context() {
+        let template = HtmlTemplate::parse(
+            "{% for x in xs %}{% include 'item' %}{% endfor %}",
+        )
+        .unwrap();
+        let context = HtmlTemplateValue::new_map().with("xs", vec![1i64, 2]);
+        let output = template
+            .render(&context, |name| {
+                assert_eq!(name, "item");
+                Ok(String::from("[{{ x }}]"))
+            })
+            .unwrap();
+        assert_eq!(output, "[1][2]");
+    }
+
+    #[test]
+    fn render_should_fail_if_includes_are_recursive() {
+        let template = HtmlTemplate::parse("{% include 'self' %}").unwrap();
+        let result = template.render(&HtmlTemplateValue::new_map(), |_| {
+            Ok(String::from("{% include 'self' %}"))
+        });
+        assert!(matches!(
+            result,
+            Err(HtmlTemplateError::IncludeTooDeep(x)) if x == "self"
+        ));
+    }
+}
@@ -0,0 +1,705 @@
+use super::{HtmlTemplateError, HtmlTemplateValue};
+
+/// Names of the placeholders of vimwiki templates (e.g. `%title%`), which
+/// are written as-is in place of the placeholder
+const LEGACY_PLACEHOLDERS: &[&str] = &[
+    "title",
+    "date",
+    "root_path",
+    "wiki_path",
+    "css",
+    "encoding",
+    "content",
+];
+
+/// Names of the filters available to expressions alongside the number of
+/// arguments each accepts at most
+const FILTERS: &[(&str, usize)] = &[
+    ("safe", 0),
+    ("escape", 0),
+    ("upper", 0),
+    ("lower", 0),
+    ("length", 0),
+    ("first", 0),
+    ("last", 0),
+    ("join", 1),
+    ("default", 1),
+];
+
+/// Maximum number of levels that blocks can be nested
+const MAX_DEPTH: usize = 64;
+
+/// Represents a piece of a parsed template
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    /// Text written as-is
+    Text(String),
+
+    /// Expression whose value is written
+    Expr(Expr),
+
+    /// Series of conditions paired with the nodes to render for the first
+    /// condition that is true, falling back to the last nodes otherwise
+    If {
+        branches: Vec<(Expr, Vec<Node>)>,
+        otherwise: Vec<Node>,
+    },
+
+    /// Nodes rendered for each item of a list or entry of a map, falling
+    /// back to the last nodes if there are none
+    For {
+        key: Option<String>,
+        name: String,
+        iterable: Expr,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+
+    /// Name of another template to render in place
+    Include(String),
+}
+
+/// Represents an expression within a template
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Literal(HtmlTemplateValue),
+    Path(Vec<String>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Filter {
+        expr: Box<Expr>,
+        name: String,
+        args: Vec<Expr>,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    Ne,
+    In,
+    NotIn,
+}
+
+/// Represents a piece of template text prior to parsing
+#[derive(Debug)]
+enum Segment {
+    Text(String),
+    Expr(String, usize),
+    Tag(String, usize),
+}
+
+/// Parses the text of a template into nodes
+pub fn parse(text: &str) -> Result<Vec<Node>, HtmlTemplateError> {
+    let segments = lex(text)?;
+    let mut segments = segments.into_iter();
+    let (nodes, end) = parse_nodes(&mut segments, &[], 0)?;
+
+    match end {
+        Some((tag, line)) => Err(syntax(line, format!("Unexpected {}", tag))),
+        None => Ok(nodes),
+    }
+}
+
+fn syntax(line: usize, message: impl Into<String>) -> HtmlTemplateError {
+    HtmlTemplateError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Splits template text into text, expressions, and tags, dropping comments
+/// and trimming whitespace next to markers that start or end with `-`
+fn lex(text: &str) -> Result<Vec<Segment>, HtmlTemplateError> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    let mut line = 1;
+    let mut trim_next = false;
+
+    loop {
+        let start =
+            ["{{", "{%", "{#"].iter().filter_map(|x| rest.find(x)).min();
+
+        let start = match start {
+            Some(start) => start,
+            None => {
+                let text = if trim_next { rest.trim_start() } else { rest };
+                if !text.is_empty() {
+                    segments.push(Segment::Text(text.to_string()));
+                }
+                break;
+            }
+        };
+
+        let close = match &rest[start..start + 2] {
+            "{{" => "}}",
+            "{%" => "%}",
+            _ => "#}",
+        };
+        let end = match rest[start + 2..].find(close) {
+            Some(end) => start + 2 + end,
+            None => {
+                let line = line + rest[..start].matches('\n').count();
+                return Err(syntax(
+                    line,
+                    format!(
+                        "Missing {} for {}",
+                        close,
+                        &rest[start..start + 2]
+                    ),
+                ));
+            }
+        };
+
+        let inner = &rest[start + 2..end];
+        let mut text = &rest[..start];
+        if trim_next {
+            text = text.trim_start();
+        }
+        if inner.starts_with('-') {
+            text = text.trim_end();
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text.to_string()));
+        }
+
+        line += rest[..start].matches('\n').count();
+        trim_next = inner.ends_with('-');
+
+        let inner = inner.strip_prefix('-').unwrap_or(inner);
+        let inner = inner.strip_suffix('-').unwrap_or(inner).trim().to_string();
+        match close {
+            "}}" => segments.push(Segment::Expr(inner, line)),
+            "%}" => segments.push(Segment::Tag(inner, line)),
+            _ => {}
+        }
+
+        line += rest[start..end].matches('\n').count();
+        rest = &rest[end + 2..];
+    }
+
+    Ok(segments)
+}
+
+/// Tag (and its line) that ended a series of nodes
+type EndTag = (String, usize);
+
+/// Parses segments into nodes until reaching a tag that starts with one of
+/// the given keywords, returning the nodes alongside the tag
+fn parse_nodes(
+    segments: &mut impl Iterator<Item = Segment>,
+    until: &[&str],
+    depth: usize,
+) -> Result<(Vec<Node>, Option<EndTag>), HtmlTemplateError> {
+    let mut nodes = Vec::new();
+
+    while let Some(segment) = segments.next() {
+        match segment {
+            Segment::Text(text) => push_text(&mut nodes, &text),
+            Segment::Expr(text, line) => {
+                nodes.push(Node::Expr(parse_expr(&text, line)?))
+            }
+            Segment::Tag(tag, line) => {
+                let keyword = tag.split_whitespace().next().unwrap_or_default();
+                if until.contains(&keyword) {
+                    return Ok((nodes, Some((tag, line))));
+                }
+
+                if depth >= MAX_DEPTH {
+                    return Err(syntax(line, "Blocks nested too deeply"));
+                }
+
+                let rest = tag[keyword.len()..].trim();
+                match keyword {
+                    "if" => nodes.push(parse_if(segments, rest, line, depth)?),
+                    "for" => {
+                        nodes.push(parse_for(segments, rest, line, depth)?)
+                    }
+                    "include" => match parse_expr(rest, line)? {
+                        Expr::Literal(HtmlTemplateValue::Text(name)) => {
+                            nodes.push(Node::Include(name))
+                        }
+                        _ => return Err(syntax(
+                            line,
+                            "Include expects the name of a template in quotes",
+                        )),
+                    },
+                    _ => {
+                        return Err(syntax(line, format!("Unexpected {}", tag)))
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((nodes, None))
+}
+
+/// Parses the remainder of an `if` block whose condition is provided
+fn parse_if(
+    segments: &mut impl Iterator<Item = Segment>,
+    condition: &str,
+    line: usize,
+    depth: usize,
+) -> Result<Node, HtmlTemplateError> {
+    let mut branches = Vec::new();
+    let mut condition = parse_expr(condition, line)?;
+
+    loop {
+        let (nodes, end) =
+            parse_nodes(segments, &["elif", "else", "endif"], depth + 1)?;
+        let (tag, end_line) =
+            end.ok_or_else(|| syntax(line, "Missing endif for if"))?;
+        branches.push((condition, nodes));
+
+        let keyword = tag.split_whitespace().next().unwrap_or_default();
+        match keyword {
+            "elif" => {
+                condition = parse_expr(tag[keyword.len()..].trim(), end_line)?;
+            }
+            "else" => {
+                let (otherwise, end) =
+                    parse_nodes(segments, &["endif"], depth + 1)?;
+                if end.is_none() {
+                    return Err(syntax(line, "Missing endif for if"));
+                }
+                return Ok(Node::If {
+                    branches,
+                    otherwise,
+                });
+            }
+            _ => {
+                return Ok(Node::If {
+                    branches,
+                    otherwise: Vec::new(),
+                })
+            }
+        }
+    }
+}
+
+/// Parses the remainder of a `for` block whose loop (e.g. `x in items`) is
+/// provided
+fn parse_for(
+    segments: &mut impl Iterator<Item = Segment>,
+    head: &str,
+    line: usize,
+    depth: usize,
+) -> Result<Node, HtmlTemplateError> {
+    let (names, iterable) = match head.find(" in ") {
+        Some(idx) => (&head[..idx], &head[idx + 4..]),
+        None => return Err(syntax(line, "For expects the form: x in items")),
+    };
+
+    let names = names.split(',').map(str::trim).collect::<Vec<&str>>();
+    let is_ident = |x: &str| {
+        !x.is_empty()
+            && !x.starts_with(|c: char| c.is_ascii_digit())
+            && x.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    if !names.iter().all(|x| is_ident(x)) {
+        return Err(syntax(line, format!("Invalid loop variables: {}", head)));
+    }
+
+    let (key, name) = match names.as_slice() {
+        [name] => (None, name.to_string()),
+        [key, name] => (Some(key.to_string()), name.to_string()),
+        _ => {
+            return Err(syntax(line, "For expects at most two loop variables"))
+        }
+    };
+    let iterable = parse_expr(iterable, line)?;
+
+    let (body, end) = parse_nodes(segments, &["else", "endfor"], depth + 1)?;
+    let (tag, _) = end.ok_or_else(|| syntax(line, "Missing endfor for for"))?;
+    let otherwise = if tag == "else" {
+        let (otherwise, end) = parse_nodes(segments, &["endfor"], depth + 1)?;
+        if end.is_none() {
+            return Err(syntax(line, "Missing endfor for for"));
+        }
+        otherwise
+    } else {
+        Vec::new()
+    };
+
+    Ok(Node::For {
+        key,
+        name,
+        iterable,
+        body,
+        otherwise,
+    })
+}
+
+/// Adds text to the nodes, replacing any vimwiki placeholders with
+/// expressions that write the value of the same name as-is
+fn push_text(nodes: &mut Vec<Node>, text: &str) {
+    let mut rest = text;
+
+    while let Some((start, name)) = LEGACY_PLACEHOLDERS
+        .iter()
+        .filter_map(|name| {
+            rest.find(&format!("%{}%", name)).map(|idx| (idx, *name))
+        })
+        .min()
+    {
+        if start > 0 {
+            nodes.push(Node::Text(rest[..start].to_string()));
+        }
+        nodes.push(Node::Expr(Expr::Filter {
+            expr: Box::new(Expr::Path(vec![name.to_string()])),
+            name: String::from("safe"),
+            args: Vec::new(),
+        }));
+        rest = &rest[start + name.len() + 2..];
+    }
+
+    if !rest.is_empty() {
+        nodes.push(Node::Text(rest.to_string()));
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Int(i64),
+    Dot,
+    Pipe,
+    Comma,
+    LParen,
+    RParen,
+    Eq,
+    Ne,
+}
+
+fn tokenize(text: &str, line: usize) -> Result<Vec<Token>, HtmlTemplateError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '.' => tokens.push(Token::Dot),
+            '|' => tokens.push(Token::Pipe),
+            ',' => tokens.push(Token::Comma),
+            '(' => tokens.push(Token::LParen),
+            ')' => tokens.push(Token::RParen),
+            '=' | '!' if chars.peek() == Some(&'=') => {
+                chars.next();
+                tokens.push(if c == '=' { Token::Eq } else { Token::Ne });
+            }
+            '"' | '\'' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => s.extend(chars.next()),
+                        Some(x) if x == c => break,
+                        Some(x) => s.push(x),
+                        None => {
+                            return Err(syntax(line, "Missing end of string"))
+                        }
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_ascii_digit() => {
+                let mut s = c.to_string();
+                while let Some(x) = chars.peek().filter(|x| x.is_ascii_digit())
+                {
+                    s.push(*x);
+                    chars.next();
+                }
+                tokens.push(Token::Int(s.parse().map_err(|_| {
+                    syntax(line, format!("Invalid number: {}", s))
+                })?));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut s = c.to_string();
+                while let Some(x) =
+                    chars.peek().filter(|x| x.is_alphanumeric() || **x == '_')
+                {
+                    s.push(*x);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(s));
+            }
+            c => {
+                return Err(syntax(
+                    line,
+                    format!("Unexpected character: {}", c),
+                ))
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses an expression, where operators from lowest to highest precedence
+/// are `or`, `and`, `not`, comparisons (`==`, `!=`, `in`, `not in`), and
+/// filters (`|`)
+pub fn parse_expr(text: &str, line: usize) -> Result<Expr, HtmlTemplateError> {
+    let mut parser = ExprParser {
+        tokens: tokenize(text, line)?,
+        pos: 0,
+        line,
+    };
+
+    if parser.tokens.is_empty() {
+        return Err(syntax(line, "Missing expression"));
+    }
+
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        Some(token) => Err(syntax(
+            line,
+            format!("Unexpected {:?} in expression", token),
+        )),
+        None => Ok(expr),
+    }
+}
+
+struct ExprParser {
+    tokens: Vec<Token>,
+    pos: usize,
+    line: usize,
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_keyword(&self, offset: usize, keyword: &str) -> bool {
+        matches!(
+            self.tokens.get(self.pos + offset),
+            Some(Token::Ident(x)) if x == keyword
+        )
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), HtmlTemplateError> {
+        match self.next() {
+            Some(x) if x == token => Ok(()),
+            x => Err(syntax(
+                self.line,
+                format!("Expected {:?} but got {:?}", token, x),
+            )),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, HtmlTemplateError> {
+        let mut expr = self.parse_and()?;
+        while self.is_keyword(0, "or") {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            expr = Expr::Binary(BinaryOp::Or, Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, HtmlTemplateError> {
+        let mut expr = self.parse_not()?;
+        while self.is_keyword(0, "and") {
+            self.pos += 1;
+            let rhs = self.parse_not()?;
+            expr = Expr::Binary(BinaryOp::And, Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, HtmlTemplateError> {
+        if self.is_keyword(0, "not") {
+            self.pos += 1;
+            Ok(Expr::Not(Box::new(self.parse_not()?)))
+        } else {
+            self.parse_cmp()
+        }
+    }
+
+    fn parse_cmp(&mut self) -> Result<Expr, HtmlTemplateError> {
+        let lhs = self.parse_filtered()?;
+
+        let op = match self.peek() {
+            Some(Token::Eq) => Some((BinaryOp::Eq, 1)),
+            Some(Token::Ne) => Some((BinaryOp::Ne, 1)),
+            _ if self.is_keyword(0, "in") => Some((BinaryOp::In, 1)),
+            _ if self.is_keyword(0, "not") && self.is_keyword(1, "in") => {
+                Some((BinaryOp::NotIn, 2))
+            }
+            _ => None,
+        };
+
+        match op {
+            Some((op, cnt)) => {
+                self.pos += cnt;
+                let rhs = self.parse_filtered()?;
+                Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+            }
+            None => Ok(lhs),
+        }
+    }
+
+    fn parse_filtered(&mut self) -> Result<Expr, HtmlTemplateError> {
+        let mut expr = self.parse_primary()?;
+
+        while self.peek() == Some(&Token::Pipe) {
+            self.pos += 1;
+            let name = match self.next() {
+                Some(Token::Ident(x)) => x,
+                x => {
+                    return Err(syntax(
+                        self.line,
+                        format!("Expected filter name but got {:?}", x),
+                    ))
+                }
+            };
+
+            let mut args = Vec::new();
+            if self.peek() == Some(&Token::LParen) {
+                self.pos += 1;
+                while self.peek() != Some(&Token::RParen) {
+                    args.push(self.parse_or()?);
+                    if self.peek() == Some(&Token::Comma) {
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                self.expect(Token::RParen)?;
+            }
+
+            let is_known = FILTERS
+                .iter()
+                .any(|(x, max_args)| *x == name && args.len() <= *max_args);
+            if !is_known {
+                return Err(HtmlTemplateError::UnknownFilter(name));
+            }
+
+            expr = Expr::Filter {
+                expr: Box::new(expr),
+                name,
+                args,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, HtmlTemplateError> {
+        match self.next() {
+            Some(Token::Str(x)) => {
+                Ok(Expr::Literal(HtmlTemplateValue::Text(x)))
+            }
+            Some(Token::Int(x)) => {
+                Ok(Expr::Literal(HtmlTemplateValue::Integer(x)))
+            }
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(x)) => match x.as_str() {
+                "true" => Ok(Expr::Literal(HtmlTemplateValue::Bool(true))),
+                "false" => Ok(Expr::Literal(HtmlTemplateValue::Bool(false))),
+                "none" => Ok(Expr::Literal(HtmlTemplateValue::None)),
+                _ => {
+                    let mut path = vec![x];
+                    while self.peek() == Some(&Token::Dot) {
+                        self.pos += 1;
+                        match self.next() {
+                            Some(Token::Ident(x)) => path.push(x),
+                            Some(Token::Int(x)) => path.push(x.to_string()),
+                            x => {
+                                return Err(syntax(
+                                    self.line,
+                                    format!("Expected name but got {:?}", x),
+                                ))
+                            }
+                        }
+                    }
+                    Ok(Expr::Path(path))
+                }
+            },
+            x => Err(syntax(
+                self.line,
+                format!("Expected value but got {:?}", x),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(x: &str) -> Expr {
+        Expr::Path(x.split('.').map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn parse_expr_should_respect_precedence() {
+        let expr = parse_expr("not a or b.c == 'x' and d | length", 1).unwrap();
+        assert_eq!(
+            expr,
+            Expr::Binary(
+                BinaryOp::Or,
+                Box::new(Expr::Not(Box::new(path("a")))),
+                Box::new(Expr::Binary(
+                    BinaryOp::And,
+                    Box::new(Expr::Binary(
+                        BinaryOp::Eq,
+                        Box::new(path("b.c")),
+                        Box::new(Expr::Literal(HtmlTemplateValue::from("x"))),
+                    )),
+                    Box::new(Expr::Filter {
+                        expr: Box::new(path("d")),
+                        name: String::from("length"),
+                        args: Vec::new(),
+                    }),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn parse_should_fail_with_line_of_unclosed_block() {
+        match parse("a\n{% if x %}\nb") {
+            Err(HtmlTemplateError::Syntax { line, .. }) => assert_eq!(line, 2),
+            x => panic!("Unexpected result: {:?}", x),
+        }
+    }
+
+    #[test]
+    fn parse_should_fail_for_unknown_filter() {
+        assert!(matches!(
+            parse("{{ x | bogus }}"),
+            Err(HtmlTemplateError::UnknownFilter(x)) if x == "bogus"
+        ));
+    }
+
+    #[test]
+    fn parse_should_replace_vimwiki_placeholders_in_text() {
+        let nodes = parse("<b>%title%</b>%unknown%").unwrap();
+        assert_eq!(
+            nodes,
+            vec![
+                Node::Text(String::from("<b>")),
+                Node::Expr(Expr::Filter {
+                    expr: Box::new(path("title")),
+                    name: String::from("safe"),
+                    args: Vec::new(),
+                }),
+                Node::Text(String::from("</b>%unknown%")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::wiki::{MetadataValue, PageMetadata};
+use std::{collections::BTreeMap, fmt};
+use voca_rs::escape;
+
+/// Represents a value available to a template, where text is escaped when
+/// written to a template and html is written as-is
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HtmlTemplateValue {
+    None,
+    Bool(bool),
+    Integer(i64),
+    Text(String),
+    Html(String),
+    List(Vec<HtmlTemplateValue>),
+    Map(BTreeMap<String, HtmlTemplateValue>),
+}
+
+impl Default for HtmlTemplateValue {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl HtmlTemplateValue {
+    /// Creates an empty map
+    pub fn new_map() -> Self {
+        Self::Map(BTreeMap::new())
+    }
+
+    /// Sets the value of the entry with the given name if this is a map,
+    /// returning the map to support chaining
+    pub fn with(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<Self>,
+    ) -> Self {
+        if let Self::Map(map) = &mut self {
+            map.insert(name.into(), value.into());
+        }
+        self
+    }
+
+    /// Returns the value of the entry with the given name if this is a map,
+    /// or the item at the given position if this is a list
+    pub fn get(&self, name: &str) -> Option<&Self> {
+        match self {
+            Self::Map(x) => x.get(name),
+            Self::List(x) => name.parse::<usize>().ok().and_then(|i| x.get(i)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the value is considered true by conditions, which is
+    /// the case for everything other than none, false, zero, and empty text,
+    /// lists, and maps
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::None => false,
+            Self::Bool(x) => *x,
+            Self::Integer(x) => *x != 0,
+            Self::Text(x) | Self::Html(x) => !x.is_empty(),
+            Self::List(x) => !x.is_empty(),
+            Self::Map(x) => !x.is_empty(),
+        }
+    }
+
+    /// Returns the number of characters, items, or entries of the value
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Text(x) | Self::Html(x) => x.chars().count(),
+            Self::List(x) => x.len(),
+            Self::Map(x) => x.len(),
+            _ => 0,
+        }
+    }
+
+    /// Returns true if the value has no characters, items, or entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the value contains the other value, which is an item
+    /// of a list, the name of an entry of a map, or a substring of text
+    pub fn contains(&self, other: &Self) -> bool {
+        match self {
+            Self::List(x) => x.iter().any(|x| x == other),
+            Self::Map(x) => x.contains_key(&other.to_string()),
+            Self::Text(x) | Self::Html(x) => x.contains(&other.to_string()),
+            _ => false,
+        }
+    }
+
+    /// Produces the text of the value as it is written within a template,
+    /// escaping anything that is not html
+    pub fn to_html_string(&self) -> String {
+        match self {
+            Self::Html(x) => x.to_string(),
+            x => escape::escape_html(&x.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for HtmlTemplateValue {
+    /// Writes the value as text, where lists are comma-separated and maps
+    /// are written as nothing
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None | Self::Map(_) => Ok(()),
+            Self::Bool(x) => write!(f, "{}", x),
+            Self::Integer(x) => write!(f, "{}", x),
+            Self::Text(x) | Self::Html(x) => write!(f, "{}", x),
+            Self::List(x) => write!(
+                f,
+                "{}",
+                x.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl From<bool> for HtmlTemplateValue {
+    fn from(x: bool) -> Self {
+        Self::Bool(x)
+    }
+}
+
+impl From<i64> for HtmlTemplateValue {
+    fn from(x: i64) -> Self {
+        Self::Integer(x)
+    }
+}
+
+impl From<usize> for HtmlTemplateValue {
+    fn from(x: usize) -> Self {
+        Self::Integer(x as i64)
+    }
+}
+
+impl From<String> for HtmlTemplateValue {
+    fn from(x: String) -> Self {
+        Self::Text(x)
+    }
+}
+
+impl From<&str> for HtmlTemplateValue {
+    fn from(x: &str) -> Self {
+        Self::Text(x.to_string())
+    }
+}
+
+impl<T: Into<HtmlTemplateValue>> From<Option<T>> for HtmlTemplateValue {
+    fn from(x: Option<T>) -> Self {
+        x.map(Into::into).unwrap_or_default()
+    }
+}
+
+impl<T: Into<HtmlTemplateValue>> From<Vec<T>> for HtmlTemplateValue {
+    fn from(x: Vec<T>) -> Self {
+        Self::List(x.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<BTreeMap<String, HtmlTemplateValue>> for HtmlTemplateValue {
+    fn from(x: BTreeMap<String, HtmlTemplateValue>) -> Self {
+        Self::Map(x)
+    }
+}
+
+impl From<&MetadataValue> for HtmlTemplateValue {
+    /// Converts a metadata value, writing dates in the form of YYYY-MM-DD
+    fn from(x: &MetadataValue) -> Self {
+        match x {
+            MetadataValue::Text(x) => Self::Text(x.to_string()),
+            MetadataValue::Bool(x) => Self::Bool(*x),
+            MetadataValue::Integer(x) => Self::Integer(*x),
+            MetadataValue::Date(x) => {
+                Self::Text(x.format("%Y-%m-%d").to_string())
+            }
+            MetadataValue::List(x) => x.clone().into(),
+        }
+    }
+}
+
+impl From<&PageMetadata> for HtmlTemplateValue {
+    /// Converts metadata into a map with an entry for each metadata entry
+    fn from(x: &PageMetadata) -> Self {
+        Self::Map(
+            x.iter()
+                .map(|(name, value)| (name.to_string(), value.into()))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents configuration properties for latex output that are separate
+/// from the running state during latex conversion
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LatexConfig {
+    /// If true, will wrap a page in a complete document including the
+    /// preamble, otherwise only the body of the page is written
+    #[serde(default = "LatexConfig::default_standalone")]
+    pub standalone: bool,
+
+    /// Class of the document written when standalone
+    #[serde(default = "LatexConfig::default_document_class")]
+    pub document_class: String,
+
+    /// Sectioning commands used for headers, where the first is used for
+    /// headers of level 1 and the last for headers of its level or deeper
+    #[serde(default = "LatexConfig::default_header_commands")]
+    pub header_commands: Vec<String>,
+
+    /// Environment used to write code blocks
+    #[serde(default = "LatexConfig::default_code_style")]
+    pub code_style: LatexCodeStyle,
+
+    /// Extension given to the paths of wiki pages when writing links
+    #[serde(default = "LatexConfig::default_page_ext")]
+    pub page_ext: String,
+
+    /// Path to the diary relative to the root of the wiki, used when writing
+    /// diary links
+    #[serde(default = "LatexConfig::default_diary_rel_path")]
+    pub diary_rel_path: String,
+
+    /// Path from the page being written to the root of its wiki (e.g. `../`),
+    /// used when writing links that are relative to the root of a wiki
+    #[serde(default)]
+    pub root_rel_path: String,
+}
+
+impl Default for LatexConfig {
+    fn default() -> Self {
+        Self {
+            standalone: Self::default_standalone(),
+            document_class: Self::default_document_class(),
+            header_commands: Self::default_header_commands(),
+            code_style: Self::default_code_style(),
+            page_ext: Self::default_page_ext(),
+            diary_rel_path: Self::default_diary_rel_path(),
+            root_rel_path: String::new(),
+        }
+    }
+}
+
+impl LatexConfig {
+    #[inline]
+    pub fn default_standalone() -> bool {
+        true
+    }
+
+    #[inline]
+    pub fn default_document_class() -> String {
+        String::from("article")
+    }
+
+    #[inline]
+    pub fn default_header_commands() -> Vec<String> {
+        vec![
+            String::from("section"),
+            String::from("subsection"),
+            String::from("subsubsection"),
+            String::from("paragraph"),
+            String::from("subparagraph"),
+        ]
+    }
+
+    #[inline]
+    pub fn default_code_style() -> LatexCodeStyle {
+        LatexCodeStyle::default()
+    }
+
+    #[inline]
+    pub fn default_page_ext() -> String {
+        String::from("pdf")
+    }
+
+    #[inline]
+    pub fn default_diary_rel_path() -> String {
+        String::from("diary")
+    }
+}
+
+/// Represents the environment used to write code blocks
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatexCodeStyle {
+    /// Use the `lstlisting` environment of the listings package, passing
+    /// along the language of the code block
+    Listings,
+
+    /// Use the `verbatim` environment, dropping the language of the code
+    /// block
+    Verbatim,
+}
+
+impl Default for LatexCodeStyle {
+    fn default() -> Self {
+        Self::Listings
+    }
+}
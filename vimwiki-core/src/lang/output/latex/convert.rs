@@ -0,0 +1,54 @@
+use super::{LatexConfig, LatexFormatter, LatexOutputError, Output};
+
+pub trait ToLatexString {
+    fn to_latex_string(
+        &self,
+        config: LatexConfig,
+    ) -> Result<String, LatexOutputError>;
+}
+
+impl<T: Output<LatexFormatter>> ToLatexString for T {
+    fn to_latex_string(
+        &self,
+        config: LatexConfig,
+    ) -> Result<String, LatexOutputError> {
+        let mut formatter = LatexFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_content())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LatexOutputResult;
+
+    struct TestOutput<F: Fn(&mut LatexFormatter) -> LatexOutputResult>(F);
+    impl<F: Fn(&mut LatexFormatter) -> LatexOutputResult> Output<LatexFormatter>
+        for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    fn _text(
+        text: impl Into<String>,
+    ) -> impl Fn(&mut LatexFormatter) -> LatexOutputResult {
+        let text = text.into();
+        move |f: &mut LatexFormatter| {
+            use std::fmt::Write;
+            write!(f, "{}", text.as_str())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_latex_string_should_produce_a_string_representing_only_the_latex_of_the_output(
+    ) {
+        let output = TestOutput(_text(r"\textbf{I am some latex output}"));
+        let result = output.to_latex_string(LatexConfig::default()).unwrap();
+        assert_eq!(result, r"\textbf{I am some latex output}");
+    }
+}
@@ -0,0 +1,11 @@
+use derive_more::{Display, Error, From};
+
+pub type LatexOutputResult = Result<(), LatexOutputError>;
+
+#[derive(Debug, From, Display, Error)]
+pub enum LatexOutputError {
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
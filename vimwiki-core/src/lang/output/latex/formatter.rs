@@ -0,0 +1,64 @@
+use super::{LatexConfig, LatexOutputError, OutputFormatter};
+use std::fmt::{self, Write};
+
+/// Represents the formatter to use to write latex output that includes
+/// various options that can be set as well as a context for use when writing
+/// output
+#[derive(Clone, Default)]
+pub struct LatexFormatter {
+    /// Represents the configuration associated with the formatter
+    config: LatexConfig,
+
+    /// Contains the content to be injected into a template
+    content: String,
+}
+
+impl OutputFormatter for LatexFormatter {
+    type Error = LatexOutputError;
+}
+
+impl Write for LatexFormatter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.content.write_str(s)
+    }
+}
+
+impl LatexFormatter {
+    pub fn new(config: LatexConfig) -> Self {
+        Self {
+            config,
+            content: String::new(),
+        }
+    }
+
+    pub fn clone_without_content(&self) -> Self {
+        Self {
+            content: String::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns true if the next character written will start a new line
+    #[inline]
+    pub fn is_at_line_start(&self) -> bool {
+        self.content.is_empty() || self.content.ends_with('\n')
+    }
+
+    /// Represents the config contained within the formatter
+    #[inline]
+    pub fn config(&self) -> &LatexConfig {
+        &self.config
+    }
+
+    /// Represents the content contained within the formatter
+    #[inline]
+    pub fn get_content(&self) -> &str {
+        self.content.as_str()
+    }
+
+    /// Consumes the formatter and returns the content
+    #[inline]
+    pub fn into_content(self) -> String {
+        self.content
+    }
+}
@@ -0,0 +1,972 @@
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::LatexFormatter;
+
+mod convert;
+pub use convert::ToLatexString;
+
+mod error;
+pub use error::{LatexOutputError, LatexOutputResult};
+
+use crate::{
+    lang::{
+        elements::*,
+        output::{make_page_link_target, slugify, Output, OutputFormatter},
+    },
+    wiki::PageMetadata,
+};
+use std::fmt::Write;
+
+/// Packages required by the commands and environments used to write pages
+const PACKAGES: &[&str] = &[
+    "[utf8]{inputenc}",
+    "[T1]{fontenc}",
+    "{amsmath}",
+    "{amssymb}",
+    "{graphicx}",
+    "{listings}",
+    "{multirow}",
+    "{enumitem}",
+    "[normalem]{ulem}",
+    "{hyperref}",
+];
+
+impl<'a> Output<LatexFormatter> for Page<'a> {
+    /// Writes a page in latex, wrapping it in a document whose title, author,
+    /// and date come from the placeholders of the page when standalone
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        let standalone = f.config().standalone;
+
+        if standalone {
+            let document_class = f.config().document_class.to_string();
+            writeln!(f, r"\documentclass{{{}}}", document_class)?;
+            for package in PACKAGES {
+                writeln!(f, r"\usepackage{}", package)?;
+            }
+
+            let metadata = PageMetadata::from_page(self);
+            if let Some(title) = metadata.title() {
+                writeln!(f, r"\title{{{}}}", escape_text(title))?;
+            }
+            if let Some(author) = metadata.author() {
+                writeln!(f, r"\author{{{}}}", escape_text(author))?;
+            }
+            if let Some(date) = metadata.date() {
+                writeln!(f, r"\date{{{}}}", date.format("%Y-%m-%d"))?;
+            }
+
+            writeln!(f, r"\begin{{document}}")?;
+            if metadata.title().is_some() {
+                writeln!(f, r"\maketitle")?;
+            }
+        }
+
+        let mut is_first = true;
+        for element in self.elements.iter() {
+            // Separate each block element that produces output with a blank
+            // line so that each starts a new paragraph
+            let mut formatter = f.clone_without_content();
+            element.fmt(&mut formatter)?;
+            let text = formatter.into_content();
+
+            if !text.is_empty() {
+                if !is_first {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", text)?;
+                is_first = false;
+            }
+        }
+
+        if standalone {
+            writeln!(f)?;
+            writeln!(f, r"\end{{document}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<LatexFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<LatexFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Blockquote<'a> {
+    /// Writes a blockquote in latex, where blank lines separate paragraphs
+    ///
+    /// ```latex
+    /// \begin{quote}
+    /// line 1
+    /// \end{quote}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        writeln!(f, r"\begin{{quote}}")?;
+        for line in self {
+            writeln!(f, "{}", escape_text(line.trim()))?;
+        }
+        writeln!(f, r"\end{{quote}}")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for DefinitionList<'a> {
+    /// Writes a definition list in latex
+    ///
+    /// ```latex
+    /// \begin{description}
+    /// \item[term] definition 1
+    /// \item[] definition 2
+    /// \end{description}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        writeln!(f, r"\begin{{description}}")?;
+        for (term, defs) in self {
+            // NOTE: Brackets within the optional argument must be grouped
+            //       to keep them from ending the argument early
+            write!(f, r"\item[{{")?;
+            term.fmt(f)?;
+            write!(f, "}}]")?;
+
+            for (idx, def) in defs.iter().enumerate() {
+                if idx > 0 {
+                    write!(f, r"\item[]")?;
+                }
+                write!(f, " ")?;
+                def.fmt(f)?;
+                writeln!(f)?;
+            }
+
+            if defs.is_empty() {
+                writeln!(f)?;
+            }
+        }
+        writeln!(f, r"\end{{description}}")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<LatexFormatter> for Divider {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        writeln!(f, r"\noindent\rule{{\textwidth}}{{0.4pt}}")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Header<'a> {
+    /// Writes a header in latex using the sectioning command for its level,
+    /// labeled so links to its anchor can reference it
+    ///
+    /// ```latex
+    /// \subsection{header}\label{header}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        let commands = &f.config().header_commands;
+        let command = commands
+            .get(self.level.saturating_sub(1))
+            .or_else(|| commands.last())
+            .cloned()
+            .unwrap_or_else(|| String::from("paragraph"));
+
+        write!(f, r"\{}{{", command)?;
+        self.content.fmt(f)?;
+        writeln!(
+            f,
+            r"}}\label{{{}}}",
+            slugify(self.content.to_string().as_str())
+        )?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for List<'a> {
+    /// Writes a list in latex, labeling ordered lists to match the type and
+    /// suffix of their items
+    ///
+    /// ```latex
+    /// \begin{enumerate}[label=\alph*)]
+    /// \item first
+    /// \end{enumerate}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        let first = self.iter().next().map(|x| x.as_inner());
+        let label = match first.map(|x| (&x.ty, x.suffix)) {
+            Some((ListItemType::Ordered(ty), suffix)) => {
+                let counter = match ty {
+                    OrderedListItemType::Number
+                    | OrderedListItemType::Pound => r"\arabic*",
+                    OrderedListItemType::LowercaseAlphabet => r"\alph*",
+                    OrderedListItemType::UppercaseAlphabet => r"\Alph*",
+                    OrderedListItemType::LowercaseRoman => r"\roman*",
+                    OrderedListItemType::UppercaseRoman => r"\Roman*",
+                };
+                let suffix = suffix.as_char().unwrap_or('.');
+
+                // NOTE: Only deviations from the default of 1. need a label
+                if counter == r"\arabic*" && suffix == '.' {
+                    None
+                } else {
+                    Some(format!("{}{}", counter, suffix))
+                }
+            }
+            _ => None,
+        };
+        let env = if first.map_or(false, |x| x.ty.is_ordered()) {
+            "enumerate"
+        } else {
+            "itemize"
+        };
+
+        write!(f, r"\begin{{{}}}", env)?;
+        if let Some(label) = label {
+            write!(f, "[label={}]", label)?;
+        }
+        writeln!(f)?;
+
+        for item in self {
+            item.fmt(f)?;
+        }
+
+        writeln!(f, r"\end{{{}}}", env)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for ListItem<'a> {
+    /// Writes a list item in latex, using a box as the label of todo items
+    /// that is crossed out once complete
+    ///
+    /// ```latex
+    /// \item[$\square$] incomplete
+    /// \item[$\boxtimes$] complete
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        write!(f, r"\item")?;
+
+        if let Some(todo_status) = self.attributes.todo_status.as_ref() {
+            write!(f, "[")?;
+            todo_status.fmt(f)?;
+            write!(f, "]")?;
+        }
+
+        for (idx, content) in self.contents.iter().enumerate() {
+            // NOTE: Blocks after the first need to start on their own line,
+            //       separated from paragraphs by a blank line
+            if idx == 0 {
+                write!(f, " ")?;
+            } else if matches!(content.as_inner(), BlockElement::Paragraph(_)) {
+                writeln!(f)?;
+            }
+
+            content.fmt(f)?;
+        }
+
+        if !f.is_at_line_start() {
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Output<LatexFormatter> for ListItemTodoStatus {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self {
+            Self::Complete => write!(f, r"$\boxtimes$")?,
+            _ => write!(f, r"$\square$")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for MathBlock<'a> {
+    /// Writes a math block in latex, using its environment if it has one and
+    /// display math otherwise
+    ///
+    /// ```latex
+    /// \begin{align}
+    /// some math
+    /// \end{align}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self.environment.as_ref() {
+            Some(env) => writeln!(f, r"\begin{{{}}}", env)?,
+            None => writeln!(f, r"\[")?,
+        }
+
+        for line in self {
+            writeln!(f, "{}", line)?;
+        }
+
+        match self.environment.as_ref() {
+            Some(env) => writeln!(f, r"\end{{{}}}", env)?,
+            None => writeln!(f, r"\]")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Placeholder<'a> {
+    /// Writes nothing as placeholders are only used within the preamble of a
+    /// standalone document
+    fn fmt(&self, _f: &mut LatexFormatter) -> LatexOutputResult {
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for CodeBlock<'a> {
+    /// Writes a code block in latex, passing along its language to listings
+    /// if it is one that listings supports
+    ///
+    /// ```latex
+    /// \begin{lstlisting}[language=Python]
+    /// some code
+    /// \end{lstlisting}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match f.config().code_style {
+            LatexCodeStyle::Listings => {
+                let language =
+                    self.language_or_brush().and_then(listings_language);
+
+                write!(f, r"\begin{{lstlisting}}")?;
+                if let Some(language) = language {
+                    write!(f, "[language={}]", language)?;
+                }
+                writeln!(f)?;
+                for line in self {
+                    writeln!(f, "{}", line)?;
+                }
+                writeln!(f, r"\end{{lstlisting}}")?;
+            }
+            LatexCodeStyle::Verbatim => {
+                writeln!(f, r"\begin{{verbatim}}")?;
+                for line in self {
+                    writeln!(f, "{}", line)?;
+                }
+                writeln!(f, r"\end{{verbatim}}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps a language to the name used by the listings package, returning none
+/// for languages that listings does not support as they fail to compile
+fn listings_language(language: &str) -> Option<&'static str> {
+    Some(match language.trim().to_lowercase().as_str() {
+        "ada" => "Ada",
+        "awk" => "Awk",
+        "bash" | "sh" | "shell" => "bash",
+        "c" => "C",
+        "c++" | "cpp" => "C++",
+        "cobol" => "Cobol",
+        "erlang" => "erlang",
+        "fortran" => "Fortran",
+        "haskell" => "Haskell",
+        "html" => "HTML",
+        "java" => "Java",
+        "lisp" => "Lisp",
+        "lua" => "Lua",
+        "make" | "makefile" => "make",
+        "matlab" => "Matlab",
+        "ocaml" => "ML",
+        "pascal" => "Pascal",
+        "perl" => "Perl",
+        "php" => "PHP",
+        "prolog" => "Prolog",
+        "python" | "py" => "Python",
+        "r" => "R",
+        "ruby" | "rb" => "Ruby",
+        "scala" => "Scala",
+        "sql" => "SQL",
+        "tcl" => "tcl",
+        "tex" | "latex" => "TeX",
+        "verilog" => "Verilog",
+        "vhdl" => "VHDL",
+        "xml" => "XML",
+        _ => return None,
+    })
+}
+
+impl<'a> Output<LatexFormatter> for Paragraph<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        for line in self {
+            let mut formatter = f.clone_without_content();
+            line.fmt(&mut formatter)?;
+            let text = formatter.into_content();
+            let text = text.trim();
+
+            if !text.is_empty() {
+                writeln!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Table<'a> {
+    /// Writes a table in latex as a tabular, using multicolumn and multirow
+    /// for cells spanning more than one column or row and a horizontal line
+    /// in place of the divider row
+    ///
+    /// ```latex
+    /// \begin{tabular}{|l|c|}
+    /// \hline
+    /// \multicolumn{2}{|l|}{a} \\
+    /// \hline
+    /// \multirow{2}{*}{b} & c \\
+    ///  & d \\
+    /// \hline
+    /// \end{tabular}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        let col_cnt = self.col_cnt();
+        let align = |col: usize| match self.get_column_alignment(col) {
+            ColumnAlign::Center => "c",
+            ColumnAlign::Right => "r",
+            ColumnAlign::None | ColumnAlign::Left => "l",
+        };
+
+        // Produces the column specification of a multicolumn starting at the
+        // column, which replaces the lines of the columns it covers
+        let multicolumn_spec = |col: usize| {
+            format!("{}{}|", if col == 0 { "|" } else { "" }, align(col))
+        };
+
+        if self.centered {
+            writeln!(f, r"\begin{{center}}")?;
+        }
+
+        write!(f, r"\begin{{tabular}}{{|")?;
+        for col in 0..col_cnt {
+            write!(f, "{}|", align(col))?;
+        }
+        writeln!(f, "}}")?;
+        writeln!(f, r"\hline")?;
+
+        for row in 0..self.row_cnt() {
+            if self.get_divider_row_index() == Some(row) {
+                writeln!(f, r"\hline")?;
+                continue;
+            }
+
+            let mut col = 0;
+            while col < col_cnt {
+                if col > 0 {
+                    write!(f, " & ")?;
+                }
+
+                match self.get_cell(row, col).map(|x| x.as_inner()) {
+                    Some(Cell::Content(x)) => {
+                        let colspan = self.get_cell_colspan(row, col).max(1);
+                        let rowspan = self.get_cell_rowspan(row, col);
+
+                        let mut formatter = f.clone_without_content();
+                        x.fmt(&mut formatter)?;
+                        let mut text =
+                            formatter.into_content().trim().to_string();
+
+                        if rowspan > 1 {
+                            text = format!(
+                                r"\multirow{{{}}}{{*}}{{{}}}",
+                                rowspan, text
+                            );
+                        }
+
+                        if colspan > 1 {
+                            write!(
+                                f,
+                                r"\multicolumn{{{}}}{{{}}}{{{}}}",
+                                colspan,
+                                multicolumn_spec(col),
+                                text
+                            )?;
+                        } else {
+                            write!(f, "{}", text)?;
+                        }
+
+                        col += colspan;
+                    }
+
+                    // Cells covered by a multirow are left empty, spanning
+                    // the same columns as the cell above
+                    Some(Cell::Span(CellSpan::FromAbove)) => {
+                        let colspan = (0..row)
+                            .rev()
+                            .find(|r| {
+                                matches!(
+                                    self.get_cell(*r, col)
+                                        .map(|x| x.as_inner()),
+                                    Some(Cell::Content(_))
+                                )
+                            })
+                            .map(|r| self.get_cell_colspan(r, col))
+                            .unwrap_or_default()
+                            .max(1);
+
+                        if colspan > 1 {
+                            write!(
+                                f,
+                                r"\multicolumn{{{}}}{{{}}}{{}}",
+                                colspan,
+                                multicolumn_spec(col)
+                            )?;
+                        }
+
+                        col += colspan;
+                    }
+                    _ => col += 1,
+                }
+            }
+
+            writeln!(f, r" \\")?;
+        }
+
+        writeln!(f, r"\hline")?;
+        writeln!(f, r"\end{{tabular}}")?;
+
+        if self.centered {
+            writeln!(f, r"\end{{center}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        write!(f, "{}", escape_text(self.as_str()))?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for DecoratedText<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        let command = match self {
+            Self::Bold(_) => "textbf",
+            Self::Italic(_) => "textit",
+            Self::Strikeout(_) => "sout",
+            Self::Superscript(_) => "textsuperscript",
+            Self::Subscript(_) => "textsubscript",
+        };
+
+        write!(f, r"\{}{{", command)?;
+        for content in self.as_contents_slice() {
+            content.fmt(f)?;
+        }
+        write!(f, "}}")?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<LatexFormatter> for Keyword {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        write!(f, r"\textbf{{{}}}", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Link<'a> {
+    /// Writes a link in latex
+    ///
+    /// ### Wiki/Interwiki/Diary Link
+    ///
+    /// For `[[url|descr]]` and `[[#anchor|descr]]` in vimwiki:
+    ///
+    /// ```latex
+    /// \href{url.pdf}{descr}
+    /// \hyperref[anchor]{descr}
+    /// ```
+    ///
+    /// ### Raw Link
+    ///
+    /// For `https://example.com`:
+    ///
+    /// ```latex
+    /// \url{https://example.com}
+    /// ```
+    ///
+    /// ### Transclusion Link
+    ///
+    /// For `{{path/to/img.png}}`, where remote images are linked instead:
+    ///
+    /// ```latex
+    /// \includegraphics{path/to/img.png}
+    /// ```
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        let target = make_page_link_target(
+            self,
+            &f.config().page_ext,
+            &f.config().diary_rel_path,
+            &f.config().root_rel_path,
+        );
+
+        match self {
+            Self::Raw { .. } => {
+                write!(f, r"\url{{{}}}", escape_url(&target))?;
+                return Ok(());
+            }
+            Self::Transclusion { data } if data.is_local() => {
+                write!(
+                    f,
+                    r"\includegraphics{{{}}}",
+                    LinkData::decode_uri(data.uri_ref.path().to_string())
+                )?;
+                return Ok(());
+            }
+            Self::Wiki { data } if data.is_local_anchor() => {
+                write!(f, r"\hyperref[{}]{{", target.trim_start_matches('#'))?;
+            }
+            _ => write!(f, r"\href{{{}}}{{", escape_url(&target))?,
+        }
+
+        match self.to_description_or_fallback() {
+            Some(Description::Text(x)) => write!(f, "{}", escape_text(&x))?,
+            Some(Description::TransclusionLink(data)) => {
+                Link::Transclusion { data: *data }.fmt(f)?
+            }
+            None => write!(f, "{}", escape_text(&target))?,
+        }
+        write!(f, "}}")?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Tags<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        write!(f, ":")?;
+
+        for tag in self {
+            write!(f, "{}:", escape_text(tag.as_str()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for CodeInline<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        write!(f, r"\texttt{{{}}}", escape_text(self.as_str()))?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for MathInline<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        write!(f, "${}$", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for Comment<'a> {
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        match self {
+            Self::Line(x) => x.fmt(f),
+            Self::MultiLine(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<LatexFormatter> for LineComment<'a> {
+    /// Writes a line comment in latex, which comments out the remainder of
+    /// the line just like in vimwiki
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        write!(f, "% {}", self.as_str().trim())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<LatexFormatter> for MultiLineComment<'a> {
+    /// Writes a multiline comment in latex as a series of line comments,
+    /// ending the line so content after the comment is not commented out
+    fn fmt(&self, f: &mut LatexFormatter) -> LatexOutputResult {
+        for line in self {
+            writeln!(f, "% {}", line.trim())?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes characters in text that latex would otherwise interpret as
+/// commands or other special syntax
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\textbackslash{}"),
+            '~' => escaped.push_str(r"\textasciitilde{}"),
+            '^' => escaped.push_str(r"\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Escapes characters in a url that have special meaning within the
+/// arguments of `\href` and `\url`
+fn escape_url(url: &str) -> String {
+    url.replace('\\', r"\\")
+        .replace('#', r"\#")
+        .replace('%', r"\%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+    use similar_asserts::assert_str_eq;
+
+    fn to_latex(text: &str) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_latex_string(LatexConfig {
+            standalone: false,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn page_should_write_document_with_placeholders_if_standalone() {
+        let text = indoc! {"
+            %title First
+            %date 2021-06-07
+            %title Some & Title
+            %author Me
+            = Header =
+        "};
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let latex = page.to_latex_string(LatexConfig::default()).unwrap();
+
+        assert!(latex.starts_with("\\documentclass{article}\n"));
+        assert!(latex.contains(
+            "\\title{Some \\& Title}\n\\author{Me}\n\\date{2021-06-07}\n"
+        ));
+        assert!(!latex.contains("First"));
+        assert!(latex.ends_with(indoc! {r"
+            \begin{document}
+            \maketitle
+            \section{Header}\label{header}
+
+            \end{document}
+        "}));
+    }
+
+    #[test]
+    fn header_should_use_sectioning_command_for_level() {
+        assert_str_eq!(
+            to_latex("=== Some *Header* ===\n"),
+            "\\subsubsection{Some \\textbf{Header}}\\label{some-header}\n"
+        );
+        assert_str_eq!(
+            to_latex("====== Deep ======\n"),
+            "\\subparagraph{Deep}\\label{deep}\n"
+        );
+    }
+
+    #[test]
+    fn math_should_pass_through_environments() {
+        let text = indoc! {r"
+            inline $x_1^2$
+            {{$%align%
+            a &= b \\
+            }}$
+            {{$
+            c
+            }}$
+        "};
+
+        assert_str_eq!(
+            to_latex(text),
+            indoc! {r"
+                inline $x_1^2$
+
+                \begin{align}
+                a &= b \\
+                \end{align}
+
+                \[
+                c
+                \]
+            "}
+        );
+    }
+
+    #[test]
+    fn list_should_label_ordered_lists_and_todos() {
+        let text = indoc! {"
+            a) first
+            b) second
+                - [X] done
+                - [ ] todo
+        "};
+
+        assert_str_eq!(
+            to_latex(text),
+            indoc! {r"
+                \begin{enumerate}[label=\alph*)]
+                \item first
+                \item second
+                \begin{itemize}
+                \item[$\boxtimes$] done
+                \item[$\square$] todo
+                \end{itemize}
+                \end{enumerate}
+            "}
+        );
+    }
+
+    #[test]
+    fn table_should_use_multicolumn_and_multirow_for_spans() {
+        let text = indoc! {r"
+            | a  | >  | b |
+            |----|:--:|---|
+            | c  | d  | e |
+            | \/ | f  | g |
+        "};
+
+        assert_str_eq!(
+            to_latex(text),
+            indoc! {r"
+                \begin{tabular}{|l|c|l|}
+                \hline
+                \multicolumn{2}{|l|}{a} & b \\
+                \hline
+                \multirow{2}{*}{c} & d & e \\
+                 & f & g \\
+                \hline
+                \end{tabular}
+            "}
+        );
+    }
+
+    #[test]
+    fn code_should_use_listings_for_supported_languages() {
+        let text = indoc! {"
+            {{{python
+            print('%s' % x)
+            }}}
+            {{{rust
+            fn main() {}
+            }}}
+        "};
+
+        assert_str_eq!(
+            to_latex(text),
+            indoc! {r"
+                \begin{lstlisting}[language=Python]
+                print('%s' % x)
+                \end{lstlisting}
+
+                \begin{lstlisting}
+                fn main() {}
+                \end{lstlisting}
+            "}
+        );
+    }
+
+    #[test]
+    fn link_should_use_href_and_escape_text() {
+        let text = indoc! {"
+            [[some page#Part One|50% off]] [[#Top]] https://example.com/a#b
+            {{img.png}} `a_b`
+        "};
+
+        assert_str_eq!(
+            to_latex(text),
+            indoc! {r"
+                \href{some\%20page.pdf\#part-one}{50\% off} \hyperref[top]{\#Top} \url{https://example.com/a\#b}
+                \includegraphics{img.png} \texttt{a\_b}
+            "}
+        );
+    }
+}
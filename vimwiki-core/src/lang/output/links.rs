@@ -0,0 +1,99 @@
+use crate::lang::elements::{Link, LinkData};
+
+/// Produces the destination of a link for output formats that write each
+/// page of a wiki to its own file, mirroring the layout of the wiki
+///
+/// * `page_ext` - extension given to the files of pages (e.g. `md`)
+/// * `diary_rel_path` - path to the diary relative to the root of the wiki
+/// * `root_rel_path` - path from the current page to the root of the wiki
+///   (e.g. `../`), used for links relative to the root of a wiki
+pub(crate) fn make_page_link_target(
+    link: &Link<'_>,
+    page_ext: &str,
+    diary_rel_path: &str,
+    root_rel_path: &str,
+) -> String {
+    let data = link.data();
+    let anchor = data
+        .to_anchor()
+        .and_then(|anchor| {
+            anchor.last().map(|x| {
+                format!("#{}", slugify(&LinkData::decode_uri(x.as_ref())))
+            })
+        })
+        .unwrap_or_default();
+
+    // Produces the path to a page with the page extension, optionally
+    // relative to the root of the wiki
+    let make_page_path = |from_root: bool| {
+        let path = data
+            .uri_ref
+            .path()
+            .segments()
+            .iter()
+            .map(|s| LinkData::decode_uri(s.as_str()))
+            .collect::<Vec<String>>()
+            .join("/");
+        let path = path.trim_start_matches('/').to_string();
+
+        let mut path = if from_root || data.uri_ref.path().is_absolute() {
+            format!("{}{}", root_rel_path, path)
+        } else {
+            path
+        };
+
+        // TODO: Support alternative directory file name
+        if data.is_path_dir() {
+            path.push_str("index");
+        }
+
+        format!("{}.{}{}", encode_path(&path), page_ext, anchor)
+    };
+
+    match link {
+        Link::Wiki { data } if data.scheme().is_some() => {
+            data.uri_ref.to_string()
+        }
+        Link::Wiki { data } if data.is_local_anchor() => anchor,
+        Link::Wiki { .. } => make_page_path(false),
+
+        // NOTE: The locations of other wikis are unknown, so their pages are
+        //       resolved relative to the root of the current wiki
+        Link::IndexedInterWiki { .. } | Link::NamedInterWiki { .. } => {
+            make_page_path(true)
+        }
+        Link::Diary { date, .. } => format!(
+            "{}{}/{}.{}{}",
+            root_rel_path,
+            encode_path(diary_rel_path.trim_end_matches('/')),
+            date.format("%Y-%m-%d"),
+            page_ext,
+            anchor,
+        ),
+        Link::Raw { data } | Link::Transclusion { data } => {
+            data.uri_ref.to_string()
+        }
+    }
+}
+
+/// Encodes the characters of a path that commonly cannot appear unescaped
+/// within the link destinations of output formats
+fn encode_path(path: &str) -> String {
+    path.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+/// Produces the github-style identifier of a header from its text, which is
+/// lowercase with spaces converted to hyphens and punctuation removed
+pub(crate) fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents configuration properties for markdown output that are separate
+/// from the running state during markdown conversion
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MarkdownConfig {
+    /// Flavor of markdown to produce, which determines whether extensions
+    /// such as pipe tables and strikethrough are available
+    #[serde(default = "MarkdownConfig::default_flavor")]
+    pub flavor: MarkdownFlavor,
+
+    /// Extension given to the paths of wiki pages when writing links
+    #[serde(default = "MarkdownConfig::default_page_ext")]
+    pub page_ext: String,
+
+    /// Path to the diary relative to the root of the wiki, used when writing
+    /// diary links
+    #[serde(default = "MarkdownConfig::default_diary_rel_path")]
+    pub diary_rel_path: String,
+
+    /// Path from the page being written to the root of its wiki (e.g. `../`),
+    /// used when writing links that are relative to the root of a wiki
+    #[serde(default)]
+    pub root_rel_path: String,
+
+    /// If true, will write the placeholders of a page as YAML front matter
+    /// at the top of the page instead of discarding them
+    #[serde(default = "MarkdownConfig::default_front_matter")]
+    pub front_matter: bool,
+}
+
+impl Default for MarkdownConfig {
+    fn default() -> Self {
+        Self {
+            flavor: Self::default_flavor(),
+            page_ext: Self::default_page_ext(),
+            diary_rel_path: Self::default_diary_rel_path(),
+            root_rel_path: String::new(),
+            front_matter: Self::default_front_matter(),
+        }
+    }
+}
+
+impl MarkdownConfig {
+    #[inline]
+    pub fn default_flavor() -> MarkdownFlavor {
+        MarkdownFlavor::default()
+    }
+
+    #[inline]
+    pub fn default_page_ext() -> String {
+        String::from("md")
+    }
+
+    #[inline]
+    pub fn default_diary_rel_path() -> String {
+        String::from("diary")
+    }
+
+    #[inline]
+    pub fn default_front_matter() -> bool {
+        false
+    }
+
+    /// Returns true if configured to produce github-flavored markdown
+    #[inline]
+    pub fn is_gfm(&self) -> bool {
+        matches!(self.flavor, MarkdownFlavor::Gfm)
+    }
+}
+
+/// Represents the flavor of markdown to produce
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkdownFlavor {
+    /// Only use syntax defined by the CommonMark specification, falling back
+    /// to inline HTML for tables and strikethrough
+    #[serde(alias = "commonmark")]
+    CommonMark,
+
+    /// Use github-flavored markdown extensions such as pipe tables,
+    /// strikethrough, and task list items
+    Gfm,
+}
+
+impl Default for MarkdownFlavor {
+    fn default() -> Self {
+        Self::Gfm
+    }
+}
+
+impl std::str::FromStr for MarkdownFlavor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "commonmark" | "common_mark" => Ok(Self::CommonMark),
+            "gfm" => Ok(Self::Gfm),
+            _ => Err(format!("Unknown markdown flavor: {}", s)),
+        }
+    }
+}
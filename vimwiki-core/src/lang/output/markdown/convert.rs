@@ -0,0 +1,56 @@
+use super::{MarkdownConfig, MarkdownFormatter, MarkdownOutputError, Output};
+
+pub trait ToMarkdownString {
+    fn to_markdown_string(
+        &self,
+        config: MarkdownConfig,
+    ) -> Result<String, MarkdownOutputError>;
+}
+
+impl<T: Output<MarkdownFormatter>> ToMarkdownString for T {
+    fn to_markdown_string(
+        &self,
+        config: MarkdownConfig,
+    ) -> Result<String, MarkdownOutputError> {
+        let mut formatter = MarkdownFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_content())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MarkdownOutputResult;
+
+    struct TestOutput<F: Fn(&mut MarkdownFormatter) -> MarkdownOutputResult>(F);
+    impl<F: Fn(&mut MarkdownFormatter) -> MarkdownOutputResult>
+        Output<MarkdownFormatter> for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    fn _text(
+        text: impl Into<String>,
+    ) -> impl Fn(&mut MarkdownFormatter) -> MarkdownOutputResult {
+        let text = text.into();
+        move |f: &mut MarkdownFormatter| {
+            use std::fmt::Write;
+            write!(f, "{}", text.as_str())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_markdown_string_should_produce_a_string_representing_only_the_markdown_of_the_output(
+    ) {
+        let output = TestOutput(_text("**I am some markdown output**"));
+        let result = output
+            .to_markdown_string(MarkdownConfig::default())
+            .unwrap();
+        assert_eq!(result, "**I am some markdown output**");
+    }
+}
@@ -0,0 +1,11 @@
+use derive_more::{Display, Error, From};
+
+pub type MarkdownOutputResult = Result<(), MarkdownOutputError>;
+
+#[derive(Debug, From, Display, Error)]
+pub enum MarkdownOutputError {
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
@@ -0,0 +1,1032 @@
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::MarkdownFormatter;
+
+mod convert;
+pub use convert::ToMarkdownString;
+
+mod error;
+pub use error::{MarkdownOutputError, MarkdownOutputResult};
+
+use crate::{
+    lang::{
+        elements::*,
+        output::{make_page_link_target, Output, OutputFormatter},
+    },
+    wiki::{MetadataValue, PageMetadata},
+};
+use std::fmt::Write;
+
+impl<'a> Output<MarkdownFormatter> for Page<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        if f.config().front_matter {
+            write_front_matter(self, f)?;
+        }
+
+        for element in self.elements.iter() {
+            // Separate each block element that produces output with a blank
+            // line, as markdown would otherwise merge some of them together
+            let mut formatter = f.clone_without_content();
+            element.fmt(&mut formatter)?;
+            let text = formatter.into_content();
+
+            if !text.is_empty() {
+                if !f.get_content().is_empty() {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the metadata of a page as YAML front matter ordered by name,
+/// writing nothing if the page has no metadata
+///
+/// Text is quoted while booleans, integers, and dates are written as-is and
+/// lists are written as sequences of quoted text
+fn write_front_matter(
+    page: &Page<'_>,
+    f: &mut MarkdownFormatter,
+) -> MarkdownOutputResult {
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    let metadata = PageMetadata::from_page(page);
+    if metadata.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f, "---")?;
+    for (name, value) in metadata.iter() {
+        match value {
+            MetadataValue::Text(x) => writeln!(f, "{}: {}", name, quote(x))?,
+            MetadataValue::Bool(x) => writeln!(f, "{}: {}", name, x)?,
+            MetadataValue::Integer(x) => writeln!(f, "{}: {}", name, x)?,
+            MetadataValue::Date(x) => {
+                writeln!(f, "{}: {}", name, x.format("%Y-%m-%d"))?
+            }
+            MetadataValue::List(x) => writeln!(
+                f,
+                "{}: [{}]",
+                name,
+                x.iter()
+                    .map(|x| quote(x))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )?,
+        }
+    }
+    writeln!(f, "---")?;
+
+    Ok(())
+}
+
+impl<'a> Output<MarkdownFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Blockquote<'a> {
+    /// Writes a blockquote in markdown
+    ///
+    /// ```markdown
+    /// > line 1
+    /// >
+    /// > line 2
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        f.and_prefix("> ", |f| {
+            for line in self {
+                writeln!(f, "{}", line.trim())?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for DefinitionList<'a> {
+    /// Writes a definition list in markdown, which has no equivalent, so each
+    /// term is written in bold followed by a list of its definitions
+    ///
+    /// ```markdown
+    /// **term**
+    /// - definition 1
+    /// - definition 2
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        for (idx, (term, defs)) in self.iter().enumerate() {
+            // NOTE: A blank line is needed to keep the next term from being
+            //       treated as part of the last definition
+            if idx > 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(
+                f,
+                "**{}**",
+                render_inline(f, term.as_inner().as_inner())?
+            )?;
+            for def in defs {
+                writeln!(
+                    f,
+                    "- {}",
+                    render_inline(f, def.as_inner().as_inner())?
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<MarkdownFormatter> for Divider {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        writeln!(f, "---")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Header<'a> {
+    /// Writes a header in markdown, dropping any centering
+    ///
+    /// ```markdown
+    /// ## header
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        writeln!(
+            f,
+            "{} {}",
+            "#".repeat(self.level.min(6)),
+            render_inline(f, &self.content)?
+        )?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for List<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        for item in self {
+            item.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for ListItem<'a> {
+    /// Writes a list item in markdown, numbering ordered items as markdown
+    /// only supports numbers and writing any todo status as a task
+    ///
+    /// ```markdown
+    /// - [ ] incomplete
+    /// - [x] complete
+    /// 1. item
+    ///    continued content
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        let marker = match &self.ty {
+            ListItemType::Ordered(_) => format!(
+                "{}{}",
+                self.pos + 1,
+                if self.suffix == ListItemSuffix::Paren {
+                    ')'
+                } else {
+                    '.'
+                }
+            ),
+            ListItemType::Unordered(UnorderedListItemType::Asterisk) => {
+                String::from("*")
+            }
+            ListItemType::Unordered(_) => String::from("-"),
+        };
+
+        write!(f, "{} ", marker)?;
+
+        if let Some(todo_status) = self.attributes.todo_status.as_ref() {
+            todo_status.fmt(f)?;
+            write!(f, " ")?;
+        }
+
+        if self.contents.is_empty() {
+            writeln!(f)?;
+            return Ok(());
+        }
+
+        // Content beyond the first line is aligned with the content
+        // following the marker
+        f.and_prefix(" ".repeat(marker.len() + 1), |f| {
+            for (idx, content) in self.contents.iter().enumerate() {
+                // NOTE: Sublists can immediately follow other content, but
+                //       anything else needs a blank line to start a new block
+                if idx > 0
+                    && !matches!(content.as_inner(), BlockElement::List(_))
+                {
+                    writeln!(f)?;
+                }
+
+                content.fmt(f)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl Output<MarkdownFormatter> for ListItemTodoStatus {
+    /// Writes a todo status as a task list checkbox, where only a complete
+    /// status is checked
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        match self {
+            Self::Complete => write!(f, "[x]")?,
+            _ => write!(f, "[ ]")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for MathBlock<'a> {
+    /// Writes a math block in markdown, wrapping the lines in the block's
+    /// environment if it has one
+    ///
+    /// ```markdown
+    /// $$
+    /// \begin{align}
+    /// some math
+    /// \end{align}
+    /// $$
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        writeln!(f, "$$")?;
+
+        if let Some(env) = self.environment.as_ref() {
+            writeln!(f, r"\begin{{{}}}", env)?;
+        }
+
+        for line in self {
+            writeln!(f, "{}", line)?;
+        }
+
+        if let Some(env) = self.environment.as_ref() {
+            writeln!(f, r"\end{{{}}}", env)?;
+        }
+
+        writeln!(f, "$$")?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Placeholder<'a> {
+    /// Writes nothing as markdown has no placeholders, leaving the page to
+    /// write them as front matter if configured to do so
+    fn fmt(&self, _f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for CodeBlock<'a> {
+    /// Writes a code block in markdown as a fenced block, using the language
+    /// or brush of the code block as the info string
+    ///
+    /// ```markdown
+    /// ```python
+    /// some code
+    /// ```
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        let language = self.language_or_brush();
+
+        // The fence must be longer than any run of backticks in the code
+        let fence = "`".repeat(
+            self.lines
+                .iter()
+                .map(|line| longest_backtick_run(line))
+                .max()
+                .unwrap_or_default()
+                .max(2)
+                + 1,
+        );
+
+        writeln!(f, "{}{}", fence, language.unwrap_or_default())?;
+        for line in self {
+            writeln!(f, "{}", line)?;
+        }
+        writeln!(f, "{}", fence)?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Paragraph<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        for line in self {
+            let text = render_inline(f, line)?;
+            if !text.is_empty() {
+                writeln!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Table<'a> {
+    /// Writes a table in markdown as a pipe table when using github-flavored
+    /// markdown and the table has a single header row and no spans, and
+    /// otherwise as an HTML table
+    ///
+    /// ```markdown
+    /// | a | b |
+    /// |---|:-:|
+    /// | c | d |
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        let has_spans = self
+            .cells()
+            .any(|cell| matches!(cell.as_inner(), Cell::Span(_)));
+
+        if f.config().is_gfm()
+            && self.get_divider_row_index() == Some(1)
+            && !has_spans
+        {
+            write_pipe_table(self, f)
+        } else {
+            write_html_table(self, f)
+        }
+    }
+}
+
+/// Writes a table with a header row and no spans as a pipe table
+fn write_pipe_table(
+    table: &Table<'_>,
+    f: &mut MarkdownFormatter,
+) -> MarkdownOutputResult {
+    let write_row = |f: &mut MarkdownFormatter,
+                     row: usize|
+     -> MarkdownOutputResult {
+        write!(f, "|")?;
+        for col in 0..table.col_cnt() {
+            let text = match table.get_cell(row, col).map(|x| x.as_inner()) {
+                Some(Cell::Content(x)) => {
+                    let mut formatter = f.clone_without_content();
+                    formatter.and_table_cell(|f| x.fmt(f))?;
+                    formatter.into_content().trim().to_string()
+                }
+                _ => String::new(),
+            };
+            write!(f, " {} |", text)?;
+        }
+        writeln!(f)?;
+        Ok(())
+    };
+
+    write_row(f, 0)?;
+
+    write!(f, "|")?;
+    for col in 0..table.col_cnt() {
+        match table.get_column_alignment(col) {
+            ColumnAlign::None => write!(f, "---|")?,
+            ColumnAlign::Left => write!(f, ":---|")?,
+            ColumnAlign::Center => write!(f, ":---:|")?,
+            ColumnAlign::Right => write!(f, "---:|")?,
+        }
+    }
+    writeln!(f)?;
+
+    for row in 2..table.row_cnt() {
+        write_row(f, row)?;
+    }
+
+    Ok(())
+}
+
+/// Writes a table as HTML, supporting spans and any number of header rows
+///
+/// NOTE: Markdown is not processed within an HTML block unless separated by
+///       blank lines, so the content of each cell is surrounded by them
+fn write_html_table(
+    table: &Table<'_>,
+    f: &mut MarkdownFormatter,
+) -> MarkdownOutputResult {
+    let divider = table.get_divider_row_index();
+
+    writeln!(f, "<table>")?;
+    for row in 0..table.row_cnt() {
+        if Some(row) == divider {
+            continue;
+        }
+
+        let is_header = divider.map_or(false, |idx| row < idx);
+        let tag = if is_header { "th" } else { "td" };
+
+        writeln!(f, "<tr>")?;
+        for col in 0..table.col_cnt() {
+            match table.get_cell(row, col).map(|x| x.as_inner()) {
+                Some(Cell::Content(x)) => {
+                    write!(f, "<{}", tag)?;
+
+                    let rowspan = table.get_cell_rowspan(row, col);
+                    if rowspan > 1 {
+                        write!(f, " rowspan=\"{}\"", rowspan)?;
+                    }
+
+                    let colspan = table.get_cell_colspan(row, col);
+                    if colspan > 1 {
+                        write!(f, " colspan=\"{}\"", colspan)?;
+                    }
+
+                    let text = render_inline(f, x)?;
+                    if text.is_empty() {
+                        writeln!(f, "></{}>", tag)?;
+                    } else {
+                        writeln!(f, ">\n\n{}\n\n</{}>", text, tag)?;
+                    }
+                }
+                Some(Cell::Span(_)) => {}
+                _ => writeln!(f, "<{0}></{0}>", tag)?,
+            }
+        }
+        writeln!(f, "</tr>")?;
+    }
+    writeln!(f, "</table>")?;
+
+    Ok(())
+}
+
+impl<'a> Output<MarkdownFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        let text = escape_text(self.as_str(), f.is_in_table_cell());
+        write!(f, "{}", text)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for DecoratedText<'a> {
+    /// Writes decorated text in markdown, using HTML for superscript and
+    /// subscript as well as strikeout when not using github-flavored markdown
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        let (start, end) = match self {
+            Self::Bold(_) => ("**", "**"),
+            Self::Italic(_) => ("*", "*"),
+            Self::Strikeout(_) if f.config().is_gfm() => ("~~", "~~"),
+            Self::Strikeout(_) => ("<del>", "</del>"),
+            Self::Superscript(_) => ("<sup>", "</sup>"),
+            Self::Subscript(_) => ("<sub>", "</sub>"),
+        };
+
+        write!(f, "{}", start)?;
+        for content in self.as_contents_slice() {
+            content.fmt(f)?;
+        }
+        write!(f, "{}", end)?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<MarkdownFormatter> for Keyword {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        write!(f, "{}", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Link<'a> {
+    /// Writes a link in markdown
+    ///
+    /// ### Wiki/Interwiki/Diary Link
+    ///
+    /// For `[[url#anchor|descr]]` in vimwiki:
+    ///
+    /// ```markdown
+    /// [descr](url.md#anchor)
+    /// ```
+    ///
+    /// ### Raw Link
+    ///
+    /// For `https://example.com`:
+    ///
+    /// ```markdown
+    /// <https://example.com>
+    /// ```
+    ///
+    /// ### Transclusion Link
+    ///
+    /// For `{{path/to/img.png|descr}}`:
+    ///
+    /// ```markdown
+    /// ![descr](path/to/img.png)
+    /// ```
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        let target = make_page_link_target(
+            self,
+            &f.config().page_ext,
+            &f.config().diary_rel_path,
+            &f.config().root_rel_path,
+        );
+
+        match self {
+            Self::Raw { data } if data.scheme().is_some() => {
+                write!(f, "<{}>", target)?;
+            }
+            Self::Transclusion { data } => {
+                let alt = match data.description.as_ref() {
+                    Some(Description::Text(x)) => {
+                        escape_text(x, f.is_in_table_cell())
+                    }
+                    _ => String::new(),
+                };
+                write!(f, "![{}]({})", alt, target)?;
+            }
+            _ => {
+                write!(f, "[")?;
+                match self.to_description_or_fallback() {
+                    Some(Description::Text(x)) => {
+                        write!(f, "{}", escape_text(&x, f.is_in_table_cell()))?
+                    }
+                    Some(Description::TransclusionLink(data)) => {
+                        Link::Transclusion { data: *data }.fmt(f)?
+                    }
+                    None => write!(
+                        f,
+                        "{}",
+                        escape_text(&target, f.is_in_table_cell())
+                    )?,
+                }
+                write!(f, "]({})", target)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Tags<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        write!(f, ":")?;
+
+        for tag in self {
+            write!(f, "{}:", escape_text(tag.as_str(), f.is_in_table_cell()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for CodeInline<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        let code = if f.is_in_table_cell() {
+            self.as_str().replace('|', r"\|")
+        } else {
+            self.as_str().to_string()
+        };
+
+        // The delimiter must be longer than any run of backticks in the code,
+        // with padding to keep backticks at the edges from joining it
+        let fence = "`".repeat(longest_backtick_run(&code) + 1);
+        if code.starts_with('`') || code.ends_with('`') {
+            write!(f, "{0} {1} {0}", fence, code)?;
+        } else {
+            write!(f, "{0}{1}{0}", fence, code)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for MathInline<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        write!(f, "${}$", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for Comment<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        match self {
+            Self::Line(x) => x.fmt(f),
+            Self::MultiLine(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for LineComment<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        write!(f, "<!-- {} -->", self.as_str().trim())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<MarkdownFormatter> for MultiLineComment<'a> {
+    fn fmt(&self, f: &mut MarkdownFormatter) -> MarkdownOutputResult {
+        write!(f, "<!--")?;
+        for line in self {
+            write!(f, "\n{}", line)?;
+        }
+        write!(f, "\n-->")?;
+        Ok(())
+    }
+}
+
+/// Renders inline content into a string without leading or trailing
+/// whitespace, for use in elements that must be written on a single line
+fn render_inline(
+    f: &MarkdownFormatter,
+    content: &InlineElementContainer<'_>,
+) -> Result<String, MarkdownOutputError> {
+    let mut formatter = f.clone_without_content();
+    content.fmt(&mut formatter)?;
+    Ok(formatter.into_content().trim().to_string())
+}
+
+/// Escapes characters in text that markdown would otherwise interpret as
+/// formatting, including pipes when within a pipe table
+///
+/// NOTE: Underscores within words are left alone as they never produce
+///       emphasis in markdown
+fn escape_text(text: &str, escape_pipes: bool) -> String {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut escaped = String::with_capacity(text.len());
+
+    for (idx, c) in chars.iter().copied().enumerate() {
+        let is_intraword = || {
+            idx > 0
+                && chars[idx - 1].is_alphanumeric()
+                && chars.get(idx + 1).map_or(false, |c| c.is_alphanumeric())
+        };
+
+        let needs_escape = match c {
+            '\\' | '`' | '*' | '[' | ']' | '<' | '>' | '~' => true,
+            '_' => !is_intraword(),
+            '|' => escape_pipes,
+            _ => false,
+        };
+
+        if needs_escape {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Returns the length of the longest run of backticks within the text
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+    use similar_asserts::assert_str_eq;
+
+    fn to_markdown(text: &str, config: MarkdownConfig) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_markdown_string(config).unwrap()
+    }
+
+    #[test]
+    fn page_should_separate_block_elements_with_blank_lines() {
+        let text = indoc! {"
+            = Header =
+            some *bold* and _italic_ text
+            ----
+            > quoted
+        "};
+
+        assert_str_eq!(
+            to_markdown(text, MarkdownConfig::default()),
+            indoc! {"
+                # Header
+
+                some **bold** and *italic* text
+
+                ---
+
+                > quoted
+            "}
+        );
+    }
+
+    #[test]
+    fn page_should_write_placeholders_as_front_matter_if_configured() {
+        let text = indoc! {r#"
+            %title My "Page"
+            %date 2021-06-07
+            some text
+        "#};
+
+        assert_str_eq!(
+            to_markdown(text, MarkdownConfig::default()),
+            "some text\n"
+        );
+
+        let config = MarkdownConfig {
+            front_matter: true,
+            ..Default::default()
+        };
+        assert_str_eq!(
+            to_markdown(text, config),
+            indoc! {r#"
+                ---
+                date: 2021-06-07
+                title: "My \"Page\""
+                ---
+
+                some text
+            "#}
+        );
+    }
+
+    #[test]
+    fn front_matter_should_write_values_according_to_their_kind() {
+        let text = indoc! {"
+            %title First
+            %title Second
+            %draft true
+            %priority 10
+            %tags a, b
+            %nohtml
+            some text
+        "};
+
+        let config = MarkdownConfig {
+            front_matter: true,
+            ..Default::default()
+        };
+        assert_str_eq!(
+            to_markdown(text, config),
+            indoc! {r#"
+                ---
+                draft: true
+                nohtml: true
+                priority: 10
+                tags: ["a", "b"]
+                title: "Second"
+                ---
+
+                some text
+            "#}
+        );
+    }
+
+    #[test]
+    fn list_should_write_todos_as_tasks_and_indent_nested_content() {
+        let text = indoc! {"
+            - [ ] incomplete
+            - [X] complete
+              - [.] nested
+
+            a) lettered
+            b) second
+        "};
+
+        assert_str_eq!(
+            to_markdown(text, MarkdownConfig::default()),
+            indoc! {"
+                - [ ] incomplete
+                - [x] complete
+                  - [ ] nested
+
+                1) lettered
+                2) second
+            "}
+        );
+    }
+
+    #[test]
+    fn link_should_point_wiki_links_at_markdown_pages() {
+        let text = indoc! {"
+            [[some page|desc]] [[dir/]] [[#My Anchor]] [[/root#a#Sub Part]]
+            [[diary:2021-03-05]] [[wiki1:other]] https://example.com/page
+            {{img.png|alt text}}
+        "};
+        let config = MarkdownConfig {
+            root_rel_path: String::from("../"),
+            ..Default::default()
+        };
+
+        assert_str_eq!(
+            to_markdown(text, config),
+            indoc! {"
+                [desc](some%20page.md) [dir/](dir/index.md) [#My Anchor](#my-anchor) [/root#a#Sub Part](../root.md#sub-part)
+                [diary:2021-03-05](../diary/2021-03-05.md) [other](../other.md) <https://example.com/page>
+                ![alt text](img.png)
+            "}
+        );
+    }
+
+    #[test]
+    fn table_should_use_pipe_table_if_possible() {
+        let text = indoc! {"
+            | a | b |
+            |---|--:|
+            | c | d |
+        "};
+
+        assert_str_eq!(
+            to_markdown(text, MarkdownConfig::default()),
+            indoc! {"
+                | a | b |
+                |---|---:|
+                | c | d |
+            "}
+        );
+    }
+
+    #[test]
+    fn table_should_fall_back_to_html_for_spans_or_commonmark() {
+        let text = indoc! {"
+            | *a* | > |
+            | c   | d |
+        "};
+
+        let expected = indoc! {"
+            <table>
+            <tr>
+            <td colspan=\"2\">
+
+            **a**
+
+            </td>
+            </tr>
+            <tr>
+            <td>
+
+            c
+
+            </td>
+            <td>
+
+            d
+
+            </td>
+            </tr>
+            </table>
+        "};
+
+        assert_str_eq!(to_markdown(text, MarkdownConfig::default()), expected);
+
+        let config = MarkdownConfig {
+            flavor: MarkdownFlavor::CommonMark,
+            ..Default::default()
+        };
+        assert_str_eq!(
+            to_markdown("| a |\n|---|\n| b |\n", config),
+            indoc! {"
+                <table>
+                <tr>
+                <th>
+
+                a
+
+                </th>
+                </tr>
+                <tr>
+                <td>
+
+                b
+
+                </td>
+                </tr>
+                </table>
+            "}
+        );
+    }
+
+    #[test]
+    fn math_and_code_should_use_dollars_and_fences() {
+        let text = indoc! {r#"
+            inline $x^2$ and `code`
+            {{$%align%
+            a &= b
+            }}$
+            {{{class="brush: rust"
+            let s = "```";
+            }}}
+        "#};
+
+        assert_str_eq!(
+            to_markdown(text, MarkdownConfig::default()),
+            indoc! {r#"
+                inline $x^2$ and `code`
+
+                $$
+                \begin{align}
+                a &= b
+                \end{align}
+                $$
+
+                ````rust
+                let s = "```";
+                ````
+            "#}
+        );
+    }
+
+    #[test]
+    fn text_should_escape_markdown_characters() {
+        let text = "*not* _bold_ [x] ~~gone~~ 2 * 3\n";
+        let config = MarkdownConfig {
+            flavor: MarkdownFlavor::CommonMark,
+            ..Default::default()
+        };
+
+        assert_str_eq!(
+            to_markdown(text, config),
+            "**not** *bold* \\[x\\] <del>gone</del> 2 \\* 3\n"
+        );
+
+        assert_eq!(
+            escape_text("snake_case _a_ a|b", true),
+            r"snake_case \_a\_ a\|b"
+        );
+    }
+}
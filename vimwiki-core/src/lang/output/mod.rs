@@ -3,6 +3,27 @@ mod html;
 #[cfg(feature = "html")]
 pub use html::*;
 
+mod gemtext;
+pub use gemtext::*;
+
+mod latex;
+pub use latex::*;
+
+mod links;
+pub(crate) use links::{make_page_link_target, slugify};
+
+mod markdown;
+pub use markdown::*;
+
+mod org;
+pub use org::*;
+
+mod pandoc;
+pub use pandoc::*;
+
+mod text;
+pub use text::*;
+
 mod vimwiki;
 pub use self::vimwiki::*;
 
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents configuration properties for org output that are separate
+/// from the running state during org conversion
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrgConfig {
+    /// Extension given to the paths of wiki pages when writing links
+    #[serde(default = "OrgConfig::default_page_ext")]
+    pub page_ext: String,
+
+    /// Path to the diary relative to the root of the wiki, used when writing
+    /// diary links
+    #[serde(default = "OrgConfig::default_diary_rel_path")]
+    pub diary_rel_path: String,
+
+    /// Path from the page being written to the root of its wiki (e.g. `../`),
+    /// used when writing links that are relative to the root of a wiki
+    #[serde(default)]
+    pub root_rel_path: String,
+}
+
+impl Default for OrgConfig {
+    fn default() -> Self {
+        Self {
+            page_ext: Self::default_page_ext(),
+            diary_rel_path: Self::default_diary_rel_path(),
+            root_rel_path: String::new(),
+        }
+    }
+}
+
+impl OrgConfig {
+    #[inline]
+    pub fn default_page_ext() -> String {
+        String::from("org")
+    }
+
+    #[inline]
+    pub fn default_diary_rel_path() -> String {
+        String::from("diary")
+    }
+}
@@ -0,0 +1,54 @@
+use super::{OrgConfig, OrgFormatter, OrgOutputError, Output};
+
+pub trait ToOrgString {
+    fn to_org_string(
+        &self,
+        config: OrgConfig,
+    ) -> Result<String, OrgOutputError>;
+}
+
+impl<T: Output<OrgFormatter>> ToOrgString for T {
+    fn to_org_string(
+        &self,
+        config: OrgConfig,
+    ) -> Result<String, OrgOutputError> {
+        let mut formatter = OrgFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_content())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrgOutputResult;
+
+    struct TestOutput<F: Fn(&mut OrgFormatter) -> OrgOutputResult>(F);
+    impl<F: Fn(&mut OrgFormatter) -> OrgOutputResult> Output<OrgFormatter>
+        for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    fn _text(
+        text: impl Into<String>,
+    ) -> impl Fn(&mut OrgFormatter) -> OrgOutputResult {
+        let text = text.into();
+        move |f: &mut OrgFormatter| {
+            use std::fmt::Write;
+            write!(f, "{}", text.as_str())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_org_string_should_produce_a_string_representing_only_the_org_of_the_output(
+    ) {
+        let output = TestOutput(_text("*I am some org output*"));
+        let result = output.to_org_string(OrgConfig::default()).unwrap();
+        assert_eq!(result, "*I am some org output*");
+    }
+}
@@ -0,0 +1,11 @@
+use derive_more::{Display, Error, From};
+
+pub type OrgOutputResult = Result<(), OrgOutputError>;
+
+#[derive(Debug, From, Display, Error)]
+pub enum OrgOutputError {
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
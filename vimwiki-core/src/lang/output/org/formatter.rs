@@ -0,0 +1,151 @@
+use super::{OrgConfig, OrgOutputError, OutputFormatter};
+use std::fmt::{self, Write};
+
+/// Represents the formatter to use to write org output that includes
+/// various options that can be set as well as a context for use when writing
+/// output
+#[derive(Clone, Default)]
+pub struct OrgFormatter {
+    /// Represents the configuration associated with the formatter
+    config: OrgConfig,
+
+    /// Contains the content to be injected into a template
+    content: String,
+
+    /// Stack of prefixes (such as the indentation of list item
+    /// continuations) written at the start of every line
+    prefixes: Vec<String>,
+
+    /// If true, the formatter is writing the content of a table cell and
+    /// needs to escape pipes
+    in_table_cell: bool,
+}
+
+impl OutputFormatter for OrgFormatter {
+    type Error = OrgOutputError;
+}
+
+impl Write for OrgFormatter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // NOTE: We write a line at a time so we can inject the prefix of the
+        //       current nesting at the start of each line
+        for line in s.split_inclusive('\n') {
+            // NOTE: Blank lines are left empty rather than indented
+            if self.is_at_line_start() && line != "\n" {
+                for prefix in self.prefixes.iter() {
+                    self.content.push_str(prefix);
+                }
+            }
+
+            self.content.push_str(line);
+        }
+
+        Ok(())
+    }
+}
+
+impl OrgFormatter {
+    pub fn new(config: OrgConfig) -> Self {
+        Self {
+            config,
+            content: String::new(),
+            prefixes: Vec::new(),
+            in_table_cell: false,
+        }
+    }
+
+    pub fn clone_without_content(&self) -> Self {
+        Self {
+            content: String::new(),
+            prefixes: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns true if the next character written will start a new line
+    #[inline]
+    pub fn is_at_line_start(&self) -> bool {
+        self.content.is_empty() || self.content.ends_with('\n')
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter where every line started within the function is preceded by
+    /// the prefix, which is removed at the end of the function call
+    pub fn and_prefix<F>(
+        &mut self,
+        prefix: impl Into<String>,
+        f: F,
+    ) -> Result<(), OrgOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), OrgOutputError>,
+    {
+        self.prefixes.push(prefix.into());
+        let result = f(self);
+        self.prefixes.pop();
+        result
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter flagged as writing the content of a table cell
+    pub fn and_table_cell<F>(&mut self, f: F) -> Result<(), OrgOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), OrgOutputError>,
+    {
+        self.in_table_cell = true;
+        let result = f(self);
+        self.in_table_cell = false;
+        result
+    }
+
+    /// Whether or not the formatter is writing the content of a table
+    /// cell
+    #[inline]
+    pub fn is_in_table_cell(&self) -> bool {
+        self.in_table_cell
+    }
+
+    /// Removes whitespace from end of current output content
+    pub fn trim_end(&mut self) {
+        let diff = self.content.len() - self.content.trim_end().len();
+        self.content.truncate(self.content.len() - diff);
+    }
+
+    /// Represents the config contained within the formatter
+    #[inline]
+    pub fn config(&self) -> &OrgConfig {
+        &self.config
+    }
+
+    /// Represents the content contained within the formatter
+    #[inline]
+    pub fn get_content(&self) -> &str {
+        self.content.as_str()
+    }
+
+    /// Consumes the formatter and returns the content
+    #[inline]
+    pub fn into_content(self) -> String {
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_str_should_prefix_every_line_started_within_prefix() {
+        let mut f = OrgFormatter::default();
+        f.and_prefix("  ", |f| {
+            write!(f, "one\n\ntwo")?;
+            f.and_prefix("  ", |f| {
+                writeln!(f, " three")?;
+                writeln!(f, "four")?;
+                Ok(())
+            })
+        })
+        .unwrap();
+
+        assert_eq!(f.get_content(), "  one\n\n  two three\n    four\n");
+    }
+}
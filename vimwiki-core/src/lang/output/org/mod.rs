@@ -0,0 +1,974 @@
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::OrgFormatter;
+
+mod convert;
+pub use convert::ToOrgString;
+
+mod error;
+pub use error::{OrgOutputError, OrgOutputResult};
+
+use crate::lang::{
+    elements::*,
+    output::{make_page_link_target, Output, OutputFormatter},
+};
+use std::fmt::Write;
+use unicode_width::UnicodeWidthStr;
+
+/// Declares the keywords of vimwiki so org treats each as a todo state when
+/// starting a heading, where those after the bar are done states
+const TODO_KEYWORDS: &str = "TODO STARTED FIXME XXX | DONE FIXED";
+
+impl<'a> Output<OrgFormatter> for Page<'a> {
+    /// Writes a page in org, attaching any tags found on the line after a
+    /// header to the heading and declaring the keywords of vimwiki when a
+    /// heading starts with one that org does not know by default
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        let has_custom_keywords =
+            self.elements.iter().any(|e| match e.as_inner() {
+                BlockElement::Header(x) => matches!(
+                    x.content.iter().next().map(|x| x.as_inner()),
+                    Some(InlineElement::Keyword(k))
+                        if !matches!(k, Keyword::Todo | Keyword::Done)
+                ),
+                _ => false,
+            });
+
+        if has_custom_keywords {
+            writeln!(f, "#+TODO: {}", TODO_KEYWORDS)?;
+        }
+
+        let mut elements = self.elements.iter().peekable();
+        let mut was_placeholder = has_custom_keywords;
+        while let Some(element) = elements.next() {
+            let mut formatter = f.clone_without_content();
+            match element.as_inner() {
+                BlockElement::Header(x) => {
+                    let tags = match elements.peek().map(|x| x.as_inner()) {
+                        Some(BlockElement::Paragraph(x)) => paragraph_tags(x),
+                        _ => None,
+                    };
+
+                    if tags.is_some() {
+                        elements.next();
+                    }
+
+                    write_header(
+                        x,
+                        tags.as_deref().unwrap_or_default(),
+                        &mut formatter,
+                    )?;
+                }
+                x => x.fmt(&mut formatter)?,
+            }
+            let text = formatter.into_content();
+
+            // Separate each block element that produces output with a blank
+            // line, keeping the keyword lines of placeholders together
+            let is_placeholder =
+                matches!(element.as_inner(), BlockElement::Placeholder(_));
+            let is_grouped = is_placeholder && was_placeholder;
+            if !text.is_empty() {
+                if !f.get_content().is_empty() && !is_grouped {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", text)?;
+            }
+            was_placeholder = is_placeholder;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the tags of a paragraph if it contains nothing but tags
+fn paragraph_tags(paragraph: &Paragraph<'_>) -> Option<Vec<String>> {
+    let mut tags = Vec::new();
+
+    for line in paragraph.lines.iter() {
+        for element in line.iter() {
+            match element.as_inner() {
+                InlineElement::Tags(x) => {
+                    tags.extend(x.iter().map(|tag| tag.as_str().to_string()))
+                }
+                InlineElement::Text(x) if x.as_str().trim().is_empty() => {}
+                _ => return None,
+            }
+        }
+    }
+
+    if tags.is_empty() {
+        None
+    } else {
+        Some(tags)
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<OrgFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<OrgFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Blockquote<'a> {
+    /// Writes a blockquote in org
+    ///
+    /// ```org
+    /// #+BEGIN_QUOTE
+    /// line 1
+    /// #+END_QUOTE
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        writeln!(f, "#+BEGIN_QUOTE")?;
+        for line in self {
+            writeln!(f, "{}", line.trim())?;
+        }
+        writeln!(f, "#+END_QUOTE")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for DefinitionList<'a> {
+    /// Writes a definition list in org as a description list, nesting
+    /// definitions within a list when a term has more than one
+    ///
+    /// ```org
+    /// - term :: definition
+    /// - other term ::
+    ///   - definition 1
+    ///   - definition 2
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        for (term, defs) in self.iter() {
+            let term = render_inline(f, term.as_inner().as_inner())?;
+            let defs = defs
+                .iter()
+                .map(|def| render_inline(f, def.as_inner().as_inner()))
+                .collect::<Result<Vec<String>, _>>()?;
+
+            if let [def] = defs.as_slice() {
+                writeln!(f, "- {} :: {}", term, def)?;
+                continue;
+            }
+
+            writeln!(f, "- {} ::", term)?;
+            f.and_prefix("  ", |f| {
+                for def in defs {
+                    writeln!(f, "- {}", def)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<OrgFormatter> for Divider {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        writeln!(f, "-----")?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Header<'a> {
+    /// Writes a header in org as a heading, dropping any centering
+    ///
+    /// ```org
+    /// ** header
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        write_header(self, &[], f)
+    }
+}
+
+/// Writes a header in org as a heading with the given tags attached
+///
+/// ```org
+/// ** header :tag1:tag2:
+/// ```
+fn write_header(
+    header: &Header<'_>,
+    tags: &[String],
+    f: &mut OrgFormatter,
+) -> OrgOutputResult {
+    write!(
+        f,
+        "{} {}",
+        "*".repeat(header.level.max(1)),
+        render_inline(f, &header.content)?
+    )?;
+
+    if !tags.is_empty() {
+        // NOTE: Org tags only support alphanumeric characters alongside
+        //       `_`, `@`, `#`, and `%`
+        let tags = tags
+            .iter()
+            .map(|tag| {
+                tag.chars()
+                    .map(|c| match c {
+                        c if c.is_alphanumeric() => c,
+                        '_' | '@' | '#' | '%' => c,
+                        _ => '_',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>();
+        write!(f, " :{}:", tags.join(":"))?;
+    }
+
+    writeln!(f)?;
+    Ok(())
+}
+
+impl<'a> Output<OrgFormatter> for List<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        for item in self {
+            item.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for ListItem<'a> {
+    /// Writes a list item in org, numbering ordered items as org only
+    /// supports numbers by default and writing any todo status as a checkbox
+    ///
+    /// ```org
+    /// - [ ] incomplete
+    /// - [-] partially complete
+    /// - [X] complete
+    /// 1. item
+    ///    continued content
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        let marker = match &self.ty {
+            ListItemType::Ordered(_) => format!(
+                "{}{}",
+                self.pos + 1,
+                if self.suffix == ListItemSuffix::Paren {
+                    ')'
+                } else {
+                    '.'
+                }
+            ),
+
+            // NOTE: An asterisk at the start of a line is a heading in org,
+            //       so plus is used instead
+            ListItemType::Unordered(UnorderedListItemType::Asterisk) => {
+                String::from("+")
+            }
+            ListItemType::Unordered(_) => String::from("-"),
+        };
+
+        write!(f, "{} ", marker)?;
+
+        if let Some(todo_status) = self.attributes.todo_status.as_ref() {
+            todo_status.fmt(f)?;
+            write!(f, " ")?;
+        }
+
+        if self.contents.is_empty() {
+            writeln!(f)?;
+            return Ok(());
+        }
+
+        // Content beyond the first line is aligned with the content
+        // following the marker
+        f.and_prefix(" ".repeat(marker.len() + 1), |f| {
+            for (idx, content) in self.contents.iter().enumerate() {
+                // NOTE: Sublists can immediately follow other content, but
+                //       anything else needs a blank line to start a new block
+                if idx > 0
+                    && !matches!(content.as_inner(), BlockElement::List(_))
+                {
+                    writeln!(f)?;
+                }
+
+                content.fmt(f)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl Output<OrgFormatter> for ListItemTodoStatus {
+    /// Writes a todo status as a checkbox, where partially complete items
+    /// are marked as in progress
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::Complete => write!(f, "[X]")?,
+            Self::PartiallyComplete1
+            | Self::PartiallyComplete2
+            | Self::PartiallyComplete3 => write!(f, "[-]")?,
+            _ => write!(f, "[ ]")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for MathBlock<'a> {
+    /// Writes a math block in org, which supports latex environments
+    /// directly and display math otherwise
+    ///
+    /// ```org
+    /// \begin{align}
+    /// some math
+    /// \end{align}
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self.environment.as_ref() {
+            Some(env) => writeln!(f, r"\begin{{{}}}", env)?,
+            None => writeln!(f, r"\[")?,
+        }
+
+        for line in self {
+            writeln!(f, "{}", line)?;
+        }
+
+        match self.environment.as_ref() {
+            Some(env) => writeln!(f, r"\end{{{}}}", env)?,
+            None => writeln!(f, r"\]")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Placeholder<'a> {
+    /// Writes a placeholder in org as an in-buffer setting
+    ///
+    /// ```org
+    /// #+TITLE: some title
+    /// #+AUTHOR: some author
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::Title(x) => writeln!(f, "#+TITLE: {}", x)?,
+            Self::Date(x) => writeln!(f, "#+DATE: {}", x.format("%Y-%m-%d"))?,
+            Self::Template(x) => writeln!(f, "#+TEMPLATE: {}", x)?,
+            Self::NoHtml => writeln!(f, "#+NOHTML: t")?,
+            Self::Other { name, value } => {
+                writeln!(f, "#+{}: {}", name.to_uppercase(), value)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for CodeBlock<'a> {
+    /// Writes a code block in org as a source block when it has a language
+    /// or brush and as an example block otherwise, escaping lines that org
+    /// would otherwise treat as headings or settings
+    ///
+    /// ```org
+    /// #+BEGIN_SRC python
+    /// some code
+    /// #+END_SRC
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        let language = self.language_or_brush();
+
+        match language {
+            Some(language) => writeln!(f, "#+BEGIN_SRC {}", language)?,
+            None => writeln!(f, "#+BEGIN_EXAMPLE")?,
+        }
+
+        for line in self {
+            let trimmed = line.trim_start();
+            let needs_escape = ["*", "#+", ",*", ",#+"]
+                .iter()
+                .any(|x| trimmed.starts_with(x));
+
+            if needs_escape {
+                let indent = &line[..line.len() - trimmed.len()];
+                writeln!(f, "{},{}", indent, trimmed)?;
+            } else {
+                writeln!(f, "{}", line)?;
+            }
+        }
+
+        match language {
+            Some(_) => writeln!(f, "#+END_SRC")?,
+            None => writeln!(f, "#+END_EXAMPLE")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Paragraph<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        for line in self {
+            let text = render_inline(f, line)?;
+            if !text.is_empty() {
+                writeln!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Table<'a> {
+    /// Writes a table in org, padding cells to the display width of their
+    /// column and separating the rows above the divider with a rule, where
+    /// cells covered by spans are left empty as org tables have no spans
+    ///
+    /// When any column is aligned, a row of alignment cookies comes first,
+    /// which org leaves out when exporting
+    ///
+    /// ```org
+    /// | <l> | <c> |
+    /// | a   | b   |
+    /// |-----+-----|
+    /// | c   | d   |
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        let col_cnt = self.col_cnt();
+        let divider = self.get_divider_row_index();
+
+        let mut rows = Vec::new();
+        for row in 0..self.row_cnt() {
+            if divider == Some(row) {
+                rows.push(None);
+                continue;
+            }
+
+            let mut cells = Vec::new();
+            for col in 0..col_cnt {
+                cells.push(
+                    match self.get_cell(row, col).map(|x| x.as_inner()) {
+                        Some(Cell::Content(x)) => {
+                            let mut formatter = f.clone_without_content();
+                            formatter.and_table_cell(|f| x.fmt(f))?;
+                            formatter.into_content().trim().to_string()
+                        }
+                        _ => String::new(),
+                    },
+                );
+            }
+            rows.push(Some(cells));
+        }
+
+        let cookies = (0..col_cnt)
+            .map(|col| match self.get_column_alignment(col) {
+                ColumnAlign::None => None,
+                ColumnAlign::Left => Some("<l>"),
+                ColumnAlign::Center => Some("<c>"),
+                ColumnAlign::Right => Some("<r>"),
+            })
+            .collect::<Vec<Option<&str>>>();
+        let has_cookies = cookies.iter().any(Option::is_some);
+
+        let widths = (0..col_cnt)
+            .map(|col| {
+                rows.iter()
+                    .flatten()
+                    .map(|cells| cells[col].width())
+                    .chain(if has_cookies { Some(3) } else { None })
+                    .max()
+                    .unwrap_or_default()
+                    .max(1)
+            })
+            .collect::<Vec<usize>>();
+
+        let write_cells =
+            |f: &mut OrgFormatter, cells: &[&str]| -> OrgOutputResult {
+                write!(f, "|")?;
+                for (text, width) in cells.iter().zip(widths.iter()) {
+                    let padding = width.saturating_sub(text.width());
+                    write!(f, " {}{} |", text, " ".repeat(padding))?;
+                }
+                writeln!(f)?;
+                Ok(())
+            };
+
+        if has_cookies {
+            let cells = cookies
+                .iter()
+                .map(|x| x.unwrap_or_default())
+                .collect::<Vec<&str>>();
+            write_cells(f, &cells)?;
+        }
+
+        for row in rows.iter() {
+            match row {
+                Some(cells) => {
+                    let cells =
+                        cells.iter().map(String::as_str).collect::<Vec<&str>>();
+                    write_cells(f, &cells)?;
+                }
+                None => {
+                    let rules = widths
+                        .iter()
+                        .map(|width| "-".repeat(width + 2))
+                        .collect::<Vec<String>>();
+                    writeln!(f, "|{}|", rules.join("+"))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        write!(f, "{}", escape_text(self.as_str(), f.is_in_table_cell()))?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for DecoratedText<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        let (start, end) = match self {
+            Self::Bold(_) => ("*", "*"),
+            Self::Italic(_) => ("/", "/"),
+            Self::Strikeout(_) => ("+", "+"),
+            Self::Superscript(_) => ("^{", "}"),
+            Self::Subscript(_) => ("_{", "}"),
+        };
+
+        write!(f, "{}", start)?;
+        for content in self.as_contents_slice() {
+            content.fmt(f)?;
+        }
+        write!(f, "{}", end)?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<OrgFormatter> for Keyword {
+    /// Writes a keyword as-is, which org treats as the todo state of a
+    /// heading when the heading starts with it
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        write!(f, "{}", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Link<'a> {
+    /// Writes a link in org
+    ///
+    /// ### Wiki/Interwiki/Diary Link
+    ///
+    /// For `[[url#anchor|descr]]` and `[[#anchor|descr]]` in vimwiki, where
+    /// anchors search for the heading with the same text:
+    ///
+    /// ```org
+    /// [[file:url.org::*anchor][descr]]
+    /// [[*anchor][descr]]
+    /// ```
+    ///
+    /// ### Raw Link
+    ///
+    /// For `https://example.com`:
+    ///
+    /// ```org
+    /// [[https://example.com]]
+    /// ```
+    ///
+    /// ### Transclusion Link
+    ///
+    /// For `{{path/to/img.png}}`, which org displays inline as it has no
+    /// description:
+    ///
+    /// ```org
+    /// [[file:path/to/img.png]]
+    /// ```
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        write!(f, "[[{}]", make_org_link_target(self, f.config()))?;
+
+        if !matches!(self, Self::Raw { .. } | Self::Transclusion { .. }) {
+            match self.to_description_or_fallback() {
+                Some(Description::Text(x)) => {
+                    write!(f, "[{}]", escape_description(&x))?
+                }
+                Some(Description::TransclusionLink(data)) => write!(
+                    f,
+                    "[{}]",
+                    make_org_link_target(
+                        &Link::Transclusion { data: *data },
+                        f.config()
+                    )
+                )?,
+                None => {}
+            }
+        }
+
+        write!(f, "]")?;
+        Ok(())
+    }
+}
+
+/// Produces the target of a link in org, pointing local links at files and
+/// anchors at the heading with the same text
+fn make_org_link_target(link: &Link<'_>, config: &OrgConfig) -> String {
+    let data = link.data();
+    let anchor = data.to_anchor().and_then(|anchor| {
+        anchor.last().map(|x| LinkData::decode_uri(x.as_ref()))
+    });
+
+    let target = make_page_link_target(
+        link,
+        &config.page_ext,
+        &config.diary_rel_path,
+        &config.root_rel_path,
+    );
+
+    match link {
+        Link::Wiki { data } if data.scheme().is_some() => target,
+        Link::Wiki { data } if data.is_local_anchor() => {
+            format!("*{}", anchor.unwrap_or_default())
+        }
+        Link::Wiki { .. }
+        | Link::IndexedInterWiki { .. }
+        | Link::NamedInterWiki { .. }
+        | Link::Diary { .. } => {
+            // NOTE: Org expects file paths to be unencoded and searches for
+            //       headings by their text rather than an identifier
+            let path = target.split('#').next().unwrap_or_default();
+            let mut target = format!("file:{}", LinkData::decode_uri(path));
+            if let Some(anchor) = anchor {
+                target.push_str("::*");
+                target.push_str(&anchor);
+            }
+            target
+        }
+        Link::Transclusion { data } if data.is_local() => format!(
+            "file:{}",
+            LinkData::decode_uri(data.uri_ref.path().to_string())
+        ),
+        Link::Raw { .. } | Link::Transclusion { .. } => target,
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Tags<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        write!(f, ":")?;
+
+        for tag in self {
+            write!(f, "{}:", tag.as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for CodeInline<'a> {
+    /// Writes inline code in org as code, or as verbatim if the code would
+    /// end the code early
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        let code = escape_text(self.as_str(), f.is_in_table_cell());
+        if code.contains('~') {
+            write!(f, "={}=", code)?;
+        } else {
+            write!(f, "~{}~", code)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for MathInline<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        write!(f, r"\({}\)", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for Comment<'a> {
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        match self {
+            Self::Line(x) => x.fmt(f),
+            Self::MultiLine(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<OrgFormatter> for LineComment<'a> {
+    /// Writes a line comment in org, which must start its own line
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        if !f.is_at_line_start() {
+            writeln!(f)?;
+        }
+        write!(f, "# {}", self.as_str().trim())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<OrgFormatter> for MultiLineComment<'a> {
+    /// Writes a multiline comment in org as a series of comment lines,
+    /// ending the line so content after the comment is not commented out
+    fn fmt(&self, f: &mut OrgFormatter) -> OrgOutputResult {
+        if !f.is_at_line_start() {
+            writeln!(f)?;
+        }
+        for line in self {
+            writeln!(f, "# {}", line.trim())?;
+        }
+        Ok(())
+    }
+}
+
+fn render_inline(
+    f: &OrgFormatter,
+    content: &InlineElementContainer<'_>,
+) -> Result<String, OrgOutputError> {
+    let mut formatter = f.clone_without_content();
+    content.fmt(&mut formatter)?;
+    Ok(formatter.into_content().trim().to_string())
+}
+
+/// Escapes pipes within table cells, which would otherwise end the cell
+fn escape_text(text: &str, escape_pipes: bool) -> String {
+    if escape_pipes {
+        text.replace('|', r"\vert{}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Replaces brackets within the description of a link, which would
+/// otherwise end the link early
+fn escape_description(text: &str) -> String {
+    text.replace('[', "{").replace(']', "}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+    use similar_asserts::assert_str_eq;
+
+    fn to_org(text: &str) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_org_string(OrgConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn page_should_write_placeholders_as_settings() {
+        let text = indoc! {"
+            %title Some Title
+            %author Me
+            = Header =
+        "};
+
+        assert_str_eq!(
+            to_org(text),
+            indoc! {"
+                #+TITLE: Some Title
+                #+AUTHOR: Me
+
+                * Header
+            "}
+        );
+    }
+
+    #[test]
+    fn header_should_attach_tags_from_following_line() {
+        let text = indoc! {"
+            = Header =
+            :some-tag:other:
+            == DONE Sub *header* ==
+            paragraph :tag:
+        "};
+
+        assert_str_eq!(
+            to_org(text),
+            indoc! {"
+                * Header :some_tag:other:
+
+                ** DONE Sub *header*
+
+                paragraph :tag:
+            "}
+        );
+    }
+
+    #[test]
+    fn page_should_declare_keywords_unknown_to_org() {
+        let text = indoc! {"
+            = STARTED Header =
+            TODO item
+        "};
+
+        assert_str_eq!(
+            to_org(text),
+            indoc! {"
+                #+TODO: TODO STARTED FIXME XXX | DONE FIXED
+
+                * STARTED Header
+
+                TODO item
+            "}
+        );
+    }
+
+    #[test]
+    fn list_should_write_checkboxes_and_nest_content() {
+        let text = indoc! {"
+            * [ ] todo
+            * [.] partial
+            * [X] done
+                1) sub
+                2) sub
+        "};
+
+        assert_str_eq!(
+            to_org(text),
+            indoc! {"
+                + [ ] todo
+                + [-] partial
+                + [X] done
+                  1) sub
+                  2) sub
+            "}
+        );
+    }
+
+    #[test]
+    fn code_should_use_source_blocks_and_escape_lines() {
+        let text = indoc! {"
+            {{{python
+            # comment
+            * not a heading
+            #+not a setting
+            }}}
+            {{{
+            plain
+            }}}
+        "};
+
+        assert_str_eq!(
+            to_org(text),
+            indoc! {"
+                #+BEGIN_SRC python
+                # comment
+                ,* not a heading
+                ,#+not a setting
+                #+END_SRC
+
+                #+BEGIN_EXAMPLE
+                plain
+                #+END_EXAMPLE
+            "}
+        );
+    }
+
+    #[test]
+    fn table_should_align_columns_and_write_cookies() {
+        let text = indoc! {r"
+            | a  | long |
+            |----|:----:|
+            | c  | d    |
+        "};
+
+        assert_str_eq!(
+            to_org(text),
+            indoc! {"
+                |     | <c>  |
+                | a   | long |
+                |-----+------|
+                | c   | d    |
+            "}
+        );
+    }
+
+    #[test]
+    fn link_should_point_to_files_and_headings() {
+        let text = indoc! {"
+            [[some page#Part One|descr]] [[#Top]] [[diary:2021-01-02]]
+            https://example.com {{img.png}} *bold* _italic_ `code` $x$
+        "};
+
+        assert_str_eq!(
+            to_org(text),
+            indoc! {r"
+                [[file:some page.org::*Part One][descr]] [[*Top][#Top]] [[file:diary/2021-01-02.org][diary:2021-01-02]]
+                [[https://example.com/]] [[file:img.png]] *bold* /italic/ ~code~ \(x\)
+            "}
+        );
+    }
+}
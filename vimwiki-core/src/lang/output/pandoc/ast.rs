@@ -0,0 +1,223 @@
+//! Represents the subset of pandoc's document model (as defined by the
+//! `pandoc-types` package) needed to describe vimwiki, serializing to the
+//! JSON understood by `pandoc --from json`
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Represents a complete pandoc document
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Pandoc {
+    /// Version of `pandoc-types` that the document was written against
+    #[serde(rename = "pandoc-api-version")]
+    pub api_version: Vec<u32>,
+
+    /// Metadata of the document such as its title and date
+    pub meta: BTreeMap<String, PandocMetaValue>,
+
+    /// Block elements that make up the body of the document
+    pub blocks: Vec<PandocBlock>,
+}
+
+/// Represents a value within the metadata of a document
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+pub enum PandocMetaValue {
+    #[serde(rename = "MetaMap")]
+    Map(BTreeMap<String, PandocMetaValue>),
+    #[serde(rename = "MetaList")]
+    List(Vec<PandocMetaValue>),
+    #[serde(rename = "MetaBool")]
+    Bool(bool),
+    #[serde(rename = "MetaString")]
+    String(String),
+    #[serde(rename = "MetaInlines")]
+    Inlines(Vec<PandocInline>),
+    #[serde(rename = "MetaBlocks")]
+    Blocks(Vec<PandocBlock>),
+}
+
+/// Represents the identifier, classes, and key/value pairs of an element
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PandocAttr(pub String, pub Vec<String>, pub Vec<(String, String)>);
+
+impl PandocAttr {
+    /// Creates attributes with only the given identifier
+    pub fn with_id(id: impl Into<String>) -> Self {
+        Self(id.into(), Vec::new(), Vec::new())
+    }
+
+    /// Creates attributes with only the given classes
+    pub fn with_classes<I, S>(classes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(
+            String::new(),
+            classes.into_iter().map(Into::into).collect(),
+            Vec::new(),
+        )
+    }
+}
+
+/// Represents the url and title of a link or image
+pub type PandocTarget = (String, String);
+
+/// Represents a block element of a document
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+pub enum PandocBlock {
+    /// Inlines not wrapped in a paragraph, such as those of tight lists
+    Plain(Vec<PandocInline>),
+    Para(Vec<PandocInline>),
+    CodeBlock(PandocAttr, String),
+    /// Content passed through as-is to outputs of the given format
+    RawBlock(String, String),
+    BlockQuote(Vec<PandocBlock>),
+    OrderedList(PandocListAttributes, Vec<Vec<PandocBlock>>),
+    BulletList(Vec<Vec<PandocBlock>>),
+    /// Each term paired with its definitions
+    DefinitionList(Vec<(Vec<PandocInline>, Vec<Vec<PandocBlock>>)>),
+    Header(usize, PandocAttr, Vec<PandocInline>),
+    HorizontalRule,
+    Table(Box<PandocTable>),
+    Div(PandocAttr, Vec<PandocBlock>),
+}
+
+/// Represents the starting number, numbering style, and delimiter of an
+/// ordered list
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PandocListAttributes(
+    pub usize,
+    pub PandocListNumberStyle,
+    pub PandocListNumberDelim,
+);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "t")]
+pub enum PandocListNumberStyle {
+    DefaultStyle,
+    Example,
+    Decimal,
+    LowerRoman,
+    UpperRoman,
+    LowerAlpha,
+    UpperAlpha,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "t")]
+pub enum PandocListNumberDelim {
+    DefaultDelim,
+    Period,
+    OneParen,
+    TwoParens,
+}
+
+/// Represents a table with its caption, column specifications, head,
+/// bodies, and foot
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PandocTable(
+    pub PandocAttr,
+    pub PandocCaption,
+    pub Vec<PandocColSpec>,
+    pub PandocTableHead,
+    pub Vec<PandocTableBody>,
+    pub PandocTableFoot,
+);
+
+/// Represents the optional short caption and the full caption of a table
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PandocCaption(pub Option<Vec<PandocInline>>, pub Vec<PandocBlock>);
+
+/// Represents the alignment and width of a table column
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PandocColSpec(pub PandocAlignment, pub PandocColWidth);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "t")]
+pub enum PandocAlignment {
+    AlignLeft,
+    AlignRight,
+    AlignCenter,
+    AlignDefault,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+pub enum PandocColWidth {
+    /// Fraction of the total width of the table
+    ColWidth(f64),
+    ColWidthDefault,
+}
+
+/// Represents the rows at the top of a table
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PandocTableHead(pub PandocAttr, pub Vec<PandocRow>);
+
+/// Represents a section of the body of a table, comprised of the number of
+/// columns acting as row headers, header rows, and the remaining rows
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PandocTableBody(
+    pub PandocAttr,
+    pub usize,
+    pub Vec<PandocRow>,
+    pub Vec<PandocRow>,
+);
+
+/// Represents the rows at the bottom of a table
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PandocTableFoot(pub PandocAttr, pub Vec<PandocRow>);
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PandocRow(pub PandocAttr, pub Vec<PandocCell>);
+
+/// Represents a table cell with its alignment, rowspan, colspan, and
+/// content, where cells covered by the span of another cell are omitted
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PandocCell(
+    pub PandocAttr,
+    pub PandocAlignment,
+    pub usize,
+    pub usize,
+    pub Vec<PandocBlock>,
+);
+
+/// Represents an inline element of a document
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+pub enum PandocInline {
+    Str(String),
+    Emph(Vec<PandocInline>),
+    Underline(Vec<PandocInline>),
+    Strong(Vec<PandocInline>),
+    Strikeout(Vec<PandocInline>),
+    Superscript(Vec<PandocInline>),
+    Subscript(Vec<PandocInline>),
+    SmallCaps(Vec<PandocInline>),
+    Code(PandocAttr, String),
+    Space,
+    SoftBreak,
+    LineBreak,
+    Math(PandocMathType, String),
+    /// Content passed through as-is to outputs of the given format
+    RawInline(String, String),
+    Link(PandocAttr, Vec<PandocInline>, PandocTarget),
+    Image(PandocAttr, Vec<PandocInline>, PandocTarget),
+    Span(PandocAttr, Vec<PandocInline>),
+}
+
+impl PandocInline {
+    /// Returns true if the inline is a space or line break between words
+    #[inline]
+    pub fn is_whitespace(&self) -> bool {
+        matches!(self, Self::Space | Self::SoftBreak | Self::LineBreak)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "t")]
+pub enum PandocMathType {
+    DisplayMath,
+    InlineMath,
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents configuration properties for pandoc output that are separate
+/// from the running state during pandoc conversion
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PandocConfig {
+    /// Version of `pandoc-types` written to the document, which pandoc
+    /// requires to match its own when reading json
+    #[serde(default = "PandocConfig::default_api_version")]
+    pub api_version: Vec<u32>,
+
+    /// Extension given to the paths of wiki pages when writing links
+    #[serde(default = "PandocConfig::default_page_ext")]
+    pub page_ext: String,
+
+    /// Path to the diary relative to the root of the wiki, used when writing
+    /// diary links
+    #[serde(default = "PandocConfig::default_diary_rel_path")]
+    pub diary_rel_path: String,
+
+    /// Path from the page being written to the root of its wiki (e.g. `../`),
+    /// used when writing links that are relative to the root of a wiki
+    #[serde(default)]
+    pub root_rel_path: String,
+}
+
+impl Default for PandocConfig {
+    fn default() -> Self {
+        Self {
+            api_version: Self::default_api_version(),
+            page_ext: Self::default_page_ext(),
+            diary_rel_path: Self::default_diary_rel_path(),
+            root_rel_path: String::new(),
+        }
+    }
+}
+
+impl PandocConfig {
+    #[inline]
+    pub fn default_api_version() -> Vec<u32> {
+        vec![1, 23, 1]
+    }
+
+    #[inline]
+    pub fn default_page_ext() -> String {
+        String::from("html")
+    }
+
+    #[inline]
+    pub fn default_diary_rel_path() -> String {
+        String::from("diary")
+    }
+}
@@ -0,0 +1,56 @@
+use super::{Output, Pandoc, PandocConfig, PandocFormatter, PandocOutputError};
+
+pub trait ToPandoc {
+    fn to_pandoc(
+        &self,
+        config: PandocConfig,
+    ) -> Result<Pandoc, PandocOutputError>;
+}
+
+impl<T: Output<PandocFormatter>> ToPandoc for T {
+    fn to_pandoc(
+        &self,
+        config: PandocConfig,
+    ) -> Result<Pandoc, PandocOutputError> {
+        let mut formatter = PandocFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_pandoc())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PandocBlock, PandocInline, PandocOutputResult};
+
+    struct TestOutput<F: Fn(&mut PandocFormatter) -> PandocOutputResult>(F);
+    impl<F: Fn(&mut PandocFormatter) -> PandocOutputResult>
+        Output<PandocFormatter> for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_pandoc_should_place_stray_inlines_in_a_trailing_block() {
+        let output = TestOutput(|f: &mut PandocFormatter| {
+            f.push_block(PandocBlock::HorizontalRule);
+            f.push_inline(PandocInline::Str(String::from("text")));
+            Ok(())
+        });
+        let pandoc = output.to_pandoc(PandocConfig::default()).unwrap();
+
+        assert_eq!(pandoc.api_version, PandocConfig::default_api_version());
+        assert_eq!(
+            pandoc.blocks,
+            vec![
+                PandocBlock::HorizontalRule,
+                PandocBlock::Plain(vec![PandocInline::Str(String::from(
+                    "text"
+                ))]),
+            ]
+        );
+    }
+}
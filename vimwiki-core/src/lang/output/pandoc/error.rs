@@ -0,0 +1,11 @@
+use derive_more::{Display, Error, From};
+
+pub type PandocOutputResult = Result<(), PandocOutputError>;
+
+#[derive(Debug, From, Display, Error)]
+pub enum PandocOutputError {
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
@@ -0,0 +1,196 @@
+use super::{
+    OutputFormatter, Pandoc, PandocBlock, PandocConfig, PandocInline,
+    PandocMetaValue, PandocOutputError,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    mem,
+};
+
+/// Represents the formatter to use to build a pandoc document that includes
+/// various options that can be set as well as a context for use when writing
+/// output
+///
+/// Rather than text, the formatter collects pandoc elements, where text
+/// written to it is split into words, spaces, and soft breaks
+#[derive(Clone, Default)]
+pub struct PandocFormatter {
+    /// Represents the configuration associated with the formatter
+    config: PandocConfig,
+
+    /// Metadata of the document being built
+    meta: BTreeMap<String, PandocMetaValue>,
+
+    /// Block elements written so far
+    blocks: Vec<PandocBlock>,
+
+    /// Inline elements written so far that are not yet part of a block
+    inlines: Vec<PandocInline>,
+}
+
+impl OutputFormatter for PandocFormatter {
+    type Error = PandocOutputError;
+}
+
+impl Write for PandocFormatter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut word = String::new();
+
+        for c in s.chars() {
+            if c.is_whitespace() {
+                if !word.is_empty() {
+                    self.push_inline(PandocInline::Str(mem::take(&mut word)));
+                }
+
+                self.push_inline(if c == '\n' {
+                    PandocInline::SoftBreak
+                } else {
+                    PandocInline::Space
+                });
+            } else {
+                word.push(c);
+            }
+        }
+
+        if !word.is_empty() {
+            self.push_inline(PandocInline::Str(word));
+        }
+
+        Ok(())
+    }
+}
+
+impl PandocFormatter {
+    pub fn new(config: PandocConfig) -> Self {
+        Self {
+            config,
+            meta: BTreeMap::new(),
+            blocks: Vec::new(),
+            inlines: Vec::new(),
+        }
+    }
+
+    /// Adds an inline element, merging adjacent words and collapsing
+    /// consecutive whitespace into a single space or soft break
+    pub fn push_inline(&mut self, inline: PandocInline) {
+        match (self.inlines.last_mut(), inline) {
+            (Some(PandocInline::Str(last)), PandocInline::Str(x)) => {
+                last.push_str(&x)
+            }
+            (Some(last @ PandocInline::Space), PandocInline::SoftBreak) => {
+                *last = PandocInline::SoftBreak
+            }
+            (Some(last), x) if last.is_whitespace() && x.is_whitespace() => {}
+            (_, x) => self.inlines.push(x),
+        }
+    }
+
+    /// Adds a block element
+    #[inline]
+    pub fn push_block(&mut self, block: PandocBlock) {
+        self.blocks.push(block);
+    }
+
+    /// Sets the metadata of the document under the given key
+    #[inline]
+    pub fn insert_meta(
+        &mut self,
+        key: impl Into<String>,
+        value: PandocMetaValue,
+    ) {
+        self.meta.insert(key.into(), value);
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter, and returns the inline elements written within the function
+    /// with surrounding whitespace removed
+    pub fn and_inlines<F>(
+        &mut self,
+        f: F,
+    ) -> Result<Vec<PandocInline>, PandocOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), PandocOutputError>,
+    {
+        let outer = mem::take(&mut self.inlines);
+        let result = f(self);
+        let mut inlines = mem::replace(&mut self.inlines, outer);
+        result?;
+
+        while inlines.last().map_or(false, PandocInline::is_whitespace) {
+            inlines.pop();
+        }
+        let start = inlines
+            .iter()
+            .position(|x| !x.is_whitespace())
+            .unwrap_or(inlines.len());
+        inlines.drain(..start);
+
+        Ok(inlines)
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter, and returns the block elements written within the function
+    pub fn and_blocks<F>(
+        &mut self,
+        f: F,
+    ) -> Result<Vec<PandocBlock>, PandocOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), PandocOutputError>,
+    {
+        let outer = mem::take(&mut self.blocks);
+        let result = f(self);
+        let blocks = mem::replace(&mut self.blocks, outer);
+        result?;
+        Ok(blocks)
+    }
+
+    /// Represents the config contained within the formatter
+    #[inline]
+    pub fn config(&self) -> &PandocConfig {
+        &self.config
+    }
+
+    /// Consumes the formatter and returns the document, where any inline
+    /// elements written outside of a block are placed at the end
+    pub fn into_pandoc(mut self) -> Pandoc {
+        let inlines = mem::take(&mut self.inlines);
+        if inlines.iter().any(|x| !x.is_whitespace()) {
+            self.blocks.push(PandocBlock::Plain(inlines));
+        }
+
+        Pandoc {
+            api_version: self.config.api_version,
+            meta: self.meta,
+            blocks: self.blocks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_str_should_split_text_into_words_and_whitespace() {
+        let mut f = PandocFormatter::default();
+        let inlines = f
+            .and_inlines(|f| {
+                write!(f, " some  te")?;
+                write!(f, "xt \nhere ")?;
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            inlines,
+            vec![
+                PandocInline::Str(String::from("some")),
+                PandocInline::Space,
+                PandocInline::Str(String::from("text")),
+                PandocInline::SoftBreak,
+                PandocInline::Str(String::from("here")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,865 @@
+mod ast;
+pub use ast::*;
+
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::PandocFormatter;
+
+mod convert;
+pub use convert::ToPandoc;
+
+mod error;
+pub use error::{PandocOutputError, PandocOutputResult};
+
+use crate::lang::{
+    elements::*,
+    output::{make_page_link_target, slugify, Output, OutputFormatter},
+};
+use std::fmt::Write;
+
+/// Title given to links between wiki pages, matching the links produced by
+/// pandoc's own vimwiki reader
+const WIKILINK_TITLE: &str = "wikilink";
+
+impl<'a> Output<PandocFormatter> for Page<'a> {
+    /// Writes a page as a pandoc document, where the placeholders of the
+    /// page become the metadata of the document
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        for element in self.elements.iter() {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PandocFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PandocFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Blockquote<'a> {
+    /// Writes a blockquote as a `BlockQuote` containing a `Para` for each
+    /// group of lines separated by blank lines
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let mut blocks = Vec::new();
+
+        for group in self.lines.split(|line| line.trim().is_empty()) {
+            let text = group
+                .iter()
+                .map(|line| line.trim())
+                .collect::<Vec<&str>>()
+                .join("\n");
+            let inlines = f.and_inlines(|f| {
+                write!(f, "{}", text)?;
+                Ok(())
+            })?;
+
+            if !inlines.is_empty() {
+                blocks.push(PandocBlock::Para(inlines));
+            }
+        }
+
+        f.push_block(PandocBlock::BlockQuote(blocks));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for DefinitionList<'a> {
+    /// Writes a definition list as a `DefinitionList` where each definition
+    /// is a single `Plain` block
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let mut items = Vec::new();
+
+        for (term, defs) in self.iter() {
+            let term = f.and_inlines(|f| term.fmt(f))?;
+            let defs = defs
+                .iter()
+                .map(|def| {
+                    f.and_inlines(|f| def.fmt(f))
+                        .map(|x| vec![PandocBlock::Plain(x)])
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            items.push((term, defs));
+        }
+
+        f.push_block(PandocBlock::DefinitionList(items));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<PandocFormatter> for Divider {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        f.push_block(PandocBlock::HorizontalRule);
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Header<'a> {
+    /// Writes a header as a `Header` identified by the slug of its content
+    /// so links to its anchor can reference it, where centered headers are
+    /// given the same class as in html output
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let mut attr =
+            PandocAttr::with_id(slugify(self.content.to_string().as_str()));
+        if self.centered {
+            attr.1.push(String::from("justcenter"));
+        }
+
+        let inlines = f.and_inlines(|f| self.content.fmt(f))?;
+        f.push_block(PandocBlock::Header(self.level, attr, inlines));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for List<'a> {
+    /// Writes a list as a `BulletList` or an `OrderedList` whose numbering
+    /// style and delimiter match the type and suffix of its first item
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let items = self
+            .iter()
+            .map(|item| f.and_blocks(|f| item.fmt(f)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let first = self.iter().next().map(|x| x.as_inner());
+        match first.map(|x| (&x.ty, x.suffix)) {
+            Some((ListItemType::Ordered(ty), suffix)) => {
+                let style = match ty {
+                    OrderedListItemType::Number
+                    | OrderedListItemType::Pound => {
+                        PandocListNumberStyle::Decimal
+                    }
+                    OrderedListItemType::LowercaseAlphabet => {
+                        PandocListNumberStyle::LowerAlpha
+                    }
+                    OrderedListItemType::UppercaseAlphabet => {
+                        PandocListNumberStyle::UpperAlpha
+                    }
+                    OrderedListItemType::LowercaseRoman => {
+                        PandocListNumberStyle::LowerRoman
+                    }
+                    OrderedListItemType::UppercaseRoman => {
+                        PandocListNumberStyle::UpperRoman
+                    }
+                };
+                let delim = match suffix {
+                    ListItemSuffix::Period => PandocListNumberDelim::Period,
+                    ListItemSuffix::Paren => PandocListNumberDelim::OneParen,
+                    ListItemSuffix::None => PandocListNumberDelim::DefaultDelim,
+                };
+
+                f.push_block(PandocBlock::OrderedList(
+                    PandocListAttributes(1, style, delim),
+                    items,
+                ));
+            }
+            _ => f.push_block(PandocBlock::BulletList(items)),
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for ListItem<'a> {
+    /// Writes the contents of a list item, where paragraphs become `Plain`
+    /// blocks to keep the list tight and todo items start with a ballot box
+    /// just like the task lists read by pandoc
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let mut blocks = f.and_blocks(|f| {
+            for content in self.contents.iter() {
+                content.fmt(f)?;
+            }
+            Ok(())
+        })?;
+
+        for block in blocks.iter_mut() {
+            if let PandocBlock::Para(inlines) = block {
+                *block = PandocBlock::Plain(std::mem::take(inlines));
+            }
+        }
+
+        if let Some(todo_status) = self.attributes.todo_status.as_ref() {
+            let mut inlines = f.and_inlines(|f| todo_status.fmt(f))?;
+            match blocks.first_mut() {
+                Some(PandocBlock::Plain(x)) => {
+                    inlines.push(PandocInline::Space);
+                    inlines.append(x);
+                    *x = inlines;
+                }
+                _ => blocks.insert(0, PandocBlock::Plain(inlines)),
+            }
+        }
+
+        for block in blocks {
+            f.push_block(block);
+        }
+
+        Ok(())
+    }
+}
+
+impl Output<PandocFormatter> for ListItemTodoStatus {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        match self {
+            Self::Complete => write!(f, "\u{2612}")?,
+            _ => write!(f, "\u{2610}")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for MathBlock<'a> {
+    /// Writes a math block as display `Math` within a `Para`, wrapping the
+    /// math in its environment if it has one
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let mut math = self.lines.join("\n");
+        if let Some(env) = self.environment.as_ref() {
+            math = format!("\\begin{{{0}}}\n{1}\n\\end{{{0}}}", env, math);
+        }
+
+        f.push_block(PandocBlock::Para(vec![PandocInline::Math(
+            PandocMathType::DisplayMath,
+            math,
+        )]));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Placeholder<'a> {
+    /// Writes a placeholder into the metadata of the document
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        match self {
+            Self::Title(x) => {
+                let inlines = f.and_inlines(|f| {
+                    write!(f, "{}", x)?;
+                    Ok(())
+                })?;
+                f.insert_meta("title", PandocMetaValue::Inlines(inlines));
+            }
+            Self::Date(x) => f.insert_meta(
+                "date",
+                PandocMetaValue::String(x.format("%Y-%m-%d").to_string()),
+            ),
+            Self::Template(x) => f.insert_meta(
+                "template",
+                PandocMetaValue::String(x.to_string()),
+            ),
+            Self::NoHtml => {
+                f.insert_meta("nohtml", PandocMetaValue::Bool(true))
+            }
+            Self::Other { name, value } => f.insert_meta(
+                name.to_string(),
+                PandocMetaValue::String(value.to_string()),
+            ),
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for CodeBlock<'a> {
+    /// Writes a code block as a `CodeBlock` classed by its language or brush,
+    /// keeping any other metadata as attributes
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let language = self.language_or_brush();
+
+        let mut attributes = self
+            .metadata
+            .iter()
+            .filter(|(key, _)| self.brush().is_none() || *key != "class")
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<Vec<(String, String)>>();
+        attributes.sort();
+
+        let attr = PandocAttr(
+            String::new(),
+            language.map(ToString::to_string).into_iter().collect(),
+            attributes,
+        );
+
+        f.push_block(PandocBlock::CodeBlock(attr, self.lines.join("\n")));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Paragraph<'a> {
+    /// Writes a paragraph as a `Para`, separating its lines with soft breaks
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let inlines = f.and_inlines(|f| {
+            for (idx, line) in self.lines.iter().enumerate() {
+                if idx > 0 {
+                    f.push_inline(PandocInline::SoftBreak);
+                }
+                line.fmt(f)?;
+            }
+            Ok(())
+        })?;
+
+        // NOTE: Paragraphs made only of comments produce nothing
+        if !inlines.is_empty() {
+            f.push_block(PandocBlock::Para(inlines));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Table<'a> {
+    /// Writes a table as a `Table`, where the rows above the divider (if
+    /// there is one) form the head of the table and cells covered by the
+    /// span of another cell are omitted
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let col_specs = (0..self.col_cnt())
+            .map(|col| {
+                let align = match self.get_column_alignment(col) {
+                    ColumnAlign::None => PandocAlignment::AlignDefault,
+                    ColumnAlign::Left => PandocAlignment::AlignLeft,
+                    ColumnAlign::Center => PandocAlignment::AlignCenter,
+                    ColumnAlign::Right => PandocAlignment::AlignRight,
+                };
+                PandocColSpec(align, PandocColWidth::ColWidthDefault)
+            })
+            .collect();
+
+        let mut head = Vec::new();
+        let mut body = Vec::new();
+        let divider = self.get_divider_row_index();
+
+        for row in 0..self.row_cnt() {
+            if divider == Some(row) {
+                continue;
+            }
+
+            let mut cells = Vec::new();
+            for col in 0..self.col_cnt() {
+                if let Some(Cell::Content(x)) =
+                    self.get_cell(row, col).map(|x| x.as_inner())
+                {
+                    let inlines = f.and_inlines(|f| x.fmt(f))?;
+                    cells.push(PandocCell(
+                        PandocAttr::default(),
+                        PandocAlignment::AlignDefault,
+                        self.get_cell_rowspan(row, col).max(1),
+                        self.get_cell_colspan(row, col).max(1),
+                        if inlines.is_empty() {
+                            Vec::new()
+                        } else {
+                            vec![PandocBlock::Plain(inlines)]
+                        },
+                    ));
+                }
+            }
+
+            let row_data = PandocRow(PandocAttr::default(), cells);
+            if divider.map_or(false, |divider| row < divider) {
+                head.push(row_data);
+            } else {
+                body.push(row_data);
+            }
+        }
+
+        let attr = if self.centered {
+            PandocAttr::with_classes(vec!["center"])
+        } else {
+            PandocAttr::default()
+        };
+
+        f.push_block(PandocBlock::Table(Box::new(PandocTable(
+            attr,
+            PandocCaption::default(),
+            col_specs,
+            PandocTableHead(PandocAttr::default(), head),
+            vec![PandocTableBody(PandocAttr::default(), 0, Vec::new(), body)],
+            PandocTableFoot::default(),
+        ))));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        write!(f, "{}", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for DecoratedText<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let inlines = f.and_inlines(|f| {
+            for content in self.as_contents_slice() {
+                content.fmt(f)?;
+            }
+            Ok(())
+        })?;
+
+        f.push_inline(match self {
+            Self::Bold(_) => PandocInline::Strong(inlines),
+            Self::Italic(_) => PandocInline::Emph(inlines),
+            Self::Strikeout(_) => PandocInline::Strikeout(inlines),
+            Self::Superscript(_) => PandocInline::Superscript(inlines),
+            Self::Subscript(_) => PandocInline::Subscript(inlines),
+        });
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<PandocFormatter> for Keyword {
+    /// Writes a keyword as a `Span` classed as a keyword and by the keyword
+    /// itself (e.g. `todo`)
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let keyword = self.to_string();
+        f.push_inline(PandocInline::Span(
+            PandocAttr::with_classes(vec![
+                String::from("keyword"),
+                keyword.to_lowercase(),
+            ]),
+            vec![PandocInline::Str(keyword)],
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Link<'a> {
+    /// Writes a link as a pandoc inline
+    ///
+    /// ### Wiki/Interwiki/Diary Link
+    ///
+    /// A `Link` titled `wikilink` whose target is resolved to the page it
+    /// points to, using the description of the link as its content
+    ///
+    /// ### Raw Link
+    ///
+    /// A `Link` classed as `uri` whose content is the link itself
+    ///
+    /// ### Transclusion Link
+    ///
+    /// An `Image` whose description is its alternative text, keeping the
+    /// properties of the link as attributes
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        let target = make_page_link_target(
+            self,
+            &f.config().page_ext,
+            &f.config().diary_rel_path,
+            &f.config().root_rel_path,
+        );
+
+        match self {
+            Self::Raw { .. } => {
+                f.push_inline(PandocInline::Link(
+                    PandocAttr::with_classes(vec!["uri"]),
+                    vec![PandocInline::Str(target.to_string())],
+                    (target, String::new()),
+                ));
+            }
+            Self::Transclusion { data } => {
+                let alt = match data.description.as_ref() {
+                    Some(Description::Text(x)) => f.and_inlines(|f| {
+                        write!(f, "{}", x)?;
+                        Ok(())
+                    })?,
+                    _ => Vec::new(),
+                };
+
+                let mut attributes = data
+                    .properties
+                    .iter()
+                    .flatten()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect::<Vec<(String, String)>>();
+                attributes.sort();
+
+                f.push_inline(PandocInline::Image(
+                    PandocAttr(String::new(), Vec::new(), attributes),
+                    alt,
+                    (target, String::new()),
+                ));
+            }
+            _ => {
+                let content = f.and_inlines(|f| {
+                    match self.to_description_or_fallback() {
+                        Some(Description::Text(x)) => write!(f, "{}", x)?,
+                        Some(Description::TransclusionLink(data)) => {
+                            Link::Transclusion { data: *data }.fmt(f)?
+                        }
+                        None => write!(f, "{}", target)?,
+                    }
+                    Ok(())
+                })?;
+
+                f.push_inline(PandocInline::Link(
+                    PandocAttr::default(),
+                    content,
+                    (target, String::from(WIKILINK_TITLE)),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Tags<'a> {
+    /// Writes tags as a series of `Span`s classed as tags, separated by
+    /// spaces
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        for (idx, tag) in self.iter().enumerate() {
+            if idx > 0 {
+                f.push_inline(PandocInline::Space);
+            }
+
+            f.push_inline(PandocInline::Span(
+                PandocAttr::with_classes(vec!["tag"]),
+                vec![PandocInline::Str(tag.to_string())],
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for CodeInline<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        f.push_inline(PandocInline::Code(
+            PandocAttr::default(),
+            self.as_str().to_string(),
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for MathInline<'a> {
+    fn fmt(&self, f: &mut PandocFormatter) -> PandocOutputResult {
+        f.push_inline(PandocInline::Math(
+            PandocMathType::InlineMath,
+            self.as_str().to_string(),
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> Output<PandocFormatter> for Comment<'a> {
+    /// Writes nothing as pandoc's document model has no comments
+    fn fmt(&self, _f: &mut PandocFormatter) -> PandocOutputResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    fn to_pandoc(text: &str) -> Pandoc {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_pandoc(PandocConfig::default()).unwrap()
+    }
+
+    fn str(text: &str) -> PandocInline {
+        PandocInline::Str(text.to_string())
+    }
+
+    #[test]
+    fn page_should_serialize_to_pandoc_json() {
+        let pandoc = to_pandoc(indoc! {"
+            %title Some Title
+            = Header =
+            Some *bold* text
+        "});
+
+        assert_eq!(
+            serde_json::to_value(&pandoc).unwrap(),
+            serde_json::json!({
+                "pandoc-api-version": [1, 23, 1],
+                "meta": {
+                    "title": {
+                        "t": "MetaInlines",
+                        "c": [
+                            { "t": "Str", "c": "Some" },
+                            { "t": "Space" },
+                            { "t": "Str", "c": "Title" },
+                        ],
+                    },
+                },
+                "blocks": [
+                    {
+                        "t": "Header",
+                        "c": [1, ["header", [], []], [
+                            { "t": "Str", "c": "Header" },
+                        ]],
+                    },
+                    {
+                        "t": "Para",
+                        "c": [
+                            { "t": "Str", "c": "Some" },
+                            { "t": "Space" },
+                            { "t": "Strong", "c": [
+                                { "t": "Str", "c": "bold" },
+                            ] },
+                            { "t": "Space" },
+                            { "t": "Str", "c": "text" },
+                        ],
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn link_should_resolve_target_of_wiki_links() {
+        let pandoc = to_pandoc(indoc! {"
+            [[sub/some page#Part One|descr]] [[#Top]] https://example.com
+        "});
+
+        assert_eq!(
+            pandoc.blocks,
+            vec![PandocBlock::Para(vec![
+                PandocInline::Link(
+                    PandocAttr::default(),
+                    vec![str("descr")],
+                    (
+                        String::from("sub/some%20page.html#part-one"),
+                        String::from("wikilink"),
+                    ),
+                ),
+                PandocInline::Space,
+                PandocInline::Link(
+                    PandocAttr::default(),
+                    vec![str("#Top")],
+                    (String::from("#top"), String::from("wikilink")),
+                ),
+                PandocInline::Space,
+                PandocInline::Link(
+                    PandocAttr::with_classes(vec!["uri"]),
+                    vec![str("https://example.com/")],
+                    (String::from("https://example.com/"), String::new()),
+                ),
+            ])]
+        );
+    }
+
+    #[test]
+    fn keywords_and_tags_should_be_spans_with_classes() {
+        let pandoc = to_pandoc("TODO fix :one:two:\n");
+
+        assert_eq!(
+            pandoc.blocks,
+            vec![PandocBlock::Para(vec![
+                PandocInline::Span(
+                    PandocAttr::with_classes(vec!["keyword", "todo"]),
+                    vec![str("TODO")],
+                ),
+                PandocInline::Space,
+                str("fix"),
+                PandocInline::Space,
+                PandocInline::Span(
+                    PandocAttr::with_classes(vec!["tag"]),
+                    vec![str("one")],
+                ),
+                PandocInline::Space,
+                PandocInline::Span(
+                    PandocAttr::with_classes(vec!["tag"]),
+                    vec![str("two")],
+                ),
+            ])]
+        );
+    }
+
+    #[test]
+    fn list_should_be_tight_and_mark_todos() {
+        let pandoc = to_pandoc(indoc! {"
+            a) first
+            b) second
+                - [X] done
+        "});
+
+        assert_eq!(
+            pandoc.blocks,
+            vec![PandocBlock::OrderedList(
+                PandocListAttributes(
+                    1,
+                    PandocListNumberStyle::LowerAlpha,
+                    PandocListNumberDelim::OneParen,
+                ),
+                vec![
+                    vec![PandocBlock::Plain(vec![str("first")])],
+                    vec![
+                        PandocBlock::Plain(vec![str("second")]),
+                        PandocBlock::BulletList(vec![vec![
+                            PandocBlock::Plain(vec![
+                                str("\u{2612}"),
+                                PandocInline::Space,
+                                str("done"),
+                            ]),
+                        ]]),
+                    ],
+                ],
+            )]
+        );
+    }
+
+    #[test]
+    fn table_should_omit_cells_covered_by_spans() {
+        let pandoc = to_pandoc(indoc! {r"
+            | a  | >  | b |
+            |----|:--:|---|
+            | c  | d  | e |
+            | \/ | f  | g |
+        "});
+
+        let cell = |text: &str, rowspan: usize, colspan: usize| {
+            PandocCell(
+                PandocAttr::default(),
+                PandocAlignment::AlignDefault,
+                rowspan,
+                colspan,
+                vec![PandocBlock::Plain(vec![str(text)])],
+            )
+        };
+        let row =
+            |cells: Vec<PandocCell>| PandocRow(PandocAttr::default(), cells);
+
+        assert_eq!(
+            pandoc.blocks,
+            vec![PandocBlock::Table(Box::new(PandocTable(
+                PandocAttr::default(),
+                PandocCaption::default(),
+                vec![
+                    PandocColSpec(
+                        PandocAlignment::AlignDefault,
+                        PandocColWidth::ColWidthDefault
+                    ),
+                    PandocColSpec(
+                        PandocAlignment::AlignCenter,
+                        PandocColWidth::ColWidthDefault
+                    ),
+                    PandocColSpec(
+                        PandocAlignment::AlignDefault,
+                        PandocColWidth::ColWidthDefault
+                    ),
+                ],
+                PandocTableHead(
+                    PandocAttr::default(),
+                    vec![row(vec![cell("a", 1, 2), cell("b", 1, 1)])],
+                ),
+                vec![PandocTableBody(
+                    PandocAttr::default(),
+                    0,
+                    Vec::new(),
+                    vec![
+                        row(vec![
+                            cell("c", 2, 1),
+                            cell("d", 1, 1),
+                            cell("e", 1, 1),
+                        ]),
+                        row(vec![cell("f", 1, 1), cell("g", 1, 1)]),
+                    ],
+                )],
+                PandocTableFoot::default(),
+            )))]
+        );
+    }
+
+    #[test]
+    fn code_and_math_should_keep_language_and_environment() {
+        let pandoc = to_pandoc(indoc! {r"
+            {{{python
+            print(x)
+            }}}
+            {{$%align%
+            a &= b
+            }}$
+        "});
+
+        assert_eq!(
+            pandoc.blocks,
+            vec![
+                PandocBlock::CodeBlock(
+                    PandocAttr::with_classes(vec!["python"]),
+                    String::from("print(x)"),
+                ),
+                PandocBlock::Para(vec![PandocInline::Math(
+                    PandocMathType::DisplayMath,
+                    String::from("\\begin{align}\na &= b\n\\end{align}"),
+                )]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents configuration properties for plain text output that are
+/// separate from the running state during text conversion
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TextConfig {
+    /// Number of columns to word-wrap text to
+    #[serde(default = "TextConfig::default_width")]
+    pub width: usize,
+
+    /// If true, will color and style the text using ANSI escape codes for
+    /// display within a terminal
+    #[serde(default = "TextConfig::default_ansi")]
+    pub ansi: bool,
+
+    /// Represents the built-in theme used to highlight code blocks when
+    /// writing ANSI escape codes
+    #[serde(default = "TextConfig::default_code_theme")]
+    pub code_theme: String,
+}
+
+impl Default for TextConfig {
+    fn default() -> Self {
+        Self {
+            width: Self::default_width(),
+            ansi: Self::default_ansi(),
+            code_theme: Self::default_code_theme(),
+        }
+    }
+}
+
+impl TextConfig {
+    #[inline]
+    pub fn default_width() -> usize {
+        80
+    }
+
+    #[inline]
+    pub fn default_ansi() -> bool {
+        false
+    }
+
+    #[inline]
+    pub fn default_code_theme() -> String {
+        String::from("base16-ocean.dark")
+    }
+}
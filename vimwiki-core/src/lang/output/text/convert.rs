@@ -0,0 +1,54 @@
+use super::{Output, TextConfig, TextFormatter, TextOutputError};
+
+pub trait ToTextString {
+    fn to_text_string(
+        &self,
+        config: TextConfig,
+    ) -> Result<String, TextOutputError>;
+}
+
+impl<T: Output<TextFormatter>> ToTextString for T {
+    fn to_text_string(
+        &self,
+        config: TextConfig,
+    ) -> Result<String, TextOutputError> {
+        let mut formatter = TextFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_content())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TextOutputResult;
+
+    struct TestOutput<F: Fn(&mut TextFormatter) -> TextOutputResult>(F);
+    impl<F: Fn(&mut TextFormatter) -> TextOutputResult> Output<TextFormatter>
+        for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    fn _text(
+        text: impl Into<String>,
+    ) -> impl Fn(&mut TextFormatter) -> TextOutputResult {
+        let text = text.into();
+        move |f: &mut TextFormatter| {
+            use std::fmt::Write;
+            write!(f, "{}", text.as_str())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_text_string_should_produce_a_string_representing_only_the_text_of_the_output(
+    ) {
+        let output = TestOutput(_text("I am some text output"));
+        let result = output.to_text_string(TextConfig::default()).unwrap();
+        assert_eq!(result, "I am some text output");
+    }
+}
@@ -0,0 +1,19 @@
+use derive_more::{Display, Error};
+
+pub type TextOutputResult = Result<(), TextOutputError>;
+
+#[derive(Debug, Display, Error)]
+pub enum TextOutputError {
+    ThemeMissing(#[error(not(source))] String),
+
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
+
+impl From<std::fmt::Error> for TextOutputError {
+    fn from(source: std::fmt::Error) -> Self {
+        Self::Fmt { source }
+    }
+}
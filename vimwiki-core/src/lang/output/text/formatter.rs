@@ -0,0 +1,193 @@
+use super::{display_width, OutputFormatter, TextConfig, TextOutputError};
+use std::fmt::{self, Write};
+
+/// Escape code that resets all styling in ANSI output
+pub const ANSI_RESET: &str = "\x1b[0m";
+
+/// Represents the formatter to use to write plain text output that includes
+/// various options that can be set as well as a context for use when writing
+/// output
+#[derive(Clone, Default)]
+pub struct TextFormatter {
+    /// Represents the configuration associated with the formatter
+    config: TextConfig,
+
+    /// Contains the content to be injected into a template
+    content: String,
+
+    /// Stack of prefixes (such as the indentation of list item
+    /// continuations) written at the start of every line
+    prefixes: Vec<String>,
+
+    /// Stack of ANSI styles (select graphic rendition parameters such as
+    /// `1` for bold) applied to the text being written
+    styles: Vec<&'static str>,
+}
+
+impl OutputFormatter for TextFormatter {
+    type Error = TextOutputError;
+}
+
+impl Write for TextFormatter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // NOTE: We write a line at a time so we can inject the prefix of the
+        //       current nesting at the start of each line
+        for line in s.split_inclusive('\n') {
+            // NOTE: Blank lines are left empty rather than indented
+            if self.is_at_line_start() && line != "\n" {
+                for prefix in self.prefixes.iter() {
+                    self.content.push_str(prefix);
+                }
+            }
+
+            self.content.push_str(line);
+        }
+
+        Ok(())
+    }
+}
+
+impl TextFormatter {
+    pub fn new(config: TextConfig) -> Self {
+        Self {
+            config,
+            content: String::new(),
+            prefixes: Vec::new(),
+            styles: Vec::new(),
+        }
+    }
+
+    pub fn clone_without_content(&self) -> Self {
+        Self {
+            content: String::new(),
+            prefixes: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns true if the next character written will start a new line
+    #[inline]
+    pub fn is_at_line_start(&self) -> bool {
+        self.content.is_empty() || self.content.ends_with('\n')
+    }
+
+    /// Returns true if the formatter is writing ANSI escape codes
+    #[inline]
+    pub fn is_ansi(&self) -> bool {
+        self.config.ansi
+    }
+
+    /// Returns the number of columns left to write text within once the
+    /// prefixes of the current nesting are written
+    pub fn available_width(&self) -> usize {
+        let used = self
+            .prefixes
+            .iter()
+            .map(|x| display_width(x))
+            .sum::<usize>();
+        self.config.width.saturating_sub(used).max(1)
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter where every line started within the function is preceded by
+    /// the prefix, which is removed at the end of the function call
+    pub fn and_prefix<F>(
+        &mut self,
+        prefix: impl Into<String>,
+        f: F,
+    ) -> Result<(), TextOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), TextOutputError>,
+    {
+        self.prefixes.push(prefix.into());
+        let result = f(self);
+        self.prefixes.pop();
+        result
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter where all text written within the function is styled with
+    /// the given ANSI parameters, restoring the outer styles at the end of
+    /// the function call
+    ///
+    /// Does nothing beyond invoking the function when not writing ANSI
+    pub fn and_style<F>(
+        &mut self,
+        style: &'static str,
+        f: F,
+    ) -> Result<(), TextOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), TextOutputError>,
+    {
+        if !self.is_ansi() {
+            return f(self);
+        }
+
+        self.styles.push(style);
+        write!(self, "\x1b[{}m", style)?;
+        let result = f(self);
+        self.styles.pop();
+
+        write!(self, "{}", ANSI_RESET)?;
+        for style in self.styles.clone() {
+            write!(self, "\x1b[{}m", style)?;
+        }
+
+        result
+    }
+
+    /// Represents the config contained within the formatter
+    #[inline]
+    pub fn config(&self) -> &TextConfig {
+        &self.config
+    }
+
+    /// Represents the content contained within the formatter
+    #[inline]
+    pub fn get_content(&self) -> &str {
+        self.content.as_str()
+    }
+
+    /// Consumes the formatter and returns the content
+    #[inline]
+    pub fn into_content(self) -> String {
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn and_style_should_restore_outer_styles() {
+        let mut f = TextFormatter::new(TextConfig {
+            ansi: true,
+            ..Default::default()
+        });
+        f.and_style("1", |f| {
+            write!(f, "a")?;
+            f.and_style("3", |f| {
+                write!(f, "b")?;
+                Ok(())
+            })?;
+            write!(f, "c")?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(f.get_content(), "\x1b[1ma\x1b[3mb\x1b[0m\x1b[1mc\x1b[0m");
+    }
+
+    #[test]
+    fn and_style_should_do_nothing_when_not_ansi() {
+        let mut f = TextFormatter::default();
+        f.and_style("1", |f| {
+            write!(f, "a")?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(f.get_content(), "a");
+    }
+}
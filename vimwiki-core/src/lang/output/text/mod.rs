@@ -0,0 +1,965 @@
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::{TextFormatter, ANSI_RESET};
+
+mod convert;
+pub use convert::ToTextString;
+
+mod error;
+pub use error::{TextOutputError, TextOutputResult};
+
+use crate::lang::{
+    elements::*,
+    output::{Output, OutputFormatter},
+};
+use std::fmt::Write;
+use unicode_width::UnicodeWidthChar;
+
+/// ANSI styles of headers by level, where deeper levels use the last style
+const HEADER_STYLES: &[&str] = &["1;35", "1;34", "1;36", "1;32", "1;33"];
+
+/// ANSI style of keywords that mark something left to do
+const KEYWORD_OPEN_STYLE: &str = "1;33";
+
+/// ANSI style of keywords that mark a problem
+const KEYWORD_PROBLEM_STYLE: &str = "1;31";
+
+/// ANSI style of keywords that mark something done
+const KEYWORD_DONE_STYLE: &str = "1;32";
+
+const TAG_STYLE: &str = "36";
+const LINK_STYLE: &str = "4;34";
+const CODE_STYLE: &str = "32";
+const MATH_STYLE: &str = "35";
+const TERM_STYLE: &str = "1";
+const TABLE_HEAD_STYLE: &str = "1";
+const DIVIDER_STYLE: &str = "2";
+
+impl<'a> Output<TextFormatter> for Page<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        for element in self.elements.iter() {
+            let mut formatter = f.clone_without_content();
+            element.fmt(&mut formatter)?;
+            let text = formatter.into_content();
+
+            // Separate each block element that produces output with a blank
+            // line
+            if !text.is_empty() {
+                if !f.get_content().is_empty() {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<TextFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<TextFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<TextFormatter> for Blockquote<'a> {
+    /// Writes a blockquote as text, wrapping each group of lines into a
+    /// quoted paragraph
+    ///
+    /// ```text
+    /// > line 1 line 2
+    /// >
+    /// > line 3
+    /// ```
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        f.and_prefix("> ", |f| {
+            for (idx, lines) in self.line_groups().enumerate() {
+                if idx > 0 {
+                    writeln!(f, ">")?;
+                }
+
+                let text = lines
+                    .iter()
+                    .map(|line| line.trim())
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                for line in wrap(&text, f.available_width()) {
+                    writeln!(f, "{}", line)?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<TextFormatter> for DefinitionList<'a> {
+    /// Writes a definition list as text, indenting the definitions beneath
+    /// each term
+    ///
+    /// ```text
+    /// term
+    ///     definition 1
+    ///     definition 2
+    /// ```
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        for (term, defs) in self.iter() {
+            let term =
+                render_inline(f, Some(TERM_STYLE), term.as_inner().as_inner())?;
+            writeln!(f, "{}", term)?;
+
+            f.and_prefix("    ", |f| {
+                for def in defs {
+                    let def =
+                        render_inline(f, None, def.as_inner().as_inner())?;
+                    for line in wrap(&def, f.available_width()) {
+                        writeln!(f, "{}", line)?;
+                    }
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<TextFormatter> for Divider {
+    /// Writes a divider as a line spanning the width of the text
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let width = f.available_width();
+        f.and_style(DIVIDER_STYLE, |f| {
+            write!(f, "{}", "─".repeat(width))?;
+            Ok(())
+        })?;
+        writeln!(f)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for Header<'a> {
+    /// Writes a header as text, underlining the first two levels and
+    /// centering the header within the width if centered
+    ///
+    /// ```text
+    /// Header
+    /// ======
+    ///
+    /// Sub Header
+    /// ----------
+    /// ```
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let style =
+            HEADER_STYLES[(self.level.max(1) - 1).min(HEADER_STYLES.len() - 1)];
+        let text = render_inline(f, Some(style), &self.content)?;
+        let width = display_width(&text);
+        let indent = if self.centered {
+            f.available_width().saturating_sub(width) / 2
+        } else {
+            0
+        };
+
+        writeln!(f, "{}{}", " ".repeat(indent), text)?;
+
+        let underline = match self.level {
+            1 => Some("="),
+            2 => Some("-"),
+            _ => None,
+        };
+        if let Some(underline) = underline {
+            writeln!(f, "{}{}", " ".repeat(indent), underline.repeat(width))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for List<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        for item in self {
+            item.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for ListItem<'a> {
+    /// Writes a list item as text, numbering ordered items and aligning
+    /// wrapped and nested content with the content following the marker
+    ///
+    /// ```text
+    /// - [ ] incomplete item whose text wraps
+    ///       onto another line
+    /// 1. item
+    ///    - nested item
+    /// ```
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let mut marker = match &self.ty {
+            ListItemType::Ordered(_) => format!(
+                "{}{}",
+                self.pos + 1,
+                if self.suffix == ListItemSuffix::Paren {
+                    ')'
+                } else {
+                    '.'
+                }
+            ),
+            ListItemType::Unordered(UnorderedListItemType::Hyphen) => {
+                String::from("-")
+            }
+            ListItemType::Unordered(UnorderedListItemType::Asterisk) => {
+                String::from("*")
+            }
+            ListItemType::Unordered(UnorderedListItemType::Other(x)) => {
+                x.to_string()
+            }
+        };
+        marker.push(' ');
+
+        if let Some(todo_status) = self.attributes.todo_status.as_ref() {
+            let mut formatter = f.clone_without_content();
+            todo_status.fmt(&mut formatter)?;
+            marker.push_str(formatter.get_content());
+            marker.push(' ');
+        }
+
+        write!(f, "{}", marker)?;
+
+        if self.contents.is_empty() {
+            writeln!(f)?;
+            return Ok(());
+        }
+
+        // Content beyond the first line is aligned with the content
+        // following the marker
+        f.and_prefix(" ".repeat(display_width(&marker)), |f| {
+            for (idx, content) in self.contents.iter().enumerate() {
+                // NOTE: Sublists immediately follow other content, but
+                //       anything else is separated by a blank line
+                if idx > 0
+                    && !matches!(content.as_inner(), BlockElement::List(_))
+                {
+                    writeln!(f)?;
+                }
+
+                content.fmt(f)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl Output<TextFormatter> for ListItemTodoStatus {
+    /// Writes a todo status as the checkbox used by vimwiki
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        match self {
+            Self::Incomplete => write!(f, "[ ]")?,
+            Self::PartiallyComplete1 => write!(f, "[.]")?,
+            Self::PartiallyComplete2 => write!(f, "[o]")?,
+            Self::PartiallyComplete3 => write!(f, "[O]")?,
+            Self::Complete => write!(f, "[X]")?,
+            Self::Rejected => write!(f, "[-]")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for MathBlock<'a> {
+    /// Writes a math block as indented text
+    ///
+    /// ```text
+    ///     \begin{align}
+    ///     some math
+    ///     \end{align}
+    /// ```
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        f.and_prefix("    ", |f| {
+            let mut lines = Vec::new();
+            if let Some(env) = self.environment.as_ref() {
+                lines.push(format!(r"\begin{{{}}}", env));
+            }
+            lines.extend(self.lines.iter().map(ToString::to_string));
+            if let Some(env) = self.environment.as_ref() {
+                lines.push(format!(r"\end{{{}}}", env));
+            }
+
+            for line in lines {
+                f.and_style(MATH_STYLE, |f| {
+                    write!(f, "{}", line)?;
+                    Ok(())
+                })?;
+                writeln!(f)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<TextFormatter> for Placeholder<'a> {
+    /// Writes the title placeholder as a top-level header, dropping all
+    /// other placeholders
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        if let Self::Title(x) = self {
+            let width = display_width(x);
+            f.and_style(HEADER_STYLES[0], |f| {
+                write!(f, "{}", x)?;
+                Ok(())
+            })?;
+            writeln!(f)?;
+            writeln!(f, "{}", "=".repeat(width))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for CodeBlock<'a> {
+    /// Writes a code block as indented text, highlighting the code when
+    /// writing ANSI escape codes and the language or brush of the code
+    /// block is known
+    ///
+    /// ```text
+    ///     some code
+    /// ```
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let language = self.language_or_brush();
+
+        f.and_prefix("    ", |f| {
+            if f.is_ansi() {
+                if let Some(lines) = highlight_code(self, language, f.config())?
+                {
+                    for line in lines {
+                        writeln!(f, "{}{}", line, ANSI_RESET)?;
+                    }
+                    return Ok(());
+                }
+            }
+
+            for line in self {
+                writeln!(f, "{}", line)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Highlights the lines of a code block using ANSI escape codes, returning
+/// nothing if the language of the code block is unknown
+#[cfg(feature = "html")]
+fn highlight_code(
+    code: &CodeBlock<'_>,
+    language: Option<&str>,
+    config: &TextConfig,
+) -> Result<Option<Vec<String>>, TextOutputError> {
+    use super::html::{DEFAULT_SYNTAX_SET, DEFAULT_THEME_SET};
+    use syntect::{easy::HighlightLines, util::as_24_bit_terminal_escaped};
+
+    let syntax = match language
+        .and_then(|x| DEFAULT_SYNTAX_SET.find_syntax_by_token(x))
+    {
+        Some(syntax) => syntax,
+        None => return Ok(None),
+    };
+    let theme = DEFAULT_THEME_SET
+        .themes
+        .get(&config.code_theme)
+        .ok_or_else(|| {
+            TextOutputError::ThemeMissing(config.code_theme.to_string())
+        })?;
+
+    let mut h = HighlightLines::new(syntax, theme);
+    Ok(Some(
+        code.lines
+            .iter()
+            .map(|line| {
+                let regions = h.highlight(line, &DEFAULT_SYNTAX_SET);
+                as_24_bit_terminal_escaped(&regions[..], false)
+            })
+            .collect(),
+    ))
+}
+
+/// Highlighting code requires syntect, which comes with html support, so
+/// code is left as-is without it
+#[cfg(not(feature = "html"))]
+fn highlight_code(
+    _code: &CodeBlock<'_>,
+    _language: Option<&str>,
+    _config: &TextConfig,
+) -> Result<Option<Vec<String>>, TextOutputError> {
+    Ok(None)
+}
+
+impl<'a> Output<TextFormatter> for Paragraph<'a> {
+    /// Writes a paragraph as text, joining its lines and word-wrapping them
+    /// to the available width
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let mut lines = Vec::new();
+        for line in self.lines.iter() {
+            let text = render_inline(f, None, line)?;
+            if !text.is_empty() {
+                lines.push(text);
+            }
+        }
+
+        for line in wrap(&lines.join(" "), f.available_width()) {
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for Table<'a> {
+    /// Writes a table as text drawn with box-drawing characters, padding
+    /// cells to the display width of their column according to its alignment
+    /// and separating the rows above the divider with a rule, where cells
+    /// covered by spans are left empty
+    ///
+    /// ```text
+    /// ┌───┬──────┐
+    /// │ a │ long │
+    /// ├───┼──────┤
+    /// │ c │ d    │
+    /// └───┴──────┘
+    /// ```
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let col_cnt = self.col_cnt();
+        let divider = self.get_divider_row_index();
+
+        let mut rows = Vec::new();
+        for row in 0..self.row_cnt() {
+            if divider == Some(row) {
+                rows.push(None);
+                continue;
+            }
+
+            // NOTE: Rows above the divider are the head of the table
+            let style = match divider {
+                Some(divider) if row < divider => Some(TABLE_HEAD_STYLE),
+                _ => None,
+            };
+
+            let mut cells = Vec::new();
+            for col in 0..col_cnt {
+                cells.push(
+                    match self.get_cell(row, col).map(|x| x.as_inner()) {
+                        Some(Cell::Content(x)) => render_inline(f, style, x)?,
+                        _ => String::new(),
+                    },
+                );
+            }
+            rows.push(Some(cells));
+        }
+
+        let widths = (0..col_cnt)
+            .map(|col| {
+                rows.iter()
+                    .flatten()
+                    .map(|cells| display_width(&cells[col]))
+                    .max()
+                    .unwrap_or_default()
+                    .max(1)
+            })
+            .collect::<Vec<usize>>();
+
+        let make_rule = |left: &str, middle: &str, right: &str| {
+            let rules = widths
+                .iter()
+                .map(|width| "─".repeat(width + 2))
+                .collect::<Vec<String>>();
+            format!("{}{}{}", left, rules.join(middle), right)
+        };
+
+        writeln!(f, "{}", make_rule("┌", "┬", "┐"))?;
+        for row in rows.iter() {
+            match row {
+                Some(cells) => {
+                    write!(f, "│")?;
+                    for (col, (text, width)) in
+                        cells.iter().zip(widths.iter()).enumerate()
+                    {
+                        let padding = width.saturating_sub(display_width(text));
+                        let (left, right) = match self.get_column_alignment(col)
+                        {
+                            ColumnAlign::Center => {
+                                (padding / 2, padding - padding / 2)
+                            }
+                            ColumnAlign::Right => (padding, 0),
+                            _ => (0, padding),
+                        };
+                        write!(
+                            f,
+                            " {}{}{} │",
+                            " ".repeat(left),
+                            text,
+                            " ".repeat(right)
+                        )?;
+                    }
+                    writeln!(f)?;
+                }
+                None => writeln!(f, "{}", make_rule("├", "┼", "┤"))?,
+            }
+        }
+        writeln!(f, "{}", make_rule("└", "┴", "┘"))?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<TextFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        write!(f, "{}", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<TextFormatter> for DecoratedText<'a> {
+    /// Writes decorated text as styled text when writing ANSI escape codes
+    /// and as plain text otherwise
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let style = match self {
+            Self::Bold(_) => Some("1"),
+            Self::Italic(_) => Some("3"),
+            Self::Strikeout(_) => Some("9"),
+            Self::Superscript(_) | Self::Subscript(_) => None,
+        };
+
+        let write_contents = |f: &mut TextFormatter| -> TextOutputResult {
+            for content in self.as_contents_slice() {
+                content.fmt(f)?;
+            }
+            Ok(())
+        };
+
+        match style {
+            Some(style) => f.and_style(style, write_contents),
+            None => write_contents(f),
+        }
+    }
+}
+
+impl<'a> Output<TextFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<TextFormatter> for Keyword {
+    /// Writes a keyword, colored by whether it marks something left to do,
+    /// a problem, or something done
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let style = match self {
+            Self::Todo | Self::Started => KEYWORD_OPEN_STYLE,
+            Self::Fixme | Self::Xxx => KEYWORD_PROBLEM_STYLE,
+            Self::Done | Self::Fixed => KEYWORD_DONE_STYLE,
+        };
+
+        f.and_style(style, |f| {
+            write!(f, "{}", self)?;
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<TextFormatter> for Link<'a> {
+    /// Writes the description of a link, falling back to its target, where
+    /// raw links are written as their url
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        let text = match self {
+            Self::Raw { data } => data.uri_ref.to_string(),
+            _ => match self.to_description_or_fallback() {
+                Some(Description::Text(x)) => x.trim().to_string(),
+                Some(Description::TransclusionLink(data)) => {
+                    data.uri_ref.to_string()
+                }
+                None => self.data().uri_ref.to_string(),
+            },
+        };
+
+        f.and_style(LINK_STYLE, |f| {
+            write!(f, "{}", text)?;
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<TextFormatter> for Tags<'a> {
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        f.and_style(TAG_STYLE, |f| {
+            write!(f, ":")?;
+
+            for tag in self {
+                write!(f, "{}:", tag.as_str())?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<TextFormatter> for CodeInline<'a> {
+    /// Writes inline code as colored text when writing ANSI escape codes and
+    /// surrounded by backticks otherwise
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        if f.is_ansi() {
+            f.and_style(CODE_STYLE, |f| {
+                write!(f, "{}", self.as_str())?;
+                Ok(())
+            })
+        } else {
+            write!(f, "`{}`", self.as_str())?;
+            Ok(())
+        }
+    }
+}
+
+impl<'a> Output<TextFormatter> for MathInline<'a> {
+    /// Writes inline math as colored text when writing ANSI escape codes and
+    /// surrounded by dollar signs otherwise
+    fn fmt(&self, f: &mut TextFormatter) -> TextOutputResult {
+        if f.is_ansi() {
+            f.and_style(MATH_STYLE, |f| {
+                write!(f, "{}", self.as_str())?;
+                Ok(())
+            })
+        } else {
+            write!(f, "${}$", self.as_str())?;
+            Ok(())
+        }
+    }
+}
+
+impl<'a> Output<TextFormatter> for Comment<'a> {
+    /// Drops comments, which are not meant to be read
+    fn fmt(&self, _f: &mut TextFormatter) -> TextOutputResult {
+        Ok(())
+    }
+}
+
+/// Renders inline content as text with surrounding whitespace removed,
+/// optionally styling all of it
+fn render_inline(
+    f: &TextFormatter,
+    style: Option<&'static str>,
+    content: &InlineElementContainer<'_>,
+) -> Result<String, TextOutputError> {
+    let mut formatter = f.clone_without_content();
+    match style {
+        Some(style) => formatter.and_style(style, |f| content.fmt(f))?,
+        None => content.fmt(&mut formatter)?,
+    }
+    Ok(trim(&formatter.into_content()))
+}
+
+/// Iterates over the characters of text, flagging those that are part of an
+/// ANSI escape code
+fn ansi_chars(text: &str) -> impl Iterator<Item = (usize, char, bool)> + '_ {
+    let mut in_escape = false;
+    text.char_indices().map(move |(idx, c)| {
+        if c == '\x1b' {
+            in_escape = true;
+            (idx, c, true)
+        } else if in_escape {
+            in_escape = c != 'm';
+            (idx, c, true)
+        } else {
+            (idx, c, false)
+        }
+    })
+}
+
+/// Returns the width of text when displayed, ignoring ANSI escape codes
+pub(crate) fn display_width(text: &str) -> usize {
+    ansi_chars(text)
+        .filter(|(_, _, is_escape)| !is_escape)
+        .map(|(_, c, _)| c.width().unwrap_or_default())
+        .sum()
+}
+
+/// Removes whitespace surrounding the visible text, keeping any ANSI escape
+/// codes
+fn trim(text: &str) -> String {
+    let visible = ansi_chars(text)
+        .filter(|(_, c, is_escape)| !is_escape && !c.is_whitespace())
+        .map(|(idx, _, _)| idx)
+        .collect::<Vec<usize>>();
+
+    match (visible.first(), visible.last()) {
+        (Some(&start), Some(&end)) => ansi_chars(text)
+            .filter(|(idx, _, is_escape)| {
+                *is_escape || (*idx >= start && *idx <= end)
+            })
+            .map(|(_, c, _)| c)
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+/// Word-wraps text to the given width, where words wider than the width are
+/// placed on their own line and styles spanning lines are reset at the end
+/// of each line and restored at the start of the next
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    // Escape codes applied since the last reset
+    let mut active = String::new();
+
+    for word in text.split_whitespace() {
+        let word_width = display_width(word);
+        if line_width > 0 && line_width + 1 + word_width > width {
+            if !active.is_empty() {
+                line.push_str(ANSI_RESET);
+            }
+            lines.push(std::mem::replace(&mut line, active.clone()));
+            line_width = 0;
+        }
+
+        if line_width > 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+        line.push_str(word);
+        line_width += word_width;
+
+        let mut escape = String::new();
+        for (_, c, is_escape) in ansi_chars(word) {
+            if is_escape {
+                escape.push(c);
+                if c == 'm' {
+                    if escape == ANSI_RESET {
+                        active.clear();
+                    } else {
+                        active.push_str(&escape);
+                    }
+                    escape.clear();
+                }
+            }
+        }
+    }
+
+    if line_width > 0 {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+    use similar_asserts::assert_str_eq;
+
+    fn to_text(text: &str, config: TextConfig) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_text_string(config).unwrap()
+    }
+
+    #[test]
+    fn wrap_should_keep_styles_across_lines() {
+        assert_eq!(
+            wrap("\x1b[1mone two\x1b[0m three", 3),
+            vec!["\x1b[1mone\x1b[0m", "\x1b[1mtwo\x1b[0m", "three",]
+        );
+    }
+
+    #[test]
+    fn paragraph_should_wrap_to_width() {
+        let text = indoc! {"
+            = Header =
+            some *bold* text that keeps
+            going [[page|on and on]] with `code`
+        "};
+
+        assert_str_eq!(
+            to_text(
+                text,
+                TextConfig {
+                    width: 20,
+                    ..Default::default()
+                }
+            ),
+            indoc! {"
+                Header
+                ======
+
+                some bold text that
+                keeps going on and
+                on with `code`
+            "}
+        );
+    }
+
+    #[test]
+    fn list_should_align_wrapped_and_nested_content() {
+        let text = indoc! {"
+            - [X] first item that wraps
+                1) sub
+            - second
+        "};
+
+        assert_str_eq!(
+            to_text(
+                text,
+                TextConfig {
+                    width: 20,
+                    ..Default::default()
+                }
+            ),
+            indoc! {"
+                - [X] first item
+                      that wraps
+                      1) sub
+                - second
+            "}
+        );
+    }
+
+    #[test]
+    fn table_should_use_box_drawing_characters() {
+        let text = indoc! {r"
+            | a  | long |
+            |----|:----:|
+            | c  | d    |
+        "};
+
+        assert_str_eq!(
+            to_text(text, TextConfig::default()),
+            indoc! {"
+                ┌───┬──────┐
+                │ a │ long │
+                ├───┼──────┤
+                │ c │  d   │
+                └───┴──────┘
+            "}
+        );
+    }
+
+    #[test]
+    fn ansi_should_color_headers_keywords_tags_and_links() {
+        let text = indoc! {"
+            == TODO Header ==
+            :tag: [[page]]
+        "};
+
+        assert_str_eq!(
+            to_text(
+                text,
+                TextConfig {
+                    ansi: true,
+                    ..Default::default()
+                }
+            ),
+            concat!(
+                "\x1b[1;34m\x1b[1;33mTODO\x1b[0m\x1b[1;34m Header\x1b[0m\n",
+                "-----------\n",
+                "\n",
+                "\x1b[36m:tag:\x1b[0m \x1b[4;34mpage\x1b[0m\n",
+            )
+        );
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn ansi_should_highlight_code_blocks() {
+        let text = indoc! {"
+            {{{rust
+            let x = 5;
+            }}}
+        "};
+
+        let output = to_text(
+            text,
+            TextConfig {
+                ansi: true,
+                ..Default::default()
+            },
+        );
+        assert!(output.starts_with("    \x1b[38;2;"), "{:?}", output);
+        assert!(output.contains("let"), "{:?}", output);
+        assert!(
+            output.ends_with(&format!("{}\n", ANSI_RESET)),
+            "{:?}",
+            output
+        );
+    }
+}
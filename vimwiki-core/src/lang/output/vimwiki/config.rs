@@ -1,3 +1,4 @@
+use crate::lang::elements::ListItemSuffix;
 use serde::{Deserialize, Serialize};
 
 /// Represents configuration properties for HTML vimwiki that are separate from
@@ -12,6 +13,10 @@ pub struct VimwikiConfig {
     #[serde(default)]
     pub blockquote: VimwikiBlockquoteConfig,
 
+    /// Configuration settings that apply specifically to code blocks
+    #[serde(default)]
+    pub code: VimwikiCodeConfig,
+
     /// Configuration settings that apply specifically to comments
     #[serde(default)]
     pub comment: VimwikiCommentConfig,
@@ -108,6 +113,44 @@ impl VimwikiBlockquoteConfig {
     }
 }
 
+/// Represents configuration options related to code blocks
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VimwikiCodeConfig {
+    /// Style to use when writing the language of a code block
+    #[serde(default = "VimwikiCodeConfig::default_language_style")]
+    pub language_style: VimwikiCodeLanguageStyle,
+}
+
+impl Default for VimwikiCodeConfig {
+    fn default() -> Self {
+        Self {
+            language_style: Self::default_language_style(),
+        }
+    }
+}
+
+impl VimwikiCodeConfig {
+    #[inline]
+    pub fn default_language_style() -> VimwikiCodeLanguageStyle {
+        VimwikiCodeLanguageStyle::Keep
+    }
+}
+
+/// Represents the ways the language of a code block can be written
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VimwikiCodeLanguageStyle {
+    /// Keep the language as it was written
+    Keep,
+
+    /// Write the language directly after the braces such as `{{{python`
+    Bare,
+
+    /// Write the language as a brush class such as
+    /// `{{{class="brush: python"`
+    Brush,
+}
+
 /// Represents configuration options related to comments
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VimwikiCommentConfig {
@@ -197,6 +240,10 @@ pub struct VimwikiHeaderConfig {
     /// If true, will trim all leading and trailing whitespace from header's content
     #[serde(default = "VimwikiHeaderConfig::default_trim_content")]
     pub trim_content: bool,
+
+    /// If true, will write centered headers as regular headers
+    #[serde(default = "VimwikiHeaderConfig::default_no_centering")]
+    pub no_centering: bool,
 }
 
 impl Default for VimwikiHeaderConfig {
@@ -204,6 +251,7 @@ impl Default for VimwikiHeaderConfig {
         Self {
             no_padding: Self::default_no_padding(),
             trim_content: Self::default_trim_content(),
+            no_centering: Self::default_no_centering(),
         }
     }
 }
@@ -214,6 +262,11 @@ impl VimwikiHeaderConfig {
         false
     }
 
+    #[inline]
+    pub fn default_no_centering() -> bool {
+        false
+    }
+
     #[inline]
     pub fn default_trim_content() -> bool {
         true
@@ -226,17 +279,41 @@ pub struct VimwikiListConfig {
     /// Configuration settings that apply specifically to todo list items
     #[serde(default = "VimwikiListConfig::default_todo")]
     pub todo: VimwikiTodoListItemConfig,
+
+    /// Symbols to use for unordered list items at each level of nesting,
+    /// starting over for lists nested deeper than the symbols provided; if
+    /// empty, will keep the symbol of each list item
+    #[serde(default = "VimwikiListConfig::default_bullets")]
+    pub bullets: Vec<String>,
+
+    /// Suffix to use after the number or letter of ordered list items,
+    /// which are always numbered by their position; if none, will keep the
+    /// suffix of each list item
+    #[serde(default = "VimwikiListConfig::default_ordered_suffix")]
+    pub ordered_suffix: Option<ListItemSuffix>,
 }
 
 impl Default for VimwikiListConfig {
     fn default() -> Self {
         Self {
             todo: Self::default_todo(),
+            bullets: Self::default_bullets(),
+            ordered_suffix: Self::default_ordered_suffix(),
         }
     }
 }
 
 impl VimwikiListConfig {
+    #[inline]
+    pub fn default_bullets() -> Vec<String> {
+        Vec::new()
+    }
+
+    #[inline]
+    pub fn default_ordered_suffix() -> Option<ListItemSuffix> {
+        None
+    }
+
     #[inline]
     pub fn default_todo() -> VimwikiTodoListItemConfig {
         VimwikiTodoListItemConfig::default()
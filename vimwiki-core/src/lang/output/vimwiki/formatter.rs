@@ -18,6 +18,10 @@ pub struct VimwikiFormatter {
     /// If true, will skip writing whitespace until the first non-whitespace
     /// character is provided, in which case this is reset to false
     skip_whitespace: bool,
+
+    /// Current level of nesting within lists, where 0 means that the
+    /// formatter is not within a list
+    list_level: usize,
 }
 
 impl OutputFormatter for VimwikiFormatter {
@@ -49,6 +53,7 @@ impl VimwikiFormatter {
             content: String::new(),
             indent_level: 0,
             skip_whitespace: false,
+            list_level: 0,
         }
     }
 
@@ -87,6 +92,26 @@ impl VimwikiFormatter {
         result
     }
 
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter where the list level has been incremented by 1 and will be
+    /// decremented at the end of the function call
+    pub fn and_nest_list<F>(&mut self, f: F) -> Result<(), VimwikiOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), VimwikiOutputError>,
+    {
+        self.list_level += 1;
+        let result = f(self);
+        self.list_level -= 1;
+        result
+    }
+
+    /// Represents the current level of nesting within lists, starting at 1
+    /// for a list that is not within another list
+    #[inline]
+    pub fn list_level(&self) -> usize {
+        self.list_level
+    }
+
     /// Invokes the given function, passing it a mutable reference to this
     /// formatter where all leading and trailing whitespace the mutable ref
     /// produces will be removed
@@ -10,11 +10,15 @@ pub use convert::ToVimwikiString;
 mod error;
 pub use error::{VimwikiOutputError, VimwikiOutputResult};
 
+mod range;
+pub use range::{format_range, line_range_to_bytes};
+
 use crate::lang::{
     elements::*,
     output::{Output, OutputFormatter},
 };
-use std::{collections::HashMap, fmt::Write};
+use std::{borrow::Cow, collections::HashMap, fmt::Write};
+use unicode_width::UnicodeWidthStr;
 
 impl<'a> Output<VimwikiFormatter> for Page<'a> {
     fn fmt(&self, f: &mut VimwikiFormatter) -> VimwikiOutputResult {
@@ -167,11 +171,12 @@ impl<'a> Output<VimwikiFormatter> for Header<'a> {
         let VimwikiHeaderConfig {
             no_padding,
             trim_content,
+            no_centering,
         } = f.config().header;
 
         // If centered, we have to indent by some amount
         // TODO: Support configuring spaces for centered header
-        if self.centered {
+        if self.centered && !no_centering {
             write!(f, "    ")?;
         }
 
@@ -211,11 +216,13 @@ impl<'a> Output<VimwikiFormatter> for Header<'a> {
 
 impl<'a> Output<VimwikiFormatter> for List<'a> {
     fn fmt(&self, f: &mut VimwikiFormatter) -> VimwikiOutputResult {
-        for item in self {
-            item.fmt(f)?;
-        }
+        f.and_nest_list(|f| {
+            for item in self {
+                item.fmt(f)?;
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 }
 
@@ -229,7 +236,7 @@ impl<'a> Output<VimwikiFormatter> for ListItem<'a> {
                 // starting location
                 f.write_indent()?;
 
-                write!(f, "{} ", self.to_prefix())?;
+                write!(f, "{} ", list_item_prefix(self, f))?;
 
                 if let Some(todo_status) =
                     self.attributes.todo_status.as_ref().copied()
@@ -248,9 +255,30 @@ impl<'a> Output<VimwikiFormatter> for ListItem<'a> {
     }
 }
 
+/// Produces the prefix of a list item such as 1. or -, replacing the symbol
+/// or suffix if configured to do so
+fn list_item_prefix(item: &ListItem, f: &VimwikiFormatter) -> String {
+    let VimwikiListConfig {
+        bullets,
+        ordered_suffix,
+        ..
+    } = &f.config().list;
+
+    match &item.ty {
+        ListItemType::Unordered(_) if !bullets.is_empty() => {
+            let idx = f.list_level().saturating_sub(1) % bullets.len();
+            bullets[idx].to_string()
+        }
+        ListItemType::Ordered(ty) if ordered_suffix.is_some() => {
+            ty.to_prefix(item.pos, ordered_suffix.unwrap_or(item.suffix))
+        }
+        _ => item.to_prefix(),
+    }
+}
+
 impl Output<VimwikiFormatter> for ListItemTodoStatus {
     fn fmt(&self, f: &mut VimwikiFormatter) -> VimwikiOutputResult {
-        let VimwikiListConfig { todo } = f.config().list;
+        let todo = f.config().list.todo;
         let VimwikiTodoListItemConfig {
             incomplete_char,
             partially_complete_1_char,
@@ -326,22 +354,51 @@ impl<'a> Output<VimwikiFormatter> for CodeBlock<'a> {
         f.write_indent()?;
         write!(f, "{{{{{{")?;
 
-        if let Some(lang) = self.language.as_ref() {
+        // Convert the language into the configured style, which may move it
+        // into or out of the metadata
+        let mut language = self.language.as_deref().map(Cow::Borrowed);
+        let mut metadata = self
+            .metadata
+            .iter()
+            .map(|(key, value)| (key.as_ref(), Cow::Borrowed(value.as_ref())))
+            .collect::<Vec<(&str, Cow<'_, str>)>>();
+        match f.config().code.language_style {
+            VimwikiCodeLanguageStyle::Bare if language.is_none() => {
+                // NOTE: Only a brush with a single word can be written bare
+                let brush = self.language_or_brush().filter(|x| {
+                    !x.contains(|c: char| {
+                        c.is_whitespace() || c == ';' || c == '='
+                    })
+                });
+                if let Some(brush) = brush {
+                    metadata.retain(|(key, _)| *key != "class");
+                    language = Some(Cow::Borrowed(brush));
+                }
+            }
+            VimwikiCodeLanguageStyle::Brush
+                if !metadata.iter().any(|(key, _)| *key == "class") =>
+            {
+                if let Some(lang) = language.take() {
+                    metadata.push((
+                        "class",
+                        Cow::Owned(format!("brush: {}", lang)),
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(lang) = language.as_ref() {
             write!(f, "{}", lang)?;
         }
 
         // NOTE: We provide specific ordering by key to ensure consitent output,
         //       otherwise the metadata can move around with each output
-        let mut sorted_metadata = self
-            .metadata
-            .iter()
-            .map(|(key, value)| (key.as_ref(), value.as_ref()))
-            .collect::<Vec<(&str, &str)>>();
-        sorted_metadata.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        metadata.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
 
-        for (idx, (key, value)) in sorted_metadata.into_iter().enumerate() {
+        for (idx, (key, value)) in metadata.into_iter().enumerate() {
             // If a language is not preceeding the metadata, don't add a space
-            if idx != 0 || self.language.is_some() {
+            if idx != 0 || language.is_some() {
                 write!(f, " ")?;
             }
 
@@ -415,39 +472,15 @@ impl<'a> Output<VimwikiFormatter> for Table<'a> {
                 _ => None,
             })
             .map(|res| match res {
-                Ok((pos, s)) => {
-                    let is_empty = s.is_empty();
-                    let has_start_whitespace = s.trim_start().len() < s.len();
-                    let has_end_whitespace = s.trim_end().len() < s.len();
-
-                    let s = match (
-                        !no_padding,
-                        !is_empty,
-                        has_start_whitespace,
-                        has_end_whitespace,
-                    ) {
-                        // If we have content, no padding on either side, and want
-                        // padding then we apply it
-                        (true, true, false, false) => format!(" {} ", s),
-
-                        // If we have content, no padding at beginning, and want
-                        // padding then we apply it
-                        (true, true, false, true) => format!(" {}", s),
-
-                        // If we have content, no padding at end, and want
-                        // padding then we apply it
-                        (true, true, true, false) => format!("{} ", s),
-
-                        // If we don't have content and we want padding,
-                        // return a single space
-                        (true, false, _, _) => String::from(" "),
-
-                        // Otherwise, return string unmodified in situation where
-                        // we already having padding OR we don't want padding
-                        (true, true, _, _) | (false, _, _, _) => s,
-                    };
-
-                    Ok((pos, s))
+                // If we want padding, we surround the content with a single
+                // space on either side, or use a single space when empty
+                Ok((pos, s)) if !no_padding => {
+                    let s = s.trim();
+                    if s.is_empty() {
+                        Ok((pos, String::from(" ")))
+                    } else {
+                        Ok((pos, format!(" {} ", s)))
+                    }
                 }
                 x => x,
             })
@@ -459,7 +492,7 @@ impl<'a> Output<VimwikiFormatter> for Table<'a> {
             .iter()
             .fold(HashMap::new(), |mut acc, (pos, text)| {
                 let col = pos.col;
-                let new_size = text.len();
+                let new_size = text.width();
                 let cur_size = acc.entry(col).or_insert(new_size);
                 if new_size > *cur_size {
                     acc.insert(col, new_size);
@@ -487,8 +520,21 @@ impl<'a> Output<VimwikiFormatter> for Table<'a> {
             }
         }
 
-        // Third, we iterate through all cells, one row at a time, and write
-        // out the table using the size information
+        // Third, we determine the alignment of each column so content can be
+        // positioned within the column
+        let column_aligns: HashMap<usize, ColumnAlign> = (0..self.col_cnt())
+            .filter_map(|col| {
+                self.column(col)
+                    .find_map(|x| match x.as_inner() {
+                        Cell::Align(align) => Some(*align),
+                        _ => None,
+                    })
+                    .map(|align| (col, align))
+            })
+            .collect();
+
+        // Fourth, we iterate through all cells, one row at a time, and write
+        // out the table using the size and alignment information
         for row in 0..self.row_cnt() {
             f.write_indent()?;
 
@@ -507,12 +553,30 @@ impl<'a> Output<VimwikiFormatter> for Table<'a> {
                 // If we have fixed content, write it with optional padding
                 if let Some(text) = fixed_size_cells.get(&CellPos { row, col })
                 {
-                    write!(f, "{}", text)?;
+                    // NOTE: Spans are always kept at the start of the cell
+                    //       while content follows the column's alignment
+                    let remaining = max_size.saturating_sub(text.width());
+                    let is_content = matches!(
+                        self.get_cell(row, col).map(|x| x.as_inner()),
+                        Some(Cell::Content(_))
+                    );
+                    let (before, after) = match column_aligns.get(&col) {
+                        Some(ColumnAlign::Right) if is_content => {
+                            (remaining, 0)
+                        }
+                        Some(ColumnAlign::Center) if is_content => {
+                            (remaining / 2, remaining - remaining / 2)
+                        }
+                        _ => (0, remaining),
+                    };
 
-                    // NOTE: Add extra space to fill in remainder of cell
-                    if text.len() < max_size {
-                        write!(f, "{}", " ".repeat(max_size - text.len()))?;
-                    }
+                    write!(
+                        f,
+                        "{}{}{}",
+                        " ".repeat(before),
+                        text,
+                        " ".repeat(after)
+                    )?;
 
                 // Otherwise, we have some form of divider and want to write it
                 } else {
@@ -1475,6 +1539,26 @@ mod tests {
         assert_str_eq!(f.get_content(), "    = some header =\n");
     }
 
+    #[test]
+    fn header_should_not_be_centered_if_setting_enabled() {
+        let header = Header::new(
+            text_to_inline_element_container("some header"),
+            1,
+            true,
+        );
+
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            header: VimwikiHeaderConfig {
+                no_centering: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        header.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), "= some header =\n");
+    }
+
     #[test]
     fn header_should_not_support_indentation() {
         let header = Header::new(
@@ -1524,6 +1608,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn list_should_use_configured_bullets_for_each_level() {
+        let page: Page = crate::Language::from_vimwiki_str(indoc! {"
+            - a
+              * b
+                - c
+                  * d
+            - e
+        "})
+        .parse()
+        .unwrap();
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            list: VimwikiListConfig {
+                bullets: vec![String::from("*"), String::from("-")],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        page.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {"
+                * a
+                    - b
+                        * c
+                            - d
+                * e
+            "}
+        );
+    }
+
+    #[test]
+    fn list_should_use_configured_suffix_for_ordered_list_items() {
+        let page: Page = crate::Language::from_vimwiki_str(indoc! {"
+            3) a
+            7) b
+               - c
+        "})
+        .parse()
+        .unwrap();
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            list: VimwikiListConfig {
+                ordered_suffix: Some(ListItemSuffix::Period),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        page.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), "1. a\n2. b\n    - c\n");
+    }
+
     #[test]
     fn list_should_output_list_items_extra_content_at_appropriate_indentation()
     {
@@ -1974,6 +2111,50 @@ mod tests {
         assert_str_eq!(f.get_content(), "{{{language key1=\"value1\" key2=\"value2\"\nsome lines\nof code\n}}}\n");
     }
 
+    #[test]
+    fn code_block_should_write_brush_as_bare_language_if_configured() {
+        let code = CodeBlock::new(
+            None,
+            vec![
+                (Cow::from("class"), Cow::from("brush: python")),
+                (Cow::from("key"), Cow::from("value")),
+            ]
+            .into_iter()
+            .collect(),
+            vec![Cow::from("code")],
+        );
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            code: VimwikiCodeConfig {
+                language_style: VimwikiCodeLanguageStyle::Bare,
+            },
+            ..Default::default()
+        });
+        code.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), "{{{python key=\"value\"\ncode\n}}}\n");
+    }
+
+    #[test]
+    fn code_block_should_write_bare_language_as_brush_if_configured() {
+        let code = CodeBlock::new(
+            Some(Cow::from("python")),
+            Default::default(),
+            vec![Cow::from("code")],
+        );
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            code: VimwikiCodeConfig {
+                language_style: VimwikiCodeLanguageStyle::Brush,
+            },
+            ..Default::default()
+        });
+        code.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            "{{{class=\"brush: python\"\ncode\n}}}\n"
+        );
+    }
+
     #[test]
     fn code_block_should_support_indentation() {
         let code = CodeBlock::new(
@@ -2406,6 +2587,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn table_should_position_content_using_column_alignment() {
+        let table = Table::new(
+            vec![
+                (CellPos { row: 0, col: 0 }, make_text_cell("left")),
+                (CellPos { row: 0, col: 1 }, make_text_cell("right")),
+                (CellPos { row: 0, col: 2 }, make_text_cell("center")),
+                (
+                    CellPos { row: 1, col: 0 },
+                    Located::from(Cell::Align(ColumnAlign::Left)),
+                ),
+                (
+                    CellPos { row: 1, col: 1 },
+                    Located::from(Cell::Align(ColumnAlign::Right)),
+                ),
+                (
+                    CellPos { row: 1, col: 2 },
+                    Located::from(Cell::Align(ColumnAlign::Center)),
+                ),
+                (CellPos { row: 2, col: 0 }, make_text_cell("a")),
+                (CellPos { row: 2, col: 1 }, make_text_cell("  b ")),
+                (CellPos { row: 2, col: 2 }, make_text_cell("c")),
+                (
+                    CellPos { row: 3, col: 0 },
+                    Located::from(Cell::Span(CellSpan::FromAbove)),
+                ),
+                (
+                    CellPos { row: 3, col: 1 },
+                    Located::from(Cell::Span(CellSpan::FromAbove)),
+                ),
+                (
+                    CellPos { row: 3, col: 2 },
+                    Located::from(Cell::Span(CellSpan::FromLeft)),
+                ),
+            ],
+            false,
+        );
+        let mut f = VimwikiFormatter::default();
+        table.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {r"
+                | left | right | center |
+                |:-----|------:|:------:|
+                | a    |     b |   c    |
+                | \/   | \/    | >      |
+            "},
+        );
+    }
+
+    #[test]
+    fn table_should_size_columns_using_display_width() {
+        let table = Table::new(
+            vec![
+                (CellPos { row: 0, col: 0 }, make_text_cell("日本語")),
+                (CellPos { row: 0, col: 1 }, make_text_cell("é")),
+                (CellPos { row: 1, col: 0 }, make_text_cell("abc")),
+                (CellPos { row: 1, col: 1 }, make_text_cell("ab")),
+            ],
+            false,
+        );
+        let mut f = VimwikiFormatter::default();
+        table.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {"
+                | 日本語 | é  |
+                | abc    | ab |
+            "},
+        );
+    }
+
     #[test]
     fn text_should_output_the_same() {
         let text = Text::from("some text");
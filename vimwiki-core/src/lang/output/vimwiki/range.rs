@@ -0,0 +1,167 @@
+use super::{ToVimwikiString, VimwikiConfig, VimwikiOutputError};
+use crate::{
+    lang::elements::{BlockElement, Page, Region},
+    wiki::TextEdit,
+};
+use std::ops::Range;
+
+/// Formats only the top-level block elements of a page that overlap the
+/// given byte range of its text, producing the smallest edit that changes
+/// the text to match the formatted elements
+///
+/// An empty range formats the element containing its position. Returns none
+/// if no element overlaps the range or the elements are already formatted
+pub fn format_range(
+    text: &str,
+    page: &Page<'_>,
+    range: Range<usize>,
+    config: VimwikiConfig,
+) -> Result<Option<TextEdit>, VimwikiOutputError> {
+    let overlaps = |region: Region| {
+        let start = region.offset();
+        let end = start + region.len();
+        if range.start == range.end {
+            region.contains(range.start)
+        } else {
+            start < range.end && range.start < end
+        }
+    };
+
+    let elements = page
+        .elements
+        .iter()
+        .filter(|e| overlaps(e.region()))
+        .map(|e| e.as_ref().map(BlockElement::to_borrowed))
+        .collect::<Vec<_>>();
+
+    let (start, end) = match (elements.first(), elements.last()) {
+        (Some(first), Some(last)) => (
+            first.region().offset(),
+            last.region().offset() + last.region().len(),
+        ),
+        _ => return Ok(None),
+    };
+
+    let old_text = &text[start..end];
+    let mut new_text = Page { elements }.to_vimwiki_string(config)?;
+
+    // Elements at the end of a file without a trailing linefeed keep it that
+    // way, as the rest of the file is not being formatted
+    if !old_text.ends_with('\n') && new_text.ends_with('\n') {
+        new_text.pop();
+    }
+
+    Ok(make_minimal_edit(start, old_text, &new_text))
+}
+
+/// Converts a range of zero-based line numbers into the range of bytes
+/// spanning those lines within the text, including their linefeeds
+///
+/// Lines beyond the end of the text are clamped to the end of the text
+pub fn line_range_to_bytes(text: &str, lines: Range<usize>) -> Range<usize> {
+    let line_start = |line: usize| {
+        if line == 0 {
+            return 0;
+        }
+
+        text.match_indices('\n')
+            .nth(line - 1)
+            .map(|(idx, _)| idx + 1)
+            .unwrap_or_else(|| text.len())
+    };
+
+    let start = line_start(lines.start);
+    let end = line_start(lines.end).max(start);
+    start..end
+}
+
+/// Produces an edit replacing only the portion of the old text that differs
+/// from the new text, or none if they are the same
+fn make_minimal_edit(
+    offset: usize,
+    old_text: &str,
+    new_text: &str,
+) -> Option<TextEdit> {
+    if old_text == new_text {
+        return None;
+    }
+
+    let prefix_len = old_text
+        .char_indices()
+        .zip(new_text.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((idx, _), _)| idx)
+        .unwrap_or_else(|| old_text.len().min(new_text.len()));
+
+    let old_rest = &old_text[prefix_len..];
+    let new_rest = &new_text[prefix_len..];
+    let suffix_len = old_rest
+        .chars()
+        .rev()
+        .zip(new_rest.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+
+    Some(TextEdit::new(
+        Region::new(offset + prefix_len, old_rest.len() - suffix_len),
+        &new_rest[..new_rest.len() - suffix_len],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileEdits, Language};
+
+    fn apply(text: &str, edit: Option<TextEdit>) -> String {
+        FileEdits {
+            path: Default::default(),
+            edits: edit.into_iter().collect(),
+        }
+        .apply(text)
+    }
+
+    #[test]
+    fn format_range_should_only_format_elements_overlapping_range() {
+        let text = "=a=\n|a|b|\n|-|-|\n\n=b=\n|c|d|\n|-|-|\n";
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+
+        // Selects only the second table
+        let range = line_range_to_bytes(text, 5..7);
+        let edit = format_range(text, &page, range, VimwikiConfig::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            apply(text, Some(edit.clone())),
+            "=a=\n|a|b|\n|-|-|\n\n=b=\n| c | d |\n|---|---|\n"
+        );
+
+        // Edit should not cover the unchanged leading pipe
+        assert_eq!(edit.region.offset(), 22);
+    }
+
+    #[test]
+    fn format_range_should_return_none_if_already_formatted() {
+        let text = "= a =\n\nsome text";
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+
+        let edit =
+            format_range(text, &page, 8..8, VimwikiConfig::default()).unwrap();
+        assert_eq!(edit, None);
+
+        let edit =
+            format_range(text, &page, 100..200, VimwikiConfig::default())
+                .unwrap();
+        assert_eq!(edit, None);
+    }
+
+    #[test]
+    fn line_range_to_bytes_should_span_full_lines() {
+        let text = "one\ntwo\nthree";
+        assert_eq!(line_range_to_bytes(text, 0..1), 0..4);
+        assert_eq!(line_range_to_bytes(text, 1..3), 4..13);
+        assert_eq!(line_range_to_bytes(text, 2..10), 8..13);
+        assert_eq!(line_range_to_bytes(text, 5..10), 13..13);
+    }
+}
@@ -0,0 +1,183 @@
+use super::{Versioned, SCHEMA_VERSION};
+use derive_more::{Display, Error};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Represents an error encountered when migrating serialized data
+#[derive(Debug, Display, Error)]
+pub enum MigrationError {
+    /// Represents data from a schema version newer than this library knows
+    #[display(
+        fmt = "Schema version {} is newer than supported version {}",
+        version,
+        SCHEMA_VERSION
+    )]
+    UnsupportedVersion {
+        #[error(not(source))]
+        version: u64,
+    },
+
+    /// Represents data that does not match the schema once migrated
+    #[display(fmt = "{}", _0)]
+    InvalidData(serde_json::Error),
+}
+
+/// Upgrades serialized data to the current schema version, returning the
+/// data wrapped as a [`Versioned`] value
+///
+/// Data that is not wrapped with a version is assumed to be from version 1,
+/// which predates versioning. Each migration only rewrites data that has the
+/// older shape, so unversioned data that already has the current shape is
+/// left unchanged
+pub fn migrate(value: Value) -> Result<Value, MigrationError> {
+    let (version, mut data) = match value {
+        Value::Object(mut map) if is_versioned(&map) => {
+            let version = map["version"].as_u64().unwrap_or_default();
+            (version, map.remove("data").unwrap_or_default())
+        }
+        value => (1, value),
+    };
+
+    if version > u64::from(SCHEMA_VERSION) {
+        return Err(MigrationError::UnsupportedVersion { version });
+    }
+
+    if version < 2 {
+        migrate_list_items_v1(&mut data);
+    }
+
+    let mut map = Map::new();
+    map.insert(String::from("version"), Value::from(SCHEMA_VERSION));
+    map.insert(String::from("data"), data);
+    Ok(Value::Object(map))
+}
+
+/// Migrates serialized data to the current schema version and then
+/// deserializes it
+pub fn from_json_value<T: DeserializeOwned>(
+    value: Value,
+) -> Result<Versioned<T>, MigrationError> {
+    serde_json::from_value(migrate(value)?).map_err(MigrationError::InvalidData)
+}
+
+fn is_versioned(map: &Map<String, Value>) -> bool {
+    map.len() == 2
+        && matches!(map.get("version"), Some(x) if x.is_u64())
+        && map.contains_key("data")
+}
+
+/// Rewrites list items from version 1, whose contents were an object of
+/// `InlineContent` and `List` entries, into version 2, whose contents are a
+/// sequence of block elements where inline content becomes a paragraph
+fn migrate_list_items_v1(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if is_list_item(map) {
+                if let Some(contents) = map.get_mut("contents") {
+                    migrate_list_item_contents_v1(contents);
+                }
+            }
+
+            for value in map.values_mut() {
+                migrate_list_items_v1(value);
+            }
+        }
+        Value::Array(values) => {
+            for value in values.iter_mut() {
+                migrate_list_items_v1(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_list_item(map: &Map<String, Value>) -> bool {
+    ["ty", "suffix", "pos", "contents"]
+        .iter()
+        .all(|key| map.contains_key(*key))
+}
+
+fn migrate_list_item_contents_v1(contents: &mut Value) {
+    if let Some(inner) = contents
+        .as_object_mut()
+        .and_then(|map| map.remove("contents"))
+    {
+        *contents = inner;
+    }
+
+    for located in contents.as_array_mut().into_iter().flatten() {
+        let inner =
+            match located.get_mut("inner").and_then(Value::as_object_mut) {
+                Some(inner) => inner,
+                None => continue,
+            };
+
+        if let Some(mut line) = inner.remove("InlineContent") {
+            if let Some(elements) =
+                line.as_object_mut().and_then(|map| map.remove("elements"))
+            {
+                line = elements;
+            }
+
+            let mut paragraph = Map::new();
+            paragraph.insert(String::from("lines"), Value::Array(vec![line]));
+            inner.insert(String::from("Paragraph"), Value::Object(paragraph));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page};
+    use serde_json::json;
+
+    fn parse(text: &str) -> Page<'static> {
+        Language::from_vimwiki_str(text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn from_json_value_should_migrate_list_items_from_version_1() {
+        let page = parse("- one\n");
+        let mut value = serde_json::to_value(&page).unwrap();
+
+        // Rewrite the list item into the shape used by version 1
+        let item = &mut value["elements"][0]["inner"]["List"]["items"][0];
+        let line = item["inner"]["contents"][0]["inner"]["Paragraph"]["lines"]
+            [0]
+        .take();
+        let region = item["inner"]["contents"][0]["region"].take();
+        item["inner"]["contents"] = json!({
+            "contents": [{
+                "inner": { "InlineContent": { "elements": line } },
+                "region": region,
+            }]
+        });
+
+        let versioned = from_json_value::<Page>(value).unwrap();
+        assert!(versioned.is_current());
+        assert_eq!(versioned.into_inner(), page);
+    }
+
+    #[test]
+    fn from_json_value_should_accept_current_data_with_or_without_version() {
+        let page = parse("= Header =\n- one\n  - two\n");
+        let versioned = serde_json::to_value(Versioned::new(&page)).unwrap();
+        let bare = serde_json::to_value(&page).unwrap();
+
+        assert_eq!(from_json_value::<Page>(versioned).unwrap().data, page);
+        assert_eq!(from_json_value::<Page>(bare).unwrap().data, page);
+    }
+
+    #[test]
+    fn migrate_should_fail_for_newer_versions() {
+        let value = json!({ "version": SCHEMA_VERSION + 1, "data": {} });
+        assert!(matches!(
+            migrate(value),
+            Err(MigrationError::UnsupportedVersion { .. })
+        ));
+    }
+}
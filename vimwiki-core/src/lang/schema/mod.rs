@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "schema")]
+mod migrate;
+#[cfg(feature = "schema")]
+pub use migrate::{from_json_value, migrate, MigrationError};
+
+/// Current version of the schema describing serialized pages and elements,
+/// which is incremented whenever the serialized shape of an element changes
+///
+/// * `1` is the shape used by 0.1.0, where list items contained inline
+///   content and sublists
+/// * `2` is the current shape, where list items contain block elements
+///   such as paragraphs, sublists, code blocks, and tables
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Represents serialized data tagged with the version of the schema that
+/// describes it, allowing consumers to detect and migrate older data
+///
+/// ## Examples
+///
+/// ```
+/// use vimwiki::{Language, Page, Versioned, SCHEMA_VERSION};
+///
+/// let page: Page = Language::from_vimwiki_str("some text").parse().unwrap();
+/// let versioned = Versioned::new(page);
+/// assert_eq!(versioned.version, SCHEMA_VERSION);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Versioned<T> {
+    /// Version of the schema describing the data
+    pub version: u32,
+
+    /// Data described by the schema
+    pub data: T,
+}
+
+impl<T> Versioned<T> {
+    /// Tags data with the current schema version
+    pub fn new(data: T) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            data,
+        }
+    }
+
+    /// Returns true if the data is described by the current schema version
+    pub fn is_current(&self) -> bool {
+        self.version == SCHEMA_VERSION
+    }
+
+    /// Consumes the wrapper, returning the data
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+/// Generates a JSON Schema describing `T` tagged with the current schema
+/// version, as produced when serializing [`Versioned<T>`]
+///
+/// The schema of a [`crate::Page`] includes definitions of every element
+/// that can appear within a page
+#[cfg(feature = "schema")]
+pub fn json_schema<T: schemars::JsonSchema>() -> schemars::schema::RootSchema {
+    use schemars::schema::{InstanceType, Schema, SchemaObject};
+
+    let mut schema = schemars::schema_for!(Versioned<T>);
+    let metadata = schema.schema.metadata();
+    metadata.title = Some(format!(
+        "{} (schema version {})",
+        T::schema_name(),
+        SCHEMA_VERSION
+    ));
+    metadata.description = Some(format!(
+        "{} tagged with the version of the schema that describes it",
+        T::schema_name()
+    ));
+
+    // Pin the version so validators reject data from other versions
+    schema.schema.object().properties.insert(
+        String::from("version"),
+        Schema::Object(SchemaObject {
+            instance_type: Some(InstanceType::Integer.into()),
+            const_value: Some(serde_json::Value::from(SCHEMA_VERSION)),
+            ..Default::default()
+        }),
+    );
+
+    schema
+}
+
+/// Produces a JSON Schema for fields holding a [`uriparse::URIReference`],
+/// whose serialized form is defined by the uriparse crate
+#[cfg(feature = "schema")]
+pub(crate) fn uri_reference_schema(
+    _gen: &mut schemars::gen::SchemaGenerator,
+) -> schemars::schema::Schema {
+    use schemars::schema::{InstanceType, Schema, SchemaObject};
+
+    let mut schema = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    };
+    schema.metadata().description = Some(String::from(
+        "URI reference as serialized by the uriparse crate",
+    ));
+
+    let object = schema.object();
+    for name in &["scheme", "authority", "path", "query", "fragment"] {
+        object
+            .properties
+            .insert(name.to_string(), Schema::Bool(true));
+        object.required.insert(name.to_string());
+    }
+
+    Schema::Object(schema)
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+    use super::*;
+    use crate::Page;
+
+    #[test]
+    fn json_schema_should_define_every_element_of_page() {
+        let schema = json_schema::<Page>();
+        for name in &[
+            "BlockElement",
+            "InlineElement",
+            "ListItem",
+            "Table",
+            "Placeholder",
+            "Region",
+        ] {
+            assert!(
+                schema.definitions.keys().any(|x| x.starts_with(name)),
+                "Missing definition for {}",
+                name
+            );
+        }
+
+        let version =
+            &schema.schema.object.as_ref().unwrap().properties["version"];
+        let value = serde_json::to_value(version).unwrap();
+        assert_eq!(value["const"], SCHEMA_VERSION);
+    }
+}
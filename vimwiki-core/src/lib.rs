@@ -1,5 +1,6 @@
 mod lang;
 mod utils;
+mod wiki;
 
 // Export all elements at top level
 pub use lang::elements::*;
@@ -7,6 +8,12 @@ pub use lang::elements::*;
 // Export all outputs at top level
 pub use lang::output::*;
 
+// Export schema versioning at top level
+pub use lang::schema::*;
+
+// Export all wiki-level operations at top level
+pub use wiki::*;
+
 // Export our parser error, which is used for language parsing
 pub use lang::parsers::Error as ParseError;
 
@@ -1,3 +1,8 @@
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+};
+
 /// Represents an equality check that is considered strict. In the case of
 /// a `Located<T>`, will check both the inner type AND the region.
 pub trait StrictEq<Rhs: ?Sized = Self> {
@@ -17,3 +22,66 @@ impl<T: StrictEq> StrictEq for Vec<T> {
             && self.iter().zip(other.iter()).all(|(x, y)| x.strict_eq(y))
     }
 }
+
+/// Normalize a path, removing things like `.` and `..`.
+///
+/// CAUTION: This does not resolve symlinks (unlike
+/// [`std::fs::canonicalize`]). This may cause incorrect or surprising
+/// behavior at times. This should be used carefully. Unfortunately,
+/// [`std::fs::canonicalize`] can be hard to use correctly, since it can often
+/// fail, or on Windows returns annoying device paths. This is a problem Cargo
+/// needs to improve on.
+///
+/// From https://github.com/rust-lang/cargo/blob/070e459c2d8b79c5b2ac5218064e7603329c92ae/crates/cargo-util/src/paths.rs#L81
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut components = path.components().peekable();
+    let mut ret =
+        if let Some(c @ Component::Prefix(..)) = components.peek().cloned() {
+            components.next();
+            PathBuf::from(c.as_os_str())
+        } else {
+            PathBuf::new()
+        };
+
+    for component in components {
+        match component {
+            Component::Prefix(..) => unreachable!(),
+            Component::RootDir => {
+                ret.push(component.as_os_str());
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                ret.pop();
+            }
+            Component::Normal(c) => {
+                ret.push(c);
+            }
+        }
+    }
+    ret
+}
+
+/// Converts a path to a string suitable for a uri by converting platform-specific
+/// separators into /
+pub fn path_to_uri_string(path: &Path) -> String {
+    let out = path
+        .components()
+        .filter_map(|c| {
+            match c {
+                // Prefixes like C: are skipped
+                Component::Prefix(_) => None,
+                Component::RootDir => None,
+                Component::CurDir => Some(Cow::Borrowed(".")),
+                Component::ParentDir => Some(Cow::Borrowed("..")),
+                Component::Normal(x) => Some(x.to_string_lossy()),
+            }
+        })
+        .collect::<Vec<Cow<'_, str>>>()
+        .join("/");
+
+    if path.is_absolute() {
+        format!("/{}", out)
+    } else {
+        out
+    }
+}
@@ -0,0 +1,393 @@
+use super::{
+    collect_elements, collect_links, LinkTarget, LinkTargetError,
+    LinkTargetKind, Wiki,
+};
+use crate::{
+    lang::elements::{
+        Anchor, BlockElement, Element, InlineElement, Link, Page, Region,
+    },
+    utils::normalize_path,
+    Language,
+};
+use derive_more::{Display, Error, IsVariant};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// Represents the kind of link that was checked
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq, Hash, IsVariant)]
+pub enum LinkKind {
+    #[display(fmt = "wiki")]
+    Wiki,
+
+    #[display(fmt = "interwiki")]
+    IndexedInterWiki,
+
+    #[display(fmt = "interwiki")]
+    NamedInterWiki,
+
+    #[display(fmt = "diary")]
+    Diary,
+
+    #[display(fmt = "raw")]
+    Raw,
+
+    #[display(fmt = "transclusion")]
+    Transclusion,
+}
+
+impl<'a> From<&'a Link<'_>> for LinkKind {
+    fn from(link: &'a Link<'_>) -> Self {
+        match link {
+            Link::Wiki { .. } => Self::Wiki,
+            Link::IndexedInterWiki { .. } => Self::IndexedInterWiki,
+            Link::NamedInterWiki { .. } => Self::NamedInterWiki,
+            Link::Diary { .. } => Self::Diary,
+            Link::Raw { .. } => Self::Raw,
+            Link::Transclusion { .. } => Self::Transclusion,
+        }
+    }
+}
+
+/// Represents the reason why a link is considered broken
+#[derive(Clone, Debug, Display, PartialEq, Eq, Error)]
+pub enum BrokenLinkReason {
+    /// Represents a link that could not be resolved to a resource
+    #[display(fmt = "{}", _0)]
+    Unresolved(LinkTargetError),
+
+    /// Represents a link whose targeted page does not exist
+    #[display(fmt = "Page {:?} does not exist", path)]
+    MissingPage {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+
+    /// Represents a link whose targeted directory does not exist
+    #[display(fmt = "Directory {:?} does not exist", path)]
+    MissingDirectory {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+
+    /// Represents a link whose targeted file does not exist
+    #[display(fmt = "File {:?} does not exist", path)]
+    MissingFile {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+
+    /// Represents a link whose anchor does not match any header or tag
+    /// within the targeted page
+    #[display(fmt = "No header or tag in {:?} matches {}", path, anchor)]
+    MissingAnchor {
+        #[error(not(source))]
+        path: PathBuf,
+        anchor: Anchor<'static>,
+    },
+}
+
+/// Represents a link within a page that does not lead anywhere
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// Path to the page containing the link
+    pub path: PathBuf,
+
+    /// Region within the page covered by the link
+    pub region: Region,
+
+    /// Kind of link that is broken
+    pub kind: LinkKind,
+
+    /// Decoded uri of the link
+    pub uri: String,
+
+    /// Reason why the link is broken
+    pub reason: BrokenLinkReason,
+}
+
+/// Checks every link within every page of the provided wikis, returning
+/// the links that are broken in the order they appear within each page
+///
+/// Links to remote resources such as websites are not checked. Pages that
+/// are not loaded as part of a wiki are read from the filesystem when
+/// checking anchors that point to them
+pub fn check_links(wikis: &[Wiki<'_>]) -> Vec<BrokenLink> {
+    let mut checker = LinkChecker::new(wikis);
+    wikis
+        .iter()
+        .flat_map(|wiki| wiki.pages.iter())
+        .flat_map(|page| checker.check_page(&page.path, &page.page))
+        .collect()
+}
+
+/// Checks every link within a single page against the provided wikis
+pub fn check_page_links(
+    wikis: &[Wiki<'_>],
+    path: &Path,
+    page: &Page<'_>,
+) -> Vec<BrokenLink> {
+    LinkChecker::new(wikis).check_page(path, page)
+}
+
+/// Caches the anchor targets of pages so each page is only examined once
+struct LinkChecker<'a, 'b> {
+    wikis: &'a [Wiki<'b>],
+    pages: HashMap<PathBuf, &'a Page<'b>>,
+    targets: HashMap<PathBuf, Option<Vec<String>>>,
+}
+
+impl<'a, 'b> LinkChecker<'a, 'b> {
+    fn new(wikis: &'a [Wiki<'b>]) -> Self {
+        let pages = wikis
+            .iter()
+            .flat_map(|wiki| wiki.pages.iter())
+            .map(|page| (normalize_path(&page.path), &page.page))
+            .collect();
+
+        Self {
+            wikis,
+            pages,
+            targets: HashMap::new(),
+        }
+    }
+
+    fn check_page(&mut self, path: &Path, page: &Page<'_>) -> Vec<BrokenLink> {
+        collect_links(page)
+            .into_iter()
+            .filter_map(|link| {
+                let region = link.region();
+                let link = link.into_inner();
+                self.check_link(path, &link).map(|reason| BrokenLink {
+                    path: path.to_path_buf(),
+                    region,
+                    kind: LinkKind::from(&link),
+                    uri: make_uri_string(&link),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    fn check_link(
+        &mut self,
+        src: &Path,
+        link: &Link<'_>,
+    ) -> Option<BrokenLinkReason> {
+        let target = match LinkTarget::resolve(self.wikis, src, link) {
+            Ok(Some(target)) => target,
+            Ok(None) => return None,
+            Err(x) => return Some(BrokenLinkReason::Unresolved(x)),
+        };
+
+        let path = target.path;
+        match target.kind {
+            LinkTargetKind::Page
+                if !self.pages.contains_key(&path) && !path.is_file() =>
+            {
+                return Some(BrokenLinkReason::MissingPage { path })
+            }
+            LinkTargetKind::Directory if !path.is_dir() => {
+                return Some(BrokenLinkReason::MissingDirectory { path })
+            }
+            LinkTargetKind::File if !path.exists() => {
+                return Some(BrokenLinkReason::MissingFile { path })
+            }
+            _ => {}
+        }
+
+        // Anchors are only meaningful for pages, and we skip checking them
+        // when a page exists but cannot be parsed
+        match (target.kind, target.anchor) {
+            (LinkTargetKind::Page, Some(anchor)) => {
+                let targets = self.load_anchor_targets(&path)?;
+                if is_anchor_within(&anchor, targets) {
+                    None
+                } else {
+                    Some(BrokenLinkReason::MissingAnchor { path, anchor })
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Loads the header text and tags of the page at the given path in
+    /// document order, reading the page from disk if it is not part of a
+    /// loaded wiki
+    fn load_anchor_targets(&mut self, path: &Path) -> Option<&[String]> {
+        if !self.targets.contains_key(path) {
+            let targets = match self.pages.get(path) {
+                Some(page) => Some(make_anchor_targets(page)),
+                None => std::fs::read_to_string(path).ok().and_then(|text| {
+                    Language::from_vimwiki_str(&text)
+                        .parse::<Page>()
+                        .ok()
+                        .map(|page| make_anchor_targets(&page))
+                }),
+            };
+            self.targets.insert(path.to_path_buf(), targets);
+        }
+
+        self.targets.get(path).and_then(Option::as_deref)
+    }
+}
+
+/// Produces the decoded uri of a link, using the date of diary links as
+/// their uri is otherwise empty
+fn make_uri_string(link: &Link<'_>) -> String {
+    match link {
+        Link::Diary { date, .. } => format!("diary:{}", date),
+        _ => link.data().to_decoded_uri_string(),
+    }
+}
+
+/// Produces the text of each header and each tag within a page in document
+/// order, representing everything an anchor can point to
+fn make_anchor_targets(page: &Page<'_>) -> Vec<String> {
+    let mut targets = Vec::new();
+    for element in collect_elements(page) {
+        match element.into_inner() {
+            Element::Block(BlockElement::Header(x)) => {
+                targets.push(x.to_string().trim().to_string());
+            }
+            Element::Inline(InlineElement::Tags(tags)) => {
+                targets.extend(tags.into_iter().map(|t| t.to_string()));
+            }
+            _ => {}
+        }
+    }
+    targets
+}
+
+/// Whether or not each part of the anchor can be found within the targets,
+/// where each part must appear after the part before it
+fn is_anchor_within(anchor: &Anchor<'_>, targets: &[String]) -> bool {
+    let mut remaining = targets;
+    for part in anchor.iter().map(Cow::as_ref) {
+        match remaining.iter().position(|t| t == part.trim()) {
+            Some(pos) => remaining = &remaining[pos + 1..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wiki::WikiPage;
+
+    fn make_wiki(path: &str, pages: Vec<(&str, &str)>) -> Wiki<'static> {
+        Wiki {
+            path: PathBuf::from(path),
+            pages: pages
+                .into_iter()
+                .map(|(path, text)| {
+                    WikiPage::new(
+                        path,
+                        Language::from_vimwiki_str(text)
+                            .parse::<Page>()
+                            .unwrap()
+                            .into_owned(),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn reasons(broken: Vec<BrokenLink>) -> Vec<(String, String)> {
+        broken
+            .into_iter()
+            .map(|b| (b.uri, b.reason.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn check_links_should_report_links_to_missing_pages() {
+        let wikis = vec![make_wiki(
+            "/wiki",
+            vec![
+                (
+                    "/wiki/index.wiki",
+                    "[[b]] [[missing]] [[https://example.com]]",
+                ),
+                ("/wiki/b.wiki", "[[index]]"),
+            ],
+        )];
+
+        let broken = check_links(&wikis);
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].path, Path::new("/wiki/index.wiki"));
+        assert_eq!(broken[0].kind, LinkKind::Wiki);
+        assert_eq!(broken[0].region, Region::new_at_depth(6, 11, 1));
+        assert_eq!(
+            broken[0].reason,
+            BrokenLinkReason::MissingPage {
+                path: PathBuf::from("/wiki/missing.wiki")
+            }
+        );
+    }
+
+    #[test]
+    fn check_links_should_report_anchors_missing_from_target_page() {
+        let wikis = vec![make_wiki(
+            "/wiki",
+            vec![
+                (
+                    "/wiki/index.wiki",
+                    "[[b#One]] [[b#One#Two]] [[b#tag]] [[b#Two#One]] [[#Nope]]",
+                ),
+                ("/wiki/b.wiki", "= One =\n== Two ==\n:tag:\n"),
+            ],
+        )];
+
+        let broken = reasons(check_links(&wikis));
+        assert_eq!(
+            broken,
+            vec![
+                (
+                    "b#Two#One".to_string(),
+                    "No header or tag in \"/wiki/b.wiki\" matches #Two#One"
+                        .to_string()
+                ),
+                (
+                    "#Nope".to_string(),
+                    "No header or tag in \"/wiki/index.wiki\" matches #Nope"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_links_should_report_links_that_cannot_be_resolved() {
+        let wikis = vec![make_wiki(
+            "/wiki",
+            vec![("/wiki/index.wiki", "[[wn.other:page]] [[wiki3:page]]")],
+        )];
+
+        let broken = check_links(&wikis);
+        assert_eq!(broken.len(), 2);
+        assert!(broken
+            .iter()
+            .all(|b| b.kind.is_named_inter_wiki()
+                || b.kind.is_indexed_inter_wiki()));
+        assert_eq!(
+            broken[0].reason,
+            BrokenLinkReason::Unresolved(
+                LinkTargetError::MissingWikiWithName {
+                    name: "other".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            broken[1].reason,
+            BrokenLinkReason::Unresolved(
+                LinkTargetError::MissingWikiWithIndex { index: 3 }
+            )
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::lang::elements::Region;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Represents a replacement of the text found within some region of a file
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// Region of the original text to be replaced
+    pub region: Region,
+
+    /// Text to put in place of the region
+    pub new_text: String,
+}
+
+impl TextEdit {
+    pub fn new(region: Region, new_text: impl Into<String>) -> Self {
+        Self {
+            region,
+            new_text: new_text.into(),
+        }
+    }
+}
+
+/// Represents a collection of edits to apply to a single file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEdits {
+    /// Path to the file being edited
+    pub path: PathBuf,
+
+    /// Edits to apply to the file, ordered by their position in the file
+    /// and never overlapping
+    pub edits: Vec<TextEdit>,
+}
+
+impl FileEdits {
+    /// Applies the edits to the provided text, producing the updated text
+    ///
+    /// Assumes that the text is the same as what was used to determine the
+    /// regions of the edits
+    pub fn apply(&self, text: &str) -> String {
+        let mut edits = self.edits.iter().collect::<Vec<&TextEdit>>();
+        edits.sort_by_key(|e| e.region.offset());
+
+        let mut out = String::with_capacity(text.len());
+        let mut pos = 0;
+        for edit in edits {
+            let start = edit.region.offset();
+            let end = start + edit.region.len();
+            out.push_str(&text[pos..start]);
+            out.push_str(&edit.new_text);
+            pos = end;
+        }
+        out.push_str(&text[pos..]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_should_replace_each_region_with_new_text() {
+        let edits = FileEdits {
+            path: PathBuf::new(),
+            edits: vec![
+                TextEdit::new(Region::new(10, 5), "3rd"),
+                TextEdit::new(Region::new(0, 5), "1st"),
+            ],
+        };
+
+        assert_eq!(edits.apply("first and third"), "1st and 3rd");
+    }
+}
@@ -0,0 +1,132 @@
+use super::{make_header, make_list_item, make_wiki_link, page_caption};
+use crate::{
+    lang::elements::{BlockElement, List, Located, Page},
+    utils::normalize_path,
+    wiki::{rename::diary_date, Wiki, WikiPage},
+};
+use chrono::{Datelike, NaiveDate};
+use std::{cmp::Reverse, collections::BTreeMap};
+
+/// Diary pages of a wiki grouped by year and then by month
+type DiaryEntries<'a, 'b> =
+    BTreeMap<i32, BTreeMap<u32, Vec<(NaiveDate, &'a WikiPage<'b>)>>>;
+
+/// Builds a section beginning with a top-level header containing the
+/// provided name that indexes every page within the diary of a wiki, newest
+/// first, where each year has a second-level header and each month within
+/// the year has a third-level header followed by a list of links to the
+/// pages of that month
+///
+/// Links are relative to the diary's index page. If specified, each link
+/// uses the text of the first header of its page as its description
+pub fn make_diary_index(
+    wiki: &Wiki<'_>,
+    name: &str,
+    captions: bool,
+) -> Page<'static> {
+    let root = normalize_path(&wiki.path);
+    let mut entries: DiaryEntries = BTreeMap::new();
+    for page in wiki.pages.iter() {
+        let path = normalize_path(&page.path);
+        let date = path
+            .strip_prefix(&root)
+            .ok()
+            .and_then(|path| diary_date(wiki, path));
+        if let Some(date) = date {
+            entries
+                .entry(date.year())
+                .or_default()
+                .entry(date.month())
+                .or_default()
+                .push((date, page));
+        }
+    }
+
+    let mut elements = vec![make_header(name, 1)];
+    for (year, months) in entries.into_iter().rev() {
+        elements.push(make_header(&year.to_string(), 2));
+
+        for (_, mut pages) in months.into_iter().rev() {
+            pages.sort_by_key(|(date, _)| Reverse(*date));
+            elements.push(make_header(&pages[0].0.format("%B").to_string(), 3));
+
+            let items = pages
+                .into_iter()
+                .enumerate()
+                .map(|(pos, (date, page))| {
+                    let description = if captions {
+                        page_caption(&page.page)
+                    } else {
+                        None
+                    };
+                    let uri = date.format("%Y-%m-%d").to_string();
+                    make_list_item(pos, make_wiki_link(&uri, description), None)
+                })
+                .collect();
+            elements.push(Located::from(BlockElement::List(List::new(items))));
+        }
+    }
+
+    Page::new(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, ToVimwikiString, VimwikiConfig};
+    use indoc::indoc;
+    use std::path::PathBuf;
+
+    #[test]
+    fn make_diary_index_should_group_pages_by_year_and_month() {
+        let wiki = Wiki {
+            path: PathBuf::from("/wiki"),
+            pages: vec![
+                ("/wiki/index.wiki", ""),
+                ("/wiki/diary/diary.wiki", ""),
+                ("/wiki/diary/2020-12-31.wiki", ""),
+                ("/wiki/diary/2021-01-05.wiki", "= Some Day =\n"),
+                ("/wiki/diary/2021-01-10.wiki", ""),
+                ("/wiki/diary/2021-03-01.wiki", ""),
+                ("/wiki/other/2021-03-02.wiki", ""),
+            ]
+            .into_iter()
+            .map(|(path, text)| {
+                WikiPage::new(
+                    path,
+                    Language::from_vimwiki_str(text)
+                        .parse::<Page>()
+                        .unwrap()
+                        .into_owned(),
+                )
+            })
+            .collect(),
+            ..Default::default()
+        };
+
+        let page = make_diary_index(&wiki, "Diary", true);
+        assert_eq!(
+            page.to_vimwiki_string(VimwikiConfig::default()).unwrap(),
+            indoc! {"
+                = Diary =
+
+                == 2021 ==
+
+                === March ===
+
+                - [[2021-03-01]]
+
+                === January ===
+
+                - [[2021-01-10]]
+                - [[2021-01-05|Some Day]]
+
+                == 2020 ==
+
+                === December ===
+
+                - [[2020-12-31]]
+            "}
+        );
+    }
+}
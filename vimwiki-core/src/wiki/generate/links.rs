@@ -0,0 +1,232 @@
+use super::{make_header, make_list_item, make_wiki_link, page_caption};
+use crate::{
+    lang::elements::{BlockElement, List, Located, Page},
+    utils::{normalize_path, path_to_uri_string},
+    wiki::{
+        rename::{relative_path, strip_ext},
+        TagIndex, Wiki,
+    },
+};
+use std::path::Path;
+
+/// Builds a section beginning with a top-level header containing the
+/// provided name followed by a list of links to every page within the wiki,
+/// ordered by path and excluding the page at the given path
+///
+/// Links are relative to the directory of the page at the given path. If
+/// specified, each link uses the text of the first header of its page as
+/// its description
+pub fn make_links_index(
+    wiki: &Wiki<'_>,
+    path: &Path,
+    name: &str,
+    captions: bool,
+) -> Page<'static> {
+    let path = normalize_path(path);
+    let mut pages: Vec<_> = wiki
+        .pages
+        .iter()
+        .filter(|p| normalize_path(&p.path) != path)
+        .collect();
+    pages.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let items = pages
+        .into_iter()
+        .enumerate()
+        .map(|(pos, p)| {
+            let uri = make_page_uri(wiki, &path, &p.path);
+            let description = if captions {
+                page_caption(&p.page)
+            } else {
+                None
+            };
+            make_list_item(pos, make_wiki_link(&uri, description), None)
+        })
+        .collect();
+
+    Page::new(vec![
+        make_header(name, 1),
+        Located::from(BlockElement::List(List::new(items))),
+    ])
+}
+
+/// Builds a section beginning with a top-level header containing the
+/// provided name followed by a second-level header for each tag within the
+/// index, where each tag header is followed by a list of links to the
+/// locations of the tag within the wiki
+///
+/// Links are relative to the directory of the page at the given path and
+/// use the tag as their anchor. Locations outside of the wiki are excluded
+pub fn make_tag_links(
+    wiki: &Wiki<'_>,
+    index: &TagIndex,
+    path: &Path,
+    name: &str,
+) -> Page<'static> {
+    let path = normalize_path(path);
+    let mut elements = vec![make_header(name, 1)];
+
+    for (tag, locations) in index.iter() {
+        let items: Vec<_> = locations
+            .iter()
+            .filter(|loc| wiki.contains_path(&loc.path))
+            .enumerate()
+            .map(|(pos, loc)| {
+                let uri = make_page_uri(wiki, &path, &loc.path);
+                let description = match loc.header.as_ref() {
+                    Some(header) => {
+                        format!("{}#{}", uri, header.to_string().trim())
+                    }
+                    None => uri.to_string(),
+                };
+                let link = make_wiki_link(
+                    &format!("{}#{}", uri, tag),
+                    Some(description),
+                );
+                make_list_item(pos, link, None)
+            })
+            .collect();
+
+        if !items.is_empty() {
+            elements.push(make_header(tag.as_str(), 2));
+            elements.push(Located::from(BlockElement::List(List::new(items))));
+        }
+    }
+
+    Page::new(elements)
+}
+
+/// Produces the uri of a page within a wiki relative to the directory of
+/// another page, omitting the extension of the wiki
+fn make_page_uri(wiki: &Wiki<'_>, src: &Path, target: &Path) -> String {
+    let src_dir = src.parent().unwrap_or(src);
+    let target = strip_ext(&normalize_path(target), &wiki.ext);
+    path_to_uri_string(&relative_path(src_dir, &target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, ToVimwikiString, VimwikiConfig, WikiPage};
+    use indoc::indoc;
+    use std::path::PathBuf;
+
+    fn make_wiki(pages: Vec<(&str, &str)>) -> Wiki<'static> {
+        Wiki {
+            path: PathBuf::from("/wiki"),
+            pages: pages
+                .into_iter()
+                .map(|(path, text)| {
+                    WikiPage::new(
+                        path,
+                        Language::from_vimwiki_str(text)
+                            .parse::<Page>()
+                            .unwrap()
+                            .into_owned(),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn make_links_index_should_link_to_every_other_page() {
+        let wiki = make_wiki(vec![
+            ("/wiki/index.wiki", ""),
+            ("/wiki/sub/b.wiki", "= Page B =\n"),
+            ("/wiki/a.wiki", "text\n"),
+        ]);
+
+        let page = make_links_index(
+            &wiki,
+            Path::new("/wiki/index.wiki"),
+            "Generated Links",
+            false,
+        );
+        assert_eq!(
+            page.to_vimwiki_string(VimwikiConfig::default()).unwrap(),
+            indoc! {"
+                = Generated Links =
+
+                - [[a]]
+                - [[sub/b]]
+            "}
+        );
+
+        let page = make_links_index(
+            &wiki,
+            Path::new("/wiki/sub/b.wiki"),
+            "Generated Links",
+            true,
+        );
+        assert_eq!(
+            page.to_vimwiki_string(VimwikiConfig::default()).unwrap(),
+            indoc! {"
+                = Generated Links =
+
+                - [[../a]]
+                - [[../index]]
+            "}
+        );
+    }
+
+    #[test]
+    fn make_links_index_should_use_first_header_as_caption_if_specified() {
+        let wiki = make_wiki(vec![
+            ("/wiki/index.wiki", ""),
+            ("/wiki/b.wiki", "text\n= Page B =\n== Sub ==\n"),
+        ]);
+
+        let page = make_links_index(
+            &wiki,
+            Path::new("/wiki/index.wiki"),
+            "Generated Links",
+            true,
+        );
+        assert_eq!(
+            page.to_vimwiki_string(VimwikiConfig::default()).unwrap(),
+            indoc! {"
+                = Generated Links =
+
+                - [[b|Page B]]
+            "}
+        );
+    }
+
+    #[test]
+    fn make_tag_links_should_group_links_to_locations_by_tag() {
+        let wiki = make_wiki(vec![
+            ("/wiki/index.wiki", ""),
+            ("/wiki/a.wiki", ":one:\n= Header =\n:two:\n"),
+            ("/wiki/sub/b.wiki", ":two:\n"),
+        ]);
+        let mut index = TagIndex::from_wikis(&[wiki.to_borrowed()]);
+        index.update_page(
+            Path::new("/other/c.wiki"),
+            &Language::from_vimwiki_str(":one:\n").parse().unwrap(),
+        );
+
+        let page = make_tag_links(
+            &wiki,
+            &index,
+            Path::new("/wiki/index.wiki"),
+            "Generated Tags",
+        );
+        assert_eq!(
+            page.to_vimwiki_string(VimwikiConfig::default()).unwrap(),
+            indoc! {"
+                = Generated Tags =
+
+                == one ==
+
+                - [[a#one|a]]
+
+                == two ==
+
+                - [[a#two|a#Header]]
+                - [[sub/b#two|sub/b]]
+            "}
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use super::TextEdit;
+use crate::lang::{
+    elements::{
+        BlockElement, Description, Header, InlineElement,
+        InlineElementContainer, Link, LinkData, List, ListItem,
+        ListItemContents, Located, Page, Paragraph, Region, Text,
+    },
+    output::{ToVimwikiString, VimwikiConfig, VimwikiOutputError},
+};
+use derive_more::IsVariant;
+use serde::{Deserialize, Serialize};
+
+mod diary;
+pub use diary::make_diary_index;
+
+mod links;
+pub use links::{make_links_index, make_tag_links};
+
+mod toc;
+pub use toc::{make_toc, make_toc_list};
+
+/// Represents where a generated section is placed within a page that does
+/// not already contain the section
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Hash, IsVariant, Serialize, Deserialize,
+)]
+pub enum SectionPlacement {
+    /// Section is placed at the beginning of the page
+    Start,
+
+    /// Section is placed at the end of the page
+    End,
+}
+
+/// Finds the region of the section within a page that would be generated
+/// from the provided fragment, returning none if the page does not contain
+/// the section or the fragment does not begin with a header
+///
+/// The header that begins the fragment has its level and text used to find
+/// the existing section within the page. An existing section spans from its
+/// header up to the next header of any level, unless the fragment contains
+/// headers of its own, in which case the section spans up to the next header
+/// of the same or higher level. Without a next header, the section spans to
+/// the end of the text
+pub fn find_section(
+    text: &str,
+    page: &Page<'_>,
+    fragment: &Page<'_>,
+) -> Option<Region> {
+    let header = match fragment.elements.first().map(Located::as_inner) {
+        Some(BlockElement::Header(x)) => x,
+        _ => return None,
+    };
+    let has_subheaders = fragment
+        .elements
+        .iter()
+        .skip(1)
+        .any(|e| matches!(e.as_inner(), BlockElement::Header(_)));
+
+    let headers = page_headers(page);
+    let idx = headers.iter().position(|h| is_same_header(h, header))?;
+    let start = headers[idx].region().offset();
+    let end = headers[idx + 1..]
+        .iter()
+        .find(|h| !has_subheaders || h.level <= header.level)
+        .map_or(text.len(), |h| h.region().offset());
+
+    Some(Region::new(start, end - start))
+}
+
+/// Produces an edit that replaces the section of a page generated from the
+/// provided fragment (see [`find_section`]), or inserts the fragment based on
+/// the placement if the page does not already contain the section
+///
+/// Returns none if the fragment does not begin with a header
+pub fn make_section_edit(
+    text: &str,
+    page: &Page<'_>,
+    fragment: &Page<'_>,
+    placement: SectionPlacement,
+    config: VimwikiConfig,
+) -> Result<Option<TextEdit>, VimwikiOutputError> {
+    if !matches!(
+        fragment.elements.first().map(Located::as_inner),
+        Some(BlockElement::Header(_))
+    ) {
+        return Ok(None);
+    }
+
+    let new_text = fragment.to_vimwiki_string(config)?;
+
+    // If replacing an existing section that is followed by more content, we
+    // keep a blank line between the section and the content that follows
+    if let Some(region) = find_section(text, page, fragment) {
+        let end = region.offset() + region.len();
+        return Ok(Some(if end < text.len() {
+            TextEdit::new(region, format!("{}\n", new_text))
+        } else {
+            TextEdit::new(region, new_text)
+        }));
+    }
+
+    Ok(Some(match placement {
+        SectionPlacement::Start if text.is_empty() => {
+            TextEdit::new(Region::new(0, 0), new_text)
+        }
+        SectionPlacement::Start => {
+            TextEdit::new(Region::new(0, 0), format!("{}\n", new_text))
+        }
+        SectionPlacement::End => {
+            let sep = if text.is_empty() || text.ends_with("\n\n") {
+                ""
+            } else if text.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            TextEdit::new(
+                Region::new(text.len(), 0),
+                format!("{}{}", sep, new_text),
+            )
+        }
+    }))
+}
+
+/// Returns the text of the first header within a page, used as a caption
+/// when linking to the page
+fn page_caption(page: &Page<'_>) -> Option<String> {
+    page.elements.iter().find_map(|e| match e.as_inner() {
+        BlockElement::Header(x) => Some(x.to_string().trim().to_string()),
+        _ => None,
+    })
+}
+
+/// Produces a header block element containing the provided text
+fn make_header(text: &str, level: usize) -> Located<BlockElement<'static>> {
+    Located::from(BlockElement::Header(Header::new(
+        InlineElementContainer::new(vec![Located::from(InlineElement::Text(
+            Text::from(text.to_string()),
+        ))]),
+        level,
+        false,
+    )))
+}
+
+/// Produces a wiki link to the provided uri, which is encoded prior to
+/// being parsed
+fn make_wiki_link(uri: &str, description: Option<String>) -> Link<'static> {
+    Link::try_new_wiki_link(
+        LinkData::encode_uri(uri).as_str(),
+        description.map(Description::from),
+    )
+    .map(Link::into_owned)
+    .expect("Impossible: encoded uri should always be valid")
+}
+
+/// Produces a list item containing a link and optionally a sublist
+fn make_list_item(
+    pos: usize,
+    link: Link<'static>,
+    sublist: Option<List<'static>>,
+) -> Located<ListItem<'static>> {
+    let mut contents = vec![Located::from(BlockElement::Paragraph(
+        Paragraph::new(vec![InlineElementContainer::new(vec![Located::from(
+            InlineElement::Link(link),
+        )])]),
+    ))];
+    if let Some(sublist) = sublist {
+        contents.push(Located::from(BlockElement::List(sublist)));
+    }
+
+    Located::from(ListItem {
+        pos,
+        contents: ListItemContents::new(contents),
+        ..Default::default()
+    })
+}
+
+/// Collects the top-level headers of a page in document order
+fn page_headers<'a>(page: &'a Page<'_>) -> Vec<Located<&'a Header<'a>>> {
+    page.elements
+        .iter()
+        .filter_map(|e| match e.as_inner() {
+            BlockElement::Header(x) => Some(Located::new(x, e.region())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns true if the headers share the same level and text
+fn is_same_header(a: &Header<'_>, b: &Header<'_>) -> bool {
+    a.level == b.level && a.to_string().trim() == b.to_string().trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn parse(text: &str) -> Page<'_> {
+        Language::from_vimwiki_str(text).parse::<Page>().unwrap()
+    }
+
+    fn apply(
+        text: &str,
+        fragment: &str,
+        placement: SectionPlacement,
+    ) -> String {
+        let page = parse(text);
+        let fragment = parse(fragment);
+        let edit = make_section_edit(
+            text,
+            &page,
+            &fragment,
+            placement,
+            VimwikiConfig::default(),
+        )
+        .unwrap()
+        .unwrap();
+
+        super::super::FileEdits {
+            path: Default::default(),
+            edits: vec![edit],
+        }
+        .apply(text)
+    }
+
+    #[test]
+    fn make_section_edit_should_replace_existing_section_in_place() {
+        let text = "intro\n\n= Section =\n- old\n\n= Next =\ntext\n";
+        assert_eq!(
+            apply(text, "= Section =\n- new\n", SectionPlacement::Start),
+            "intro\n\n= Section =\n\n- new\n\n= Next =\ntext\n"
+        );
+    }
+
+    #[test]
+    fn make_section_edit_should_replace_through_end_of_page() {
+        let text = "= Section =\n- old\n- older\n";
+        assert_eq!(
+            apply(text, "= Section =\n- new\n", SectionPlacement::End),
+            "= Section =\n\n- new\n"
+        );
+    }
+
+    #[test]
+    fn make_section_edit_should_include_subheaders_if_fragment_has_them() {
+        let text = "= Section =\n== Old ==\n- old\n\n= Next =\n";
+        assert_eq!(
+            apply(
+                text,
+                "= Section =\n== New ==\n- new\n",
+                SectionPlacement::End
+            ),
+            "= Section =\n\n== New ==\n\n- new\n\n= Next =\n"
+        );
+    }
+
+    #[test]
+    fn make_section_edit_should_insert_missing_section_based_on_placement() {
+        let text = "= Other =\ntext\n";
+        assert_eq!(
+            apply(text, "= Section =\n- new\n", SectionPlacement::Start),
+            "= Section =\n\n- new\n\n= Other =\ntext\n"
+        );
+        assert_eq!(
+            apply(text, "= Section =\n- new\n", SectionPlacement::End),
+            "= Other =\ntext\n\n= Section =\n\n- new\n"
+        );
+    }
+}
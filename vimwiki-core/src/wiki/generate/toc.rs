@@ -0,0 +1,138 @@
+use super::{make_header, make_list_item, make_wiki_link};
+use crate::lang::elements::{BlockElement, List, Located, Page};
+
+/// Builds a table of contents for a page as a section beginning with a
+/// top-level header containing the provided name followed by a list of links
+/// to each header within the page
+///
+/// The name should match the table of contents name used by the wiki, which
+/// is used to exclude an existing table of contents from itself
+pub fn make_toc(page: &Page<'_>, name: &str) -> Page<'static> {
+    Page::new(vec![
+        make_header(name, 1),
+        Located::from(BlockElement::List(make_toc_list(page, name))),
+    ])
+}
+
+/// Builds a list of links to each header within a page, where headers are
+/// nested beneath the closest preceding header of a lower level
+///
+/// Each link uses the complete anchor of its header, comprised of the text
+/// of the header and each of the headers it is nested beneath, with the
+/// header's text as the description. The top-level header whose text matches
+/// the provided name is treated as the table of contents and is excluded
+pub fn make_toc_list(page: &Page<'_>, name: &str) -> List<'static> {
+    let headers: Vec<(usize, String)> = page
+        .elements
+        .iter()
+        .filter_map(|e| match e.as_inner() {
+            BlockElement::Header(x) => Some((x.level, x.to_string())),
+            _ => None,
+        })
+        .map(|(level, text)| (level, text.trim().to_string()))
+        .filter(|(level, text)| *level != 1 || text != name.trim())
+        .collect();
+
+    make_list(&headers, &mut Vec::new())
+}
+
+/// Builds a list from a series of headers, where each header captures all
+/// subsequent headers with a higher level as its children
+fn make_list(
+    headers: &[(usize, String)],
+    anchors: &mut Vec<String>,
+) -> List<'static> {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < headers.len() {
+        let (level, text) = &headers[i];
+        let children_cnt = headers[i + 1..]
+            .iter()
+            .take_while(|(l, _)| l > level)
+            .count();
+
+        anchors.push(text.to_string());
+        let link = make_wiki_link(
+            &format!("#{}", anchors.join("#")),
+            Some(text.to_string()),
+        );
+        let sublist = if children_cnt > 0 {
+            Some(make_list(&headers[i + 1..=i + children_cnt], anchors))
+        } else {
+            None
+        };
+        anchors.pop();
+
+        items.push(make_list_item(items.len(), link, sublist));
+        i += children_cnt + 1;
+    }
+
+    List::new(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        InlineElement, Language, Link, ToVimwikiString, VimwikiConfig,
+    };
+    use indoc::indoc;
+
+    fn parse(text: &str) -> Page<'_> {
+        Language::from_vimwiki_str(text).parse::<Page>().unwrap()
+    }
+
+    #[test]
+    fn make_toc_should_nest_links_to_headers_by_level() {
+        let page = parse(indoc! {"
+            = Contents =
+            - [[#Old|Old]]
+
+            = One =
+            == Two ==
+            === Three ===
+            == Four ==
+            = Five =
+        "});
+
+        let toc = make_toc(&page, "Contents")
+            .to_vimwiki_string(VimwikiConfig::default())
+            .unwrap();
+        assert_eq!(
+            toc,
+            indoc! {"
+                = Contents =
+
+                - [[#One|One]]
+                    - [[#One#Two|Two]]
+                        - [[#One#Two#Three|Three]]
+                    - [[#One#Four|Four]]
+                - [[#Five|Five]]
+            "}
+        );
+    }
+
+    #[test]
+    fn make_toc_list_should_produce_same_links_as_parsed_wiki_links() {
+        fn first_link<'a>(list: &'a List<'a>) -> &'a Link<'a> {
+            match list.items[0].contents[0].as_inner() {
+                BlockElement::Paragraph(x) => match x.lines[0][0].as_inner() {
+                    InlineElement::Link(x) => x,
+                    x => panic!("Unexpected element: {:?}", x),
+                },
+                x => panic!("Unexpected element: {:?}", x),
+            }
+        }
+
+        let page = parse("= Some Header =\n");
+        let list = make_toc_list(&page, "Contents");
+
+        let expected = parse("- [[#Some Header|Some Header]]\n");
+        let expected = match expected.elements[0].as_inner() {
+            BlockElement::List(x) => x,
+            x => panic!("Unexpected element: {:?}", x),
+        };
+
+        assert_eq!(first_link(&list), first_link(expected));
+    }
+}
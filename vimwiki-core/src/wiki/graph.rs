@@ -0,0 +1,344 @@
+use super::{collect_links, LinkTarget, Wiki};
+use crate::{
+    lang::elements::{Anchor, Description, Page, Region},
+    utils::normalize_path,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+/// Represents a link from one page to another within a [`WikiGraph`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    /// Path to the page containing the link
+    pub src: PathBuf,
+
+    /// Path to the page targeted by the link
+    pub dst: PathBuf,
+
+    /// Region within the source page covered by the link
+    pub region: Region,
+
+    /// Decoded anchor within the targeted page, if the link has one
+    pub anchor: Option<Anchor<'static>>,
+
+    /// Description of the link, if it has one
+    pub description: Option<Description<'static>>,
+}
+
+/// Represents the links between pages of one or more wikis, where each page
+/// is a node and each link that resolves to a page is an edge
+///
+/// Links to remote resources, arbitrary files, and directories are not
+/// included. Edges may point to pages that are not nodes of the graph, which
+/// is the case for links to pages that do not exist
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WikiGraph {
+    /// Outgoing edges of each page, ordered by position within the page
+    outgoing: BTreeMap<PathBuf, Vec<GraphEdge>>,
+
+    /// Sources of the edges targeting each page, which may include pages
+    /// that are not nodes of the graph
+    incoming: BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+}
+
+impl WikiGraph {
+    /// Creates a new, empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a graph from every page within the provided wikis
+    pub fn from_wikis(wikis: &[Wiki<'_>]) -> Self {
+        let mut graph = Self::new();
+        for page in wikis.iter().flat_map(|w| w.pages.iter()) {
+            graph.update_page(wikis, &page.path, &page.page);
+        }
+        graph
+    }
+
+    /// Returns true if the graph contains no pages
+    pub fn is_empty(&self) -> bool {
+        self.outgoing.is_empty()
+    }
+
+    /// Returns the total number of pages within the graph
+    pub fn len(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Iterates through the path of each page within the graph in sorted
+    /// order
+    pub fn pages(&self) -> impl Iterator<Item = &Path> {
+        self.outgoing.keys().map(PathBuf::as_path)
+    }
+
+    /// Returns true if the page at the given path is within the graph
+    pub fn contains_page(&self, path: &Path) -> bool {
+        self.outgoing.contains_key(&normalize_path(path))
+    }
+
+    /// Returns the links found within the page at the given path, or an
+    /// empty slice if the page is not within the graph
+    pub fn outgoing(&self, path: &Path) -> &[GraphEdge] {
+        self.outgoing
+            .get(&normalize_path(path))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the links from other pages that target the page at the
+    /// given path, ordered by the path of the page containing the link
+    pub fn backlinks(&self, path: &Path) -> Vec<&GraphEdge> {
+        let path = normalize_path(path);
+        self.incoming
+            .get(&path)
+            .into_iter()
+            .flatten()
+            .filter(|src| **src != path)
+            .flat_map(|src| self.outgoing(src).iter())
+            .filter(|edge| edge.dst == path)
+            .collect()
+    }
+
+    /// Returns the pages that are not targeted by links from any other page
+    pub fn orphans(&self) -> Vec<&Path> {
+        self.pages()
+            .filter(|path| {
+                self.incoming
+                    .get(*path)
+                    .into_iter()
+                    .flatten()
+                    .all(|src| src == path)
+            })
+            .collect()
+    }
+
+    /// Returns the pages that do not link to any other page within the graph
+    pub fn dead_ends(&self) -> Vec<&Path> {
+        self.outgoing
+            .iter()
+            .filter(|(path, edges)| {
+                !edges.iter().any(|edge| {
+                    edge.dst != **path && self.outgoing.contains_key(&edge.dst)
+                })
+            })
+            .map(|(path, _)| path.as_path())
+            .collect()
+    }
+
+    /// Finds the shortest sequence of pages, starting with `from` and ending
+    /// with `to`, where each page links to the page after it, returning
+    /// none if `to` cannot be reached from `from`
+    pub fn shortest_path(
+        &self,
+        from: &Path,
+        to: &Path,
+    ) -> Option<Vec<PathBuf>> {
+        let from = normalize_path(from);
+        let to = normalize_path(to);
+        if !self.outgoing.contains_key(&from) {
+            return None;
+        }
+
+        let mut prev: HashMap<&Path, &Path> = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(from.as_path());
+
+        while let Some(path) = queue.pop_front() {
+            if path == to {
+                let mut pages = vec![path.to_path_buf()];
+                let mut path = path;
+                while let Some(p) = prev.get(path) {
+                    pages.push(p.to_path_buf());
+                    path = p;
+                }
+                pages.reverse();
+                return Some(pages);
+            }
+
+            for edge in self.outgoing(path) {
+                let dst = edge.dst.as_path();
+                if dst != from && !prev.contains_key(dst) {
+                    prev.insert(dst, path);
+                    queue.push_back(dst);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Replaces the node and outgoing edges of the page at the given path
+    /// with those of the provided page, resolving links against the wikis
+    pub fn update_page(
+        &mut self,
+        wikis: &[Wiki<'_>],
+        path: &Path,
+        page: &Page<'_>,
+    ) {
+        let path = normalize_path(path);
+        self.remove_edges(&path);
+
+        let edges: Vec<GraphEdge> = collect_links(page)
+            .into_iter()
+            .filter_map(|link| {
+                let region = link.region();
+                let link = link.into_inner();
+                let target =
+                    LinkTarget::resolve(wikis, &path, &link).ok()??;
+                if !target.kind.is_page() {
+                    return None;
+                }
+
+                Some(GraphEdge {
+                    src: path.to_path_buf(),
+                    dst: target.path,
+                    region,
+                    anchor: target.anchor,
+                    description: link
+                        .into_description()
+                        .map(Description::into_owned),
+                })
+            })
+            .collect();
+
+        for edge in edges.iter() {
+            self.incoming
+                .entry(edge.dst.to_path_buf())
+                .or_default()
+                .insert(path.to_path_buf());
+        }
+        self.outgoing.insert(path, edges);
+    }
+
+    /// Removes the page at the given path and its outgoing edges, leaving
+    /// edges from other pages that target it in place
+    pub fn remove_page(&mut self, path: &Path) {
+        let path = normalize_path(path);
+        self.remove_edges(&path);
+        self.outgoing.remove(&path);
+    }
+
+    fn remove_edges(&mut self, path: &Path) {
+        for edge in self.outgoing.get(path).into_iter().flatten() {
+            if let Some(srcs) = self.incoming.get_mut(&edge.dst) {
+                srcs.remove(path);
+                if srcs.is_empty() {
+                    self.incoming.remove(&edge.dst);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wiki::WikiPage, Language};
+
+    fn parse(text: &str) -> Page<'static> {
+        Language::from_vimwiki_str(text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned()
+    }
+
+    fn make_wikis(pages: Vec<(&str, &str)>) -> Vec<Wiki<'static>> {
+        vec![Wiki {
+            path: PathBuf::from("/wiki"),
+            pages: pages
+                .into_iter()
+                .map(|(path, text)| WikiPage::new(path, parse(text)))
+                .collect(),
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn backlinks_should_include_links_from_other_pages() {
+        let wikis = make_wikis(vec![
+            ("/wiki/a.wiki", "[[b#Header|to b]] [[a]]"),
+            ("/wiki/b.wiki", "[[#Header]]"),
+            ("/wiki/c.wiki", "[[b]] [[https://example.com]]"),
+        ]);
+        let graph = WikiGraph::from_wikis(&wikis);
+
+        let backlinks = graph.backlinks(Path::new("/wiki/b.wiki"));
+        let srcs: Vec<&Path> =
+            backlinks.iter().map(|e| e.src.as_path()).collect();
+        assert_eq!(
+            srcs,
+            vec![Path::new("/wiki/a.wiki"), Path::new("/wiki/c.wiki")]
+        );
+        assert_eq!(backlinks[0].anchor, Some(Anchor::from("Header")));
+        assert_eq!(backlinks[0].description, Some(Description::from("to b")));
+        assert_eq!(graph.outgoing(Path::new("/wiki/c.wiki")).len(), 1);
+    }
+
+    #[test]
+    fn orphans_and_dead_ends_should_ignore_links_to_self() {
+        let wikis = make_wikis(vec![
+            ("/wiki/a.wiki", "[[b]] [[missing]]"),
+            ("/wiki/b.wiki", "[[b]]"),
+            ("/wiki/c.wiki", "[[c]] [[a]]"),
+        ]);
+        let graph = WikiGraph::from_wikis(&wikis);
+
+        assert_eq!(graph.orphans(), vec![Path::new("/wiki/c.wiki")]);
+        assert_eq!(graph.dead_ends(), vec![Path::new("/wiki/b.wiki")]);
+    }
+
+    #[test]
+    fn shortest_path_should_follow_fewest_links() {
+        let wikis = make_wikis(vec![
+            ("/wiki/a.wiki", "[[b]] [[c]]"),
+            ("/wiki/b.wiki", "[[c]]"),
+            ("/wiki/c.wiki", "[[d]]"),
+            ("/wiki/d.wiki", ""),
+        ]);
+        let graph = WikiGraph::from_wikis(&wikis);
+
+        assert_eq!(
+            graph.shortest_path(
+                Path::new("/wiki/a.wiki"),
+                Path::new("/wiki/d.wiki")
+            ),
+            Some(vec![
+                PathBuf::from("/wiki/a.wiki"),
+                PathBuf::from("/wiki/c.wiki"),
+                PathBuf::from("/wiki/d.wiki"),
+            ])
+        );
+        assert_eq!(
+            graph.shortest_path(
+                Path::new("/wiki/d.wiki"),
+                Path::new("/wiki/a.wiki")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn update_page_should_replace_edges_of_page() {
+        let mut wikis =
+            make_wikis(vec![("/wiki/a.wiki", "[[b]]"), ("/wiki/b.wiki", "")]);
+        let mut graph = WikiGraph::from_wikis(&wikis);
+        assert_eq!(graph.backlinks(Path::new("/wiki/b.wiki")).len(), 1);
+
+        wikis[0].pages[0].page = parse("[[c]]");
+        graph.update_page(
+            &wikis,
+            Path::new("/wiki/a.wiki"),
+            &wikis[0].pages[0].page,
+        );
+        assert!(graph.backlinks(Path::new("/wiki/b.wiki")).is_empty());
+        assert_eq!(graph.backlinks(Path::new("/wiki/c.wiki")).len(), 1);
+
+        graph.remove_page(Path::new("/wiki/a.wiki"));
+        assert!(graph.backlinks(Path::new("/wiki/c.wiki")).is_empty());
+        assert!(!graph.contains_page(Path::new("/wiki/a.wiki")));
+    }
+}
@@ -0,0 +1,316 @@
+use super::{rename::diary_date, TextEdit, Wiki, WikiGraph, WikiPage};
+use crate::{lang::elements::Region, utils::normalize_path};
+use derive_more::{Display, IsVariant};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
+
+mod rules;
+pub use rules::*;
+
+/// Represents how serious the problem reported by a lint rule is
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    IsVariant,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[display(fmt = "hint")]
+    Hint,
+
+    #[display(fmt = "info")]
+    Info,
+
+    #[display(fmt = "warning")]
+    Warning,
+
+    #[display(fmt = "error")]
+    Error,
+}
+
+/// Represents configuration options for linting wikis
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Names of rules that will not be run
+    #[serde(default)]
+    pub disabled: Vec<String>,
+
+    /// Severity to report for a rule, by name, in place of the default
+    /// severity of the rule
+    #[serde(default)]
+    pub severity: HashMap<String, Severity>,
+}
+
+impl LintConfig {
+    /// Returns true if the rule with the given name is enabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.iter().any(|x| x == name)
+    }
+}
+
+/// Represents a single problem found by a lint rule within a page
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    /// Region within the page where the problem was found
+    pub region: Region,
+
+    /// Description of the problem
+    pub message: String,
+
+    /// Optional edit to the page that resolves the problem
+    pub fix: Option<TextEdit>,
+}
+
+impl Lint {
+    pub fn new(region: Region, message: impl Into<String>) -> Self {
+        Self {
+            region,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    /// Attaches an edit that resolves the problem
+    pub fn with_fix(mut self, fix: TextEdit) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// Represents a problem reported by a lint rule for a specific page
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Name of the rule that reported the problem
+    pub rule: String,
+
+    /// How serious the problem is
+    pub severity: Severity,
+
+    /// Path to the page containing the problem
+    pub path: PathBuf,
+
+    /// Region within the page where the problem was found
+    pub region: Region,
+
+    /// Description of the problem
+    pub message: String,
+
+    /// Optional edit to the page that resolves the problem
+    pub fix: Option<TextEdit>,
+}
+
+/// Represents the information available to a lint rule when checking a page
+pub struct LintContext<'a, 'b> {
+    /// All wikis being linted
+    pub wikis: &'a [Wiki<'b>],
+
+    /// Wiki containing the page being checked
+    pub wiki: &'a Wiki<'b>,
+
+    /// Page being checked
+    pub page: &'a WikiPage<'b>,
+
+    /// Links between all pages of the wikis being linted
+    pub graph: &'a WikiGraph,
+}
+
+impl<'a, 'b> LintContext<'a, 'b> {
+    /// Returns true if some other page links to the page being checked
+    pub fn has_backlinks(&self) -> bool {
+        !self.graph.backlinks(&self.page.path).is_empty()
+    }
+
+    /// Returns true if the page being checked is the index page of its wiki
+    pub fn is_index_page(&self) -> bool {
+        normalize_path(&self.page.path)
+            == normalize_path(
+                &self.wiki.path.join(format!("index.{}", self.wiki.ext)),
+            )
+    }
+
+    /// Returns true if the page being checked is a diary page of its wiki
+    pub fn is_diary_page(&self) -> bool {
+        normalize_path(&self.page.path)
+            .strip_prefix(normalize_path(&self.wiki.path))
+            .ok()
+            .and_then(|path| diary_date(self.wiki, path))
+            .is_some()
+    }
+}
+
+/// Represents a rule that checks pages for some kind of problem
+pub trait LintRule {
+    /// Unique name of the rule, used when configuring the rule
+    fn name(&self) -> &'static str;
+
+    /// Short explanation of what the rule checks
+    fn description(&self) -> &'static str;
+
+    /// Severity reported by the rule when not configured otherwise
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Checks the page provided by the context, returning each problem found
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint>;
+}
+
+/// Represents a collection of lint rules to run against wikis
+pub struct LintRegistry {
+    rules: Vec<Box<dyn LintRule>>,
+}
+
+impl Default for LintRegistry {
+    /// Creates a registry containing each of the builtin rules
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(OrphanPageRule);
+        registry.register(DuplicateHeaderRule);
+        registry.register(SkippedHeaderLevelRule);
+        registry.register(EmptyListItemRule);
+        registry.register(UndatedTodoRule);
+        registry.register(RaggedTableRule);
+        registry.register(UnusedPlaceholderRule);
+        registry
+    }
+}
+
+impl LintRegistry {
+    /// Creates a registry without any rules
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Adds a rule to the registry
+    pub fn register<R: LintRule + 'static>(&mut self, rule: R) {
+        self.rules.push(Box::new(rule));
+    }
+
+    /// Iterates through each rule within the registry
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(AsRef::as_ref)
+    }
+
+    /// Runs every enabled rule against every page within the provided wikis,
+    /// returning diagnostics ordered by page and then by position
+    pub fn lint(
+        &self,
+        wikis: &[Wiki<'_>],
+        config: &LintConfig,
+    ) -> Vec<Diagnostic> {
+        let graph = WikiGraph::from_wikis(wikis);
+        let mut diagnostics = Vec::new();
+
+        for wiki in wikis {
+            for page in wiki.pages.iter() {
+                let ctx = LintContext {
+                    wikis,
+                    wiki,
+                    page,
+                    graph: &graph,
+                };
+
+                let start = diagnostics.len();
+                for rule in self.rules().filter(|r| config.is_enabled(r.name()))
+                {
+                    let severity = config
+                        .severity
+                        .get(rule.name())
+                        .copied()
+                        .unwrap_or_else(|| rule.default_severity());
+                    diagnostics.extend(rule.check(&ctx).into_iter().map(
+                        |lint| Diagnostic {
+                            rule: rule.name().to_string(),
+                            severity,
+                            path: page.path.to_path_buf(),
+                            region: lint.region,
+                            message: lint.message,
+                            fix: lint.fix,
+                        },
+                    ));
+                }
+                diagnostics[start..].sort_by_key(|d| d.region.offset());
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Produces a region spanning from the start of the first region to the end
+/// of the last region
+fn span_regions(first: Region, last: Region) -> Region {
+    let end = last.offset() + last.len();
+    Region::new_at_depth(first.offset(), end - first.offset(), first.depth())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lang::elements::Page, Language};
+
+    fn make_wiki(pages: Vec<(&str, &str)>) -> Wiki<'static> {
+        Wiki {
+            path: PathBuf::from("/wiki"),
+            pages: pages
+                .into_iter()
+                .map(|(path, text)| {
+                    WikiPage::new(
+                        path,
+                        Language::from_vimwiki_str(text)
+                            .parse::<Page>()
+                            .unwrap()
+                            .into_owned(),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lint_should_report_orphan_pages_excluding_index_and_diary() {
+        let wikis = vec![make_wiki(vec![
+            ("/wiki/index.wiki", "[[linked]]"),
+            ("/wiki/linked.wiki", ""),
+            ("/wiki/orphan.wiki", "[[orphan]]"),
+            ("/wiki/diary/2021-05-03.wiki", ""),
+        ])];
+
+        let diagnostics =
+            LintRegistry::default().lint(&wikis, &LintConfig::default());
+        let paths: Vec<&std::path::Path> =
+            diagnostics.iter().map(|d| d.path.as_path()).collect();
+        assert_eq!(paths, vec![std::path::Path::new("/wiki/orphan.wiki")]);
+        assert_eq!(diagnostics[0].rule, "orphan-page");
+        assert_eq!(diagnostics[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn lint_should_honor_disabled_rules_and_severity_overrides() {
+        let wikis = vec![make_wiki(vec![(
+            "/wiki/index.wiki",
+            "= A =\n=== B ===\n= A =\n",
+        )])];
+
+        let mut config = LintConfig::default();
+        config.disabled.push(String::from("duplicate-header"));
+        config
+            .severity
+            .insert(String::from("skipped-header-level"), Severity::Error);
+
+        let diagnostics = LintRegistry::default().lint(&wikis, &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "skipped-header-level");
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+}
@@ -0,0 +1,499 @@
+use super::{span_regions, Lint, LintContext, LintRule, Severity};
+use crate::{
+    lang::elements::{
+        BlockElement, Element, Header, InlineBlockElement,
+        InlineElementContainer, Located, Paragraph, Placeholder, Region,
+    },
+    wiki::{collect_elements, PageMetadata, TextEdit},
+    ToVimwikiString, VimwikiConfig,
+};
+use chrono::NaiveDate;
+use std::collections::HashSet;
+
+/// Reports pages that no other page links to, ignoring the index page and
+/// diary pages of a wiki as those are reached without links
+///
+/// The lint points at the first header of the page, falling back to the
+/// first element of the page when it has no headers
+pub struct OrphanPageRule;
+
+impl LintRule for OrphanPageRule {
+    fn name(&self) -> &'static str {
+        "orphan-page"
+    }
+
+    fn description(&self) -> &'static str {
+        "Page is not linked to by any other page"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint> {
+        if ctx.has_backlinks() || ctx.is_index_page() || ctx.is_diary_page() {
+            return Vec::new();
+        }
+
+        let region = headers(ctx)
+            .first()
+            .map(|header| header.region())
+            .or_else(|| ctx.page.page.elements.first().map(|x| x.region()))
+            .unwrap_or_default();
+        vec![Lint::new(region, "No other page links to this page")]
+    }
+}
+
+/// Reports headers whose text matches an earlier header within the same
+/// page, meaning that anchors can only ever reach the earlier header
+pub struct DuplicateHeaderRule;
+
+impl LintRule for DuplicateHeaderRule {
+    fn name(&self) -> &'static str {
+        "duplicate-header"
+    }
+
+    fn description(&self) -> &'static str {
+        "Header has the same anchor as an earlier header"
+    }
+
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint> {
+        let mut seen = HashSet::new();
+        headers(ctx)
+            .into_iter()
+            .filter_map(|header| {
+                let text = header.to_string().trim().to_string();
+                if seen.insert(text.to_string()) {
+                    None
+                } else {
+                    Some(Lint::new(
+                        header.region(),
+                        format!(
+                            "Header \"{}\" is already used by this page",
+                            text
+                        ),
+                    ))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reports headers that are more than one level deeper than the header
+/// before them, offering to raise the header to the next level
+pub struct SkippedHeaderLevelRule;
+
+impl LintRule for SkippedHeaderLevelRule {
+    fn name(&self) -> &'static str {
+        "skipped-header-level"
+    }
+
+    fn description(&self) -> &'static str {
+        "Header skips one or more levels below the previous header"
+    }
+
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let mut prev_level = None;
+
+        for header in headers(ctx) {
+            let level = header.level;
+            if let Some(prev) = prev_level.filter(|prev| level > prev + 1) {
+                let message = format!(
+                    "Header is level {} but follows a level {} header",
+                    level, prev
+                );
+                let fixed = Header {
+                    level: prev + 1,
+                    ..Header::clone(header.as_inner())
+                };
+                let lint = Lint::new(header.region(), message);
+                lints.push(
+                    match fixed.to_vimwiki_string(VimwikiConfig::default()) {
+                        Ok(text) => {
+                            lint.with_fix(TextEdit::new(header.region(), text))
+                        }
+                        Err(_) => lint,
+                    },
+                );
+            }
+            prev_level = Some(level);
+        }
+
+        lints
+    }
+}
+
+/// Reports list items without any content, including lines containing only
+/// a list marker, which are treated as paragraphs instead of list items
+pub struct EmptyListItemRule;
+
+impl LintRule for EmptyListItemRule {
+    fn name(&self) -> &'static str {
+        "empty-list-item"
+    }
+
+    fn description(&self) -> &'static str {
+        "List item has no content"
+    }
+
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        for element in collect_elements(&ctx.page.page) {
+            let region = element.region();
+            match element.into_inner() {
+                Element::InlineBlock(InlineBlockElement::ListItem(item)) => {
+                    let is_empty = item.contents.non_sublist_iter().all(|x| {
+                        matches!(x, BlockElement::Paragraph(p) if is_blank(p))
+                    });
+                    if is_empty {
+                        lints.push(Lint::new(region, "List item is empty"));
+                    }
+                }
+                Element::Block(BlockElement::Paragraph(p)) => {
+                    lints.extend(
+                        p.lines
+                            .iter()
+                            .filter(|line| is_list_marker(&line.to_string()))
+                            .filter_map(line_region)
+                            .map(|region| {
+                                Lint::new(region, "List item is empty")
+                            }),
+                    );
+                }
+                _ => {}
+            }
+        }
+        lints
+    }
+}
+
+/// Reports todo list items that are not yet complete and do not mention a
+/// date in the form of YYYY-MM-DD
+pub struct UndatedTodoRule;
+
+impl LintRule for UndatedTodoRule {
+    fn name(&self) -> &'static str {
+        "undated-todo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Incomplete todo item does not include a date"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Hint
+    }
+
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint> {
+        collect_elements(&ctx.page.page)
+            .into_iter()
+            .filter_map(|element| {
+                let region = element.region();
+                match element.into_inner() {
+                    Element::InlineBlock(InlineBlockElement::ListItem(
+                        item,
+                    )) if item.is_todo()
+                        && !item.is_todo_complete()
+                        && !item.is_todo_rejected() =>
+                    {
+                        let has_date =
+                            item.contents.non_sublist_iter().any(|x| match x {
+                                BlockElement::Paragraph(p) => {
+                                    has_date(&p.to_string())
+                                }
+                                _ => false,
+                            });
+                        if has_date {
+                            None
+                        } else {
+                            Some(Lint::new(region, "Todo item has no date"))
+                        }
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reports table rows that have fewer cells than the widest row of the table
+pub struct RaggedTableRule;
+
+impl LintRule for RaggedTableRule {
+    fn name(&self) -> &'static str {
+        "ragged-table"
+    }
+
+    fn description(&self) -> &'static str {
+        "Table row has fewer cells than other rows"
+    }
+
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        for element in collect_elements(&ctx.page.page) {
+            let region = element.region();
+            if let Element::Block(BlockElement::Table(table)) =
+                element.into_inner()
+            {
+                let cols = table.col_cnt();
+                for row in 0..table.row_cnt() {
+                    let cells = table.row(row).count();
+                    if cells < cols {
+                        lints.push(Lint::new(
+                            region,
+                            format!(
+                                "Row {} has {} of {} cells",
+                                row + 1,
+                                cells,
+                                cols
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        lints
+    }
+}
+
+/// Reports placeholders that have no effect, either because a later
+/// placeholder of the same kind replaces them or because their value is not
+/// valid for the metadata entry they describe
+///
+/// Replaced placeholders are offered a fix that removes them
+pub struct UnusedPlaceholderRule;
+
+impl LintRule for UnusedPlaceholderRule {
+    fn name(&self) -> &'static str {
+        "unused-placeholder"
+    }
+
+    fn description(&self) -> &'static str {
+        "Placeholder is replaced by a later placeholder or has an invalid value"
+    }
+
+    fn check(&self, ctx: &LintContext<'_, '_>) -> Vec<Lint> {
+        let placeholders: Vec<Located<&Placeholder>> = ctx
+            .page
+            .page
+            .elements
+            .iter()
+            .filter_map(|element| match element.as_inner() {
+                BlockElement::Placeholder(x) => {
+                    Some(Located::new(x, element.region()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut lints: Vec<Lint> = placeholders
+            .iter()
+            .enumerate()
+            .filter(|(idx, placeholder)| {
+                placeholders[idx + 1..].iter().any(|other| {
+                    is_same_placeholder_kind(
+                        placeholder.as_inner(),
+                        other.as_inner(),
+                    )
+                })
+            })
+            .map(|(_, placeholder)| {
+                let region = placeholder.region();
+                Lint::new(
+                    region,
+                    "Placeholder is replaced by a later placeholder",
+                )
+                .with_fix(TextEdit::new(region, ""))
+            })
+            .collect();
+
+        lints.extend(
+            PageMetadata::validate_page(&ctx.page.page)
+                .into_iter()
+                .map(|x| Lint::new(x.region(), x.into_inner().to_string())),
+        );
+        lints
+    }
+}
+
+/// Whether or not two placeholders describe the same metadata entry, where
+/// placeholders that are not builtin are compared by name
+fn is_same_placeholder_kind(a: &Placeholder<'_>, b: &Placeholder<'_>) -> bool {
+    match (a, b) {
+        (
+            Placeholder::Other { name: a, .. },
+            Placeholder::Other { name: b, .. },
+        ) => a == b,
+        (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
+
+/// Collects the headers of the page being checked in document order
+fn headers<'a>(ctx: &LintContext<'a, '_>) -> Vec<Located<&'a Header<'a>>> {
+    ctx.page
+        .page
+        .elements
+        .iter()
+        .filter_map(|element| match element.as_inner() {
+            BlockElement::Header(x) => Some(Located::new(x, element.region())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether or not the paragraph contains only whitespace or a todo checkbox,
+/// which is what remains of a todo list item without any content
+fn is_blank(paragraph: &Paragraph<'_>) -> bool {
+    let text = paragraph.to_string();
+    let text = text.trim();
+    text.is_empty()
+        || (text.starts_with('[')
+            && text.ends_with(']')
+            && text.chars().count() == 3)
+}
+
+/// Whether or not the line consists of nothing but a list marker and an
+/// optional todo checkbox
+fn is_list_marker(line: &str) -> bool {
+    let line = line.trim();
+    let marker = match line.strip_suffix(']') {
+        Some(x) => match x.rfind('[') {
+            Some(idx) if x[idx + 1..].chars().count() == 1 => x[..idx].trim(),
+            _ => line,
+        },
+        None => line,
+    };
+
+    match marker {
+        "-" | "*" | "#" => true,
+        _ => match marker.strip_suffix(|c| c == '.' || c == ')') {
+            // Ordered markers are numbers or single letters such as 1. or a)
+            Some(x) => {
+                (!x.is_empty() && x.chars().all(|c| c.is_ascii_digit()))
+                    || (x.len() == 1
+                        && x.chars().all(|c| c.is_ascii_alphabetic()))
+            }
+            None => false,
+        },
+    }
+}
+
+/// Produces the region covered by the inline elements of a line
+fn line_region(line: &InlineElementContainer<'_>) -> Option<Region> {
+    let first = line.iter().next()?.region();
+    let last = line.iter().last()?.region();
+    Some(span_regions(first, last))
+}
+
+/// Whether or not the text contains a date in the form of YYYY-MM-DD
+fn has_date(text: &str) -> bool {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .any(|word| NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lang::elements::Page,
+        wiki::{Wiki, WikiGraph, WikiPage},
+        Language,
+    };
+    use std::path::PathBuf;
+
+    fn check(rule: &dyn LintRule, text: &str) -> Vec<Lint> {
+        let page = WikiPage::new(
+            "/wiki/page.wiki",
+            Language::from_vimwiki_str(text)
+                .parse::<Page>()
+                .unwrap()
+                .into_owned(),
+        );
+        let wiki = Wiki {
+            path: PathBuf::from("/wiki"),
+            ..Default::default()
+        };
+        rule.check(&LintContext {
+            wikis: std::slice::from_ref(&wiki),
+            wiki: &wiki,
+            page: &page,
+            graph: &WikiGraph::new(),
+        })
+    }
+
+    #[test]
+    fn orphan_page_should_point_at_first_header_or_element() {
+        let lints = check(&OrphanPageRule, "Intro\n\n= A =\n== B ==\n");
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].region.offset(), 7);
+
+        let lints = check(&OrphanPageRule, "\nIntro\n");
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].region.offset(), 1);
+    }
+
+    #[test]
+    fn duplicate_header_should_report_headers_with_same_text() {
+        let lints = check(&DuplicateHeaderRule, "= A =\n== B ==\n== A ==\n");
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].region.offset(), 14);
+    }
+
+    #[test]
+    fn skipped_header_level_should_offer_fix_to_next_level() {
+        let lints =
+            check(&SkippedHeaderLevelRule, "= A =\n=== B ===\n== C ==\n");
+        assert_eq!(lints.len(), 1);
+        assert_eq!(
+            lints[0].fix,
+            Some(TextEdit::new(Region::new_at_depth(6, 10, 0), "== B ==\n"))
+        );
+    }
+
+    #[test]
+    fn empty_list_item_should_report_lines_with_only_markers() {
+        let lints =
+            check(&EmptyListItemRule, "- one\n\n-\n\n1.\n\n- [ ]\n\nNote.\n");
+        let offsets: Vec<usize> =
+            lints.iter().map(|l| l.region.offset()).collect();
+        assert_eq!(offsets, vec![7, 10, 14]);
+    }
+
+    #[test]
+    fn undated_todo_should_report_incomplete_todos_without_dates() {
+        let lints = check(
+            &UndatedTodoRule,
+            "- [ ] one\n- [ ] two 2021-05-03\n- [X] three\n- four\n",
+        );
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].region.offset(), 0);
+    }
+
+    #[test]
+    fn ragged_table_should_report_rows_missing_cells() {
+        let lints = check(&RaggedTableRule, "| a | b |\n| c |\n");
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].message, "Row 2 has 1 of 2 cells");
+    }
+
+    #[test]
+    fn unused_placeholder_should_report_replaced_and_invalid_placeholders() {
+        let lints = check(
+            &UnusedPlaceholderRule,
+            "\n%title One\n%foo bar\n%draft maybe\n%title Two\n%foo baz\n",
+        );
+        let messages: Vec<&str> =
+            lints.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Placeholder is replaced by a later placeholder",
+                "Placeholder is replaced by a later placeholder",
+                "Placeholder %draft expects a bool but got \"maybe\"",
+            ]
+        );
+        assert!(lints[0].fix.is_some());
+        assert!(lints[2].fix.is_none());
+    }
+}
@@ -0,0 +1,509 @@
+use crate::lang::elements::{BlockElement, Located, Page, Placeholder};
+use chrono::NaiveDate;
+use derive_more::{Display, Error, IsVariant};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::BTreeMap, fmt, str::FromStr};
+
+/// Represents the type of value held by a metadata entry
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    IsVariant,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataKind {
+    #[display(fmt = "text")]
+    Text,
+
+    #[display(fmt = "bool")]
+    Bool,
+
+    #[display(fmt = "integer")]
+    Integer,
+
+    #[display(fmt = "date")]
+    Date,
+
+    #[display(fmt = "list")]
+    List,
+}
+
+impl MetadataKind {
+    /// Returns the kind of value expected for a well-known metadata entry,
+    /// or none if the entry has no expected kind
+    pub fn for_name(name: &str) -> Option<Self> {
+        match name {
+            "title" | "template" | "author" => Some(Self::Text),
+            "nohtml" | "draft" => Some(Self::Bool),
+            "date" | "updated" => Some(Self::Date),
+            "tags" => Some(Self::List),
+            _ => None,
+        }
+    }
+
+    /// Parses text into a value of this kind, returning none if the text
+    /// does not represent a valid value
+    ///
+    /// * Bools are `true`, `false`, `yes`, or `no` in any case
+    /// * Dates are in the form of YYYY-MM-DD
+    /// * Lists are separated by commas, colons, or whitespace
+    pub fn parse_value(self, text: &str) -> Option<MetadataValue> {
+        let text = text.trim();
+        match self {
+            Self::Text => Some(MetadataValue::Text(text.to_string())),
+            Self::Bool => match text.to_lowercase().as_str() {
+                "true" | "yes" => Some(MetadataValue::Bool(true)),
+                "false" | "no" => Some(MetadataValue::Bool(false)),
+                _ => None,
+            },
+            Self::Integer => text.parse().ok().map(MetadataValue::Integer),
+            Self::Date => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .map(MetadataValue::Date),
+            Self::List => Some(MetadataValue::List(
+                text.split(|c: char| c == ',' || c == ':' || c.is_whitespace())
+                    .filter(|x| !x.is_empty())
+                    .map(ToString::to_string)
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Represents the typed value of a metadata entry
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    IsVariant,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase", tag = "type", content = "value")]
+pub enum MetadataValue {
+    Text(String),
+    Bool(bool),
+    Integer(i64),
+    Date(NaiveDate),
+    List(Vec<String>),
+}
+
+impl MetadataValue {
+    /// Parses text into the most specific kind of value it represents,
+    /// which is a bool, integer, or date before falling back to text
+    pub fn infer(text: &str) -> Self {
+        [
+            MetadataKind::Bool,
+            MetadataKind::Integer,
+            MetadataKind::Date,
+        ]
+        .iter()
+        .find_map(|kind| kind.parse_value(text))
+        .unwrap_or_else(|| Self::Text(text.trim().to_string()))
+    }
+
+    /// Returns the kind of the value
+    pub fn kind(&self) -> MetadataKind {
+        match self {
+            Self::Text(_) => MetadataKind::Text,
+            Self::Bool(_) => MetadataKind::Bool,
+            Self::Integer(_) => MetadataKind::Integer,
+            Self::Date(_) => MetadataKind::Date,
+            Self::List(_) => MetadataKind::List,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(x) => Some(x.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn as_date(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Date(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[String]> {
+        match self {
+            Self::List(x) => Some(x.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the value matches the given text, which is parsed as
+    /// the same kind as the value; lists match if any of their items match
+    pub fn matches_str(&self, text: &str) -> bool {
+        match self {
+            Self::List(items) => items.iter().any(|x| x == text.trim()),
+            x => x.kind().parse_value(text).as_ref() == Some(x),
+        }
+    }
+}
+
+impl fmt::Display for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(x) => write!(f, "{}", x),
+            Self::Bool(x) => write!(f, "{}", x),
+            Self::Integer(x) => write!(f, "{}", x),
+            Self::Date(x) => write!(f, "{}", x.format("%Y-%m-%d")),
+            Self::List(x) => write!(f, "{}", x.join(", ")),
+        }
+    }
+}
+
+/// Represents a problem with a placeholder found when extracting metadata
+#[derive(Clone, Debug, Display, PartialEq, Eq, Error)]
+pub enum MetadataError {
+    /// Represents a well-known entry whose value is not of the expected kind
+    #[display(
+        fmt = "Placeholder %{} expects a {} but got {:?}",
+        name,
+        expected,
+        value
+    )]
+    InvalidValue {
+        #[error(not(source))]
+        name: String,
+        value: String,
+        expected: MetadataKind,
+    },
+}
+
+/// Represents the metadata of a page as described by its placeholders,
+/// where each placeholder is a named entry with a typed value
+///
+/// Builtin placeholders map to the `title`, `date`, `template`, and `nohtml`
+/// entries while any other placeholder such as `%author` or `%draft` maps to
+/// an entry of the same name. Well-known entries are parsed as their
+/// expected kind and all other entries have their kind inferred. When a
+/// placeholder appears more than once, the last one wins
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PageMetadata {
+    entries: BTreeMap<String, MetadataValue>,
+}
+
+impl PageMetadata {
+    /// Creates new, empty metadata
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extracts metadata from the placeholders of a page, skipping any
+    /// placeholder whose value is invalid
+    pub fn from_page(page: &Page<'_>) -> Self {
+        Self {
+            entries: parse_placeholders(page)
+                .into_iter()
+                .filter_map(|x| x.into_inner().ok())
+                .collect(),
+        }
+    }
+
+    /// Checks the placeholders of a page, returning a problem for each
+    /// placeholder whose value is invalid
+    pub fn validate_page(page: &Page<'_>) -> Vec<Located<MetadataError>> {
+        parse_placeholders(page)
+            .into_iter()
+            .filter_map(|x| {
+                let region = x.region();
+                x.into_inner().err().map(|x| Located::new(x, region))
+            })
+            .collect()
+    }
+
+    /// Returns true if there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Iterates through each entry in order by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &MetadataValue)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Returns the value of the entry with the given name
+    pub fn get(&self, name: &str) -> Option<&MetadataValue> {
+        self.entries.get(name)
+    }
+
+    /// Sets the value of the entry with the given name, returning the
+    /// previous value if there was one
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        value: MetadataValue,
+    ) -> Option<MetadataValue> {
+        self.entries.insert(name.into(), value)
+    }
+
+    /// Sets the entry described by a placeholder, failing if the
+    /// placeholder's value is not of the expected kind
+    pub fn insert_placeholder(
+        &mut self,
+        placeholder: &Placeholder<'_>,
+    ) -> Result<(), MetadataError> {
+        let (name, value) = parse_placeholder(placeholder)?;
+        self.entries.insert(name, value);
+        Ok(())
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.get("title").and_then(MetadataValue::as_text)
+    }
+
+    pub fn date(&self) -> Option<NaiveDate> {
+        self.get("date").and_then(MetadataValue::as_date)
+    }
+
+    pub fn template(&self) -> Option<&str> {
+        self.get("template").and_then(MetadataValue::as_text)
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.get("author").and_then(MetadataValue::as_text)
+    }
+
+    /// Returns the tags listed by the page's `%tags` placeholder, which are
+    /// separate from the tags found within the page's text
+    pub fn tags(&self) -> &[String] {
+        self.get("tags")
+            .and_then(MetadataValue::as_list)
+            .unwrap_or_default()
+    }
+
+    pub fn is_no_html(&self) -> bool {
+        self.get("nohtml")
+            .and_then(MetadataValue::as_bool)
+            .unwrap_or_default()
+    }
+
+    pub fn is_draft(&self) -> bool {
+        self.get("draft")
+            .and_then(MetadataValue::as_bool)
+            .unwrap_or_default()
+    }
+
+    /// Compares two sets of metadata by the entry with the given name,
+    /// ordering metadata missing the entry after metadata that has it
+    pub fn cmp_by(&self, other: &Self, name: &str) -> Ordering {
+        match (self.get(name), other.get(name)) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+/// Represents a condition on the metadata of a page, written as
+///
+/// * `name` for entries that exist and are not false
+/// * `!name` for entries that are missing or false
+/// * `name=value` for entries whose value matches
+/// * `name!=value` for entries that are missing or whose value does not match
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetadataFilter {
+    /// Name of the entry to check
+    pub name: String,
+
+    /// Value to compare against the entry, if any
+    pub value: Option<String>,
+
+    /// Whether to invert the condition
+    pub negate: bool,
+}
+
+impl MetadataFilter {
+    /// Returns true if the metadata satisfies the filter
+    pub fn matches(&self, metadata: &PageMetadata) -> bool {
+        let entry = metadata.get(&self.name);
+        let is_match = match self.value.as_deref() {
+            Some(value) => matches!(entry, Some(x) if x.matches_str(value)),
+            None => matches!(entry, Some(x) if x.as_bool() != Some(false)),
+        };
+        is_match != self.negate
+    }
+}
+
+impl FromStr for MetadataFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, value, negate) = if let Some(idx) = s.find("!=") {
+            (&s[..idx], Some(&s[idx + 2..]), true)
+        } else if let Some(idx) = s.find('=') {
+            (&s[..idx], Some(&s[idx + 1..]), false)
+        } else if let Some(name) = s.strip_prefix('!') {
+            (name, None, true)
+        } else {
+            (s, None, false)
+        };
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("Metadata filter {:?} is missing a name", s));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            value: value.map(|x| x.trim().to_string()),
+            negate,
+        })
+    }
+}
+
+/// Converts each placeholder at the top level of a page into a named entry,
+/// or an error if the placeholder's value is not of the expected kind
+fn parse_placeholders(
+    page: &Page<'_>,
+) -> Vec<Located<Result<(String, MetadataValue), MetadataError>>> {
+    page.elements
+        .iter()
+        .filter_map(|element| {
+            let region = element.region();
+            let entry = match element.as_inner() {
+                BlockElement::Placeholder(x) => parse_placeholder(x),
+                _ => return None,
+            };
+            Some(Located::new(entry, region))
+        })
+        .collect()
+}
+
+/// Converts a placeholder into a named entry, or an error if the
+/// placeholder's value is not of the expected kind
+fn parse_placeholder(
+    placeholder: &Placeholder<'_>,
+) -> Result<(String, MetadataValue), MetadataError> {
+    match placeholder {
+        Placeholder::Title(x) => {
+            Ok((String::from("title"), MetadataValue::Text(x.to_string())))
+        }
+        Placeholder::NoHtml => {
+            Ok((String::from("nohtml"), MetadataValue::Bool(true)))
+        }
+        Placeholder::Template(x) => {
+            Ok((String::from("template"), MetadataValue::Text(x.to_string())))
+        }
+        Placeholder::Date(x) => {
+            Ok((String::from("date"), MetadataValue::Date(*x)))
+        }
+        Placeholder::Other { name, value } => {
+            match MetadataKind::for_name(name) {
+                Some(kind) => kind
+                    .parse_value(value)
+                    .map(|x| (name.to_string(), x))
+                    .ok_or_else(|| MetadataError::InvalidValue {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                        expected: kind,
+                    }),
+                None => Ok((name.to_string(), MetadataValue::infer(value))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn parse(text: &str) -> Page<'static> {
+        Language::from_vimwiki_str(text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn from_page_should_convert_placeholders_into_typed_entries() {
+        let metadata = PageMetadata::from_page(&parse(
+            "%template post\n%date 2021-06-17\n%author Jane Doe\n\
+            %tags one, two three\n%draft yes\n%weight 30\n%draft no\n",
+        ));
+
+        assert_eq!(metadata.template(), Some("post"));
+        assert_eq!(metadata.date(), Some(NaiveDate::from_ymd(2021, 6, 17)));
+        assert_eq!(metadata.author(), Some("Jane Doe"));
+        assert_eq!(metadata.tags(), ["one", "two", "three"]);
+        assert!(!metadata.is_draft());
+        assert!(!metadata.is_no_html());
+        assert_eq!(metadata.get("weight"), Some(&MetadataValue::Integer(30)));
+    }
+
+    #[test]
+    fn validate_page_should_report_values_of_unexpected_kind() {
+        let page = parse("%updated tomorrow\n%draft maybe\n%other value\n");
+        let errors = PageMetadata::validate_page(&page);
+        let messages: Vec<String> =
+            errors.iter().map(|x| x.as_inner().to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Placeholder %updated expects a date but got \"tomorrow\"",
+                "Placeholder %draft expects a bool but got \"maybe\"",
+            ]
+        );
+        assert_eq!(errors[1].region().offset(), 18);
+
+        let metadata = PageMetadata::from_page(&page);
+        assert_eq!(metadata.len(), 1);
+    }
+
+    #[test]
+    fn filter_should_match_entries_by_presence_and_value() {
+        let metadata = PageMetadata::from_page(&parse(
+            "%tags a, b\n%draft false\n%weight 10\n",
+        ));
+        let matches = |filter: &str| {
+            filter.parse::<MetadataFilter>().unwrap().matches(&metadata)
+        };
+
+        assert!(matches("tags=b"));
+        assert!(matches("weight=10"));
+        assert!(matches("weight!=9"));
+        assert!(matches("!draft"));
+        assert!(matches("!author"));
+        assert!(!matches("draft"));
+        assert!(!matches("tags=c"));
+        assert!("=value".parse::<MetadataFilter>().is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{
+    lang::elements::{
+        Element, InlineElement, IntoChildren, Link, Located, Page,
+    },
+    utils::normalize_path,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+mod check;
+pub use check::{
+    check_links, check_page_links, BrokenLink, BrokenLinkReason, LinkKind,
+};
+
+mod edit;
+pub use edit::{FileEdits, TextEdit};
+
+mod generate;
+pub use generate::{
+    find_section, make_diary_index, make_links_index, make_section_edit,
+    make_tag_links, make_toc, make_toc_list, SectionPlacement,
+};
+
+mod graph;
+pub use graph::{GraphEdge, WikiGraph};
+
+mod lint;
+pub use lint::{
+    Diagnostic, DuplicateHeaderRule, EmptyListItemRule, Lint, LintConfig,
+    LintContext, LintRegistry, LintRule, OrphanPageRule, RaggedTableRule,
+    Severity, SkippedHeaderLevelRule, UndatedTodoRule, UnusedPlaceholderRule,
+};
+
+mod metadata;
+pub use metadata::{
+    MetadataError, MetadataFilter, MetadataKind, MetadataValue, PageMetadata,
+};
+
+mod rename;
+pub use rename::{rename_page, RenameError};
+
+mod resolve;
+pub use resolve::{LinkTarget, LinkTargetError, LinkTargetKind};
+
+mod search;
+#[cfg(feature = "html")]
+pub(crate) use search::make_segments;
+pub use search::{
+    SearchField, SearchHit, SearchIndex, SearchQuery, SearchQueryError,
+    SearchResult,
+};
+
+mod tags;
+pub use tags::{TagIndex, TagLocation};
+
+/// Represents a wiki on the filesystem alongside the pages loaded from it,
+/// providing enough information to resolve links between pages
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wiki<'a> {
+    /// Index of the wiki, used when resolving `wiki{index}:` links
+    pub index: usize,
+
+    /// Optional name of the wiki, used when resolving `wn.{name}:` links
+    pub name: Option<String>,
+
+    /// Path to the root of the wiki
+    pub path: PathBuf,
+
+    /// Extension of wiki pages (without the leading period)
+    pub ext: String,
+
+    /// Path to the diary relative to the root of the wiki
+    pub diary_rel_path: PathBuf,
+
+    /// Pages loaded from the wiki
+    pub pages: Vec<WikiPage<'a>>,
+}
+
+impl Default for Wiki<'_> {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            name: None,
+            path: PathBuf::new(),
+            ext: Self::default_ext(),
+            diary_rel_path: Self::default_diary_rel_path(),
+            pages: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Wiki<'a> {
+    #[inline]
+    pub fn default_ext() -> String {
+        "wiki".to_string()
+    }
+
+    #[inline]
+    pub fn default_diary_rel_path() -> PathBuf {
+        PathBuf::from("diary")
+    }
+
+    /// Returns true if the path is contained within the wiki
+    pub fn contains_path(&self, path: &Path) -> bool {
+        normalize_path(path).starts_with(normalize_path(&self.path))
+    }
+
+    /// Produces the path to the page representing the given date within
+    /// the wiki's diary
+    pub fn make_diary_page_path(&self, date: chrono::NaiveDate) -> PathBuf {
+        self.path.join(self.diary_rel_path.as_path()).join(format!(
+            "{}.{}",
+            date.format("%Y-%m-%d"),
+            self.ext
+        ))
+    }
+
+    /// Produces the path to the page that serves as the index of the wiki's
+    /// diary
+    pub fn make_diary_index_path(&self) -> PathBuf {
+        self.path
+            .join(self.diary_rel_path.as_path())
+            .join(format!("diary.{}", self.ext))
+    }
+
+    /// Finds the page within the wiki with the specified path
+    pub fn find_page_by_path(&self, path: &Path) -> Option<&WikiPage<'a>> {
+        let path = normalize_path(path);
+        self.pages.iter().find(|p| normalize_path(&p.path) == path)
+    }
+
+    /// Borrows the wiki and all of its pages
+    pub fn to_borrowed(&self) -> Wiki {
+        Wiki {
+            index: self.index,
+            name: self.name.clone(),
+            path: self.path.to_path_buf(),
+            ext: self.ext.to_string(),
+            diary_rel_path: self.diary_rel_path.to_path_buf(),
+            pages: self.pages.iter().map(WikiPage::to_borrowed).collect(),
+        }
+    }
+
+    /// Converts the wiki and all of its pages into an owned version
+    pub fn into_owned(self) -> Wiki<'static> {
+        Wiki {
+            index: self.index,
+            name: self.name,
+            path: self.path,
+            ext: self.ext,
+            diary_rel_path: self.diary_rel_path,
+            pages: self.pages.into_iter().map(WikiPage::into_owned).collect(),
+        }
+    }
+}
+
+/// Represents a page loaded from some path within a wiki
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WikiPage<'a> {
+    /// Path to the file containing the page
+    pub path: PathBuf,
+
+    /// Page parsed from the file
+    pub page: Page<'a>,
+}
+
+impl<'a> WikiPage<'a> {
+    pub fn new(path: impl Into<PathBuf>, page: Page<'a>) -> Self {
+        Self {
+            path: path.into(),
+            page,
+        }
+    }
+
+    /// Collects every link within the page, including those nested within
+    /// other elements such as lists, tables, and decorated text
+    pub fn to_links(&self) -> Vec<Located<Link>> {
+        collect_links(&self.page)
+    }
+
+    pub fn to_borrowed(&self) -> WikiPage {
+        WikiPage {
+            path: self.path.to_path_buf(),
+            page: self.page.to_borrowed(),
+        }
+    }
+
+    pub fn into_owned(self) -> WikiPage<'static> {
+        WikiPage {
+            path: self.path,
+            page: self.page.into_owned(),
+        }
+    }
+}
+
+/// Finds the wiki that contains the given path, favoring the wiki whose
+/// root is the most specific in the case of nested wikis
+pub fn find_wiki_by_path<'a, 'b>(
+    wikis: &'a [Wiki<'b>],
+    path: &Path,
+) -> Option<&'a Wiki<'b>> {
+    wikis
+        .iter()
+        .filter(|w| w.contains_path(path))
+        .max_by_key(|w| w.path.components().count())
+}
+
+/// Collects every element within a page in document order, including the
+/// page's top-level block elements and all of their descendants
+pub fn collect_elements<'a>(page: &'a Page<'_>) -> Vec<Located<Element<'a>>> {
+    fn visit<'a>(
+        element: Located<Element<'a>>,
+        elements: &mut Vec<Located<Element<'a>>>,
+    ) {
+        let children = element.as_inner().clone().into_children();
+        elements.push(element);
+        for child in children {
+            visit(child, elements);
+        }
+    }
+
+    let mut elements = Vec::new();
+    for element in page.to_borrowed().into_children() {
+        visit(element.map(Element::from), &mut elements);
+    }
+    elements
+}
+
+/// Collects every link within a page in document order
+pub fn collect_links<'a>(page: &'a Page<'_>) -> Vec<Located<Link<'a>>> {
+    collect_elements(page)
+        .into_iter()
+        .filter_map(|element| {
+            let region = element.region();
+            match element.into_inner() {
+                Element::Inline(InlineElement::Link(x)) => {
+                    Some(Located::new(x, region))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn parse(text: &str) -> Page<'static> {
+        Language::from_vimwiki_str(text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn collect_links_should_find_links_nested_within_other_elements() {
+        let page = parse(
+            "[[one]] *[[two]]*\n\n- [[three]]\n\n| [[four]] |\n\n= [[five]] =\n",
+        );
+        let links: Vec<String> = collect_links(&page)
+            .into_iter()
+            .map(|l| l.data().to_decoded_uri_string())
+            .collect();
+        assert_eq!(links, vec!["one", "two", "three", "four", "five"]);
+    }
+
+    #[test]
+    fn find_wiki_by_path_should_favor_most_specific_wiki() {
+        let wikis = vec![
+            Wiki {
+                index: 0,
+                path: PathBuf::from("/wiki"),
+                ..Default::default()
+            },
+            Wiki {
+                index: 1,
+                path: PathBuf::from("/wiki/nested"),
+                ..Default::default()
+            },
+        ];
+
+        let wiki = find_wiki_by_path(&wikis, Path::new("/wiki/nested/a.wiki"))
+            .unwrap();
+        assert_eq!(wiki.index, 1);
+
+        let wiki =
+            find_wiki_by_path(&wikis, Path::new("/wiki/a.wiki")).unwrap();
+        assert_eq!(wiki.index, 0);
+
+        assert!(find_wiki_by_path(&wikis, Path::new("/other/a.wiki")).is_none());
+    }
+}
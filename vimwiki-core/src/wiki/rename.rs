@@ -0,0 +1,527 @@
+use super::{
+    find_wiki_by_path, resolve::is_file_scheme, FileEdits, LinkTarget,
+    LinkTargetKind, TextEdit, Wiki,
+};
+use crate::{
+    lang::{
+        elements::{Description, Link, LinkData},
+        output::{ToVimwikiString, VimwikiConfig, VimwikiOutputError},
+    },
+    utils::{normalize_path, path_to_uri_string},
+};
+use chrono::NaiveDate;
+use derive_more::{Display, Error};
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    ffi::OsStr,
+    path::{Component, Path, PathBuf},
+};
+use uriparse::URIReference;
+
+#[derive(Debug, Display, Error)]
+pub enum RenameError {
+    /// Represents an error where the page being renamed is not loaded
+    /// within any of the wikis
+    #[display(fmt = "{:?} is not a page within a wiki", path)]
+    PageNotFound {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+
+    /// Represents an error where the new path of the page is already used
+    /// by some other page
+    #[display(fmt = "{:?} already exists", path)]
+    PageAlreadyExists {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+
+    /// Represents an error where the new path of the page is not within
+    /// any of the wikis
+    #[display(fmt = "{:?} is not within a wiki", path)]
+    NotWithinWiki {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+
+    /// Represents an error that occurred when producing the text of an
+    /// updated link
+    #[display(fmt = "Failed to produce link: {}", source)]
+    Output { source: VimwikiOutputError },
+}
+
+/// Determines the edits needed across all wikis to keep links working when
+/// the page at `old` is renamed (or moved) to `new`
+///
+/// Every link that resolves to the old page, regardless of whether it is
+/// relative, absolute, interwiki, or diary, is rewritten to reference the
+/// new page while keeping its anchor and description. Relative links within
+/// the renamed page itself are also updated when the page changes
+/// directories so that they continue to point to the same destination.
+///
+/// Edits are grouped by file using the original path of each file.
+pub fn rename_page(
+    wikis: &[Wiki<'_>],
+    old: &Path,
+    new: &Path,
+) -> Result<Vec<FileEdits>, RenameError> {
+    let old = normalize_path(old);
+    let new = normalize_path(new);
+
+    if !wikis.iter().any(|w| w.find_page_by_path(&old).is_some()) {
+        return Err(RenameError::PageNotFound { path: old });
+    }
+
+    if old != new && wikis.iter().any(|w| w.find_page_by_path(&new).is_some()) {
+        return Err(RenameError::PageAlreadyExists { path: new });
+    }
+
+    let new_wiki = find_wiki_by_path(wikis, &new)
+        .ok_or_else(|| RenameError::NotWithinWiki { path: new.clone() })?;
+
+    let mut all_edits = Vec::new();
+    for page in wikis.iter().flat_map(|w| w.pages.iter()) {
+        let src = normalize_path(&page.path);
+        let is_renamed_page = src == old;
+        let new_src = if is_renamed_page { &new } else { &src };
+
+        let mut edits = Vec::new();
+        for link in page.to_links() {
+            let target = match LinkTarget::resolve(wikis, &src, &link) {
+                Ok(Some(target)) => target,
+                _ => continue,
+            };
+
+            let is_retargeted =
+                target.path == old && !target.kind.is_directory();
+            if !is_retargeted && !is_renamed_page {
+                continue;
+            }
+
+            let target = if is_retargeted {
+                LinkTarget {
+                    wiki: Some(new_wiki.index),
+                    path: new.clone(),
+                    ..target
+                }
+            } else {
+                target
+            };
+
+            // If the link as it stands already reaches the desired
+            // destination from the page's new location, leave it alone
+            if let Ok(Some(current)) =
+                LinkTarget::resolve(wikis, new_src, &link)
+            {
+                if current.is_same_destination(&target) {
+                    continue;
+                }
+            }
+
+            if let Some(new_link) = relink(wikis, &link, new_src, &target) {
+                let text = new_link
+                    .to_vimwiki_string(VimwikiConfig::default())
+                    .map_err(|source| RenameError::Output { source })?;
+                edits.push(TextEdit::new(link.region(), text));
+            }
+        }
+
+        if !edits.is_empty() {
+            all_edits.push(FileEdits {
+                path: page.path.to_path_buf(),
+                edits,
+            });
+        }
+    }
+
+    Ok(all_edits)
+}
+
+/// Produces a new version of the link found in `src` that references the
+/// target, keeping the style of the original link where possible
+fn relink(
+    wikis: &[Wiki<'_>],
+    link: &Link<'_>,
+    src: &Path,
+    target: &LinkTarget,
+) -> Option<Link<'static>> {
+    let data = link.data();
+    let src_dir = src.parent().unwrap_or_else(|| Path::new(""));
+    let fragment = data
+        .fragment_str()
+        .map(|x| format!("#{}", x))
+        .unwrap_or_default();
+
+    let make_data = |uri: String| {
+        make_uri_ref(&uri).map(|uri_ref| {
+            LinkData::new(
+                uri_ref,
+                data.description.clone().map(Description::into_owned),
+                data.properties.as_ref().map(|properties| {
+                    properties
+                        .iter()
+                        .map(|(k, v)| {
+                            (Cow::from(k.to_string()), Cow::from(v.to_string()))
+                        })
+                        .collect()
+                }),
+            )
+        })
+    };
+
+    // Links to files keep their scheme and whether or not they are absolute
+    if target.kind == LinkTargetKind::File {
+        let prefix = match data.scheme() {
+            Some(scheme) if is_file_scheme(scheme) => format!("{}:", scheme),
+            _ => String::new(),
+        };
+
+        let path = if data.uri_ref.path().is_absolute() {
+            // Transclusions without a scheme are absolute to the wiki root
+            match (data.scheme(), find_wiki_by_path(wikis, src)) {
+                (None, Some(wiki)) => {
+                    let path = target.path.strip_prefix(&wiki.path).ok()?;
+                    format!("/{}", path_to_uri_string(path))
+                }
+                (None, None) => return None,
+                _ => path_to_uri_string(&target.path),
+            }
+        } else {
+            path_to_uri_string(&relative_path(src_dir, &target.path))
+        };
+
+        let data = make_data(format!("{}{}{}", prefix, path, fragment))?;
+        return Some(match link {
+            Link::Raw { .. } => Link::Raw { data },
+            Link::Transclusion { .. } => Link::Transclusion { data },
+            _ => Link::Wiki { data },
+        });
+    }
+
+    let src_wiki = find_wiki_by_path(wikis, src);
+    let target_wiki = find_wiki_by_path(wikis, &target.path)?;
+    let within_wiki = target.path.strip_prefix(&target_wiki.path).ok()?;
+    let is_same_wiki = src_wiki.map(|w| w.index) == Some(target_wiki.index);
+
+    // Diary links remain diary links if the target is still a diary page
+    if let (Link::Diary { .. }, true) = (link, is_same_wiki) {
+        if let Some(date) = diary_date(target_wiki, within_wiki) {
+            let data = make_data(fragment)?;
+            return Some(Link::Diary { date, data });
+        }
+    }
+
+    let within_wiki =
+        path_to_uri_string(&strip_ext(within_wiki, &target_wiki.ext));
+    match link {
+        Link::Wiki { .. } | Link::Diary { .. } if is_same_wiki => {
+            let is_absolute =
+                link.is_diary() || data.uri_ref.path().is_absolute();
+            let path = if is_absolute {
+                format!("/{}", within_wiki)
+            } else {
+                let path = strip_ext(&target.path, &target_wiki.ext);
+                path_to_uri_string(&relative_path(src_dir, &path))
+            };
+
+            let data = make_data(format!("{}{}", path, fragment))?;
+            Some(Link::Wiki { data })
+        }
+        Link::NamedInterWiki { .. }
+        | Link::Wiki { .. }
+        | Link::Diary { .. }
+            if target_wiki.name.is_some() =>
+        {
+            let name = Cow::from(target_wiki.name.clone().unwrap_or_default());
+            let data = make_data(format!("{}{}", within_wiki, fragment))?;
+            Some(Link::NamedInterWiki { name, data })
+        }
+        _ => {
+            let index = u32::try_from(target_wiki.index).ok()?;
+            let data = make_data(format!("{}{}", within_wiki, fragment))?;
+            Some(Link::IndexedInterWiki { index, data })
+        }
+    }
+}
+
+/// Parses a uri reference in the same manner as links are parsed, falling
+/// back to encoding the text when it is not a valid uri
+fn make_uri_ref(text: &str) -> Option<URIReference<'static>> {
+    URIReference::try_from(text)
+        .map(URIReference::into_owned)
+        .or_else(|_| {
+            URIReference::try_from(LinkData::encode_uri(text).as_str())
+                .map(URIReference::into_owned)
+        })
+        .ok()
+}
+
+/// Parses the date of a page if it lives directly within the wiki's diary
+pub(super) fn diary_date(
+    wiki: &Wiki<'_>,
+    within_wiki: &Path,
+) -> Option<NaiveDate> {
+    if within_wiki.parent() != Some(wiki.diary_rel_path.as_path())
+        || within_wiki.extension().and_then(OsStr::to_str) != Some(&wiki.ext)
+    {
+        return None;
+    }
+
+    within_wiki
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .and_then(|stem| NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok())
+}
+
+/// Removes the extension from the path if it matches the one provided
+pub(super) fn strip_ext(path: &Path, ext: &str) -> PathBuf {
+    if path.extension().and_then(OsStr::to_str) == Some(ext) {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Produces a path relative to the directory `from` that leads to `to`,
+/// assuming that both paths are normalized
+pub(super) fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from = from.components().collect::<Vec<Component>>();
+    let to = to.components().collect::<Vec<Component>>();
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push("..");
+    }
+    for c in &to[common..] {
+        path.push(c.as_os_str());
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page, WikiPage};
+    use indoc::indoc;
+
+    fn page(path: &str, text: &str) -> WikiPage<'static> {
+        WikiPage::new(
+            path,
+            Language::from_vimwiki_str(text)
+                .parse::<Page>()
+                .unwrap()
+                .into_owned(),
+        )
+    }
+
+    fn wikis(pages: Vec<WikiPage<'static>>) -> Vec<Wiki<'static>> {
+        let (one, two) =
+            pages.into_iter().partition(|p| p.path.starts_with("/one"));
+        vec![
+            Wiki {
+                index: 0,
+                path: PathBuf::from("/one"),
+                pages: one,
+                ..Default::default()
+            },
+            Wiki {
+                index: 1,
+                name: Some(String::from("two")),
+                path: PathBuf::from("/two"),
+                pages: two,
+                ..Default::default()
+            },
+        ]
+    }
+
+    /// Applies edits to the texts of the pages, returning the path and new
+    /// text of each edited page
+    fn apply(
+        texts: &[(&str, &str)],
+        edits: Vec<FileEdits>,
+    ) -> Vec<(String, String)> {
+        edits
+            .into_iter()
+            .map(|edits| {
+                let text = texts
+                    .iter()
+                    .find(|(path, _)| Path::new(path) == edits.path)
+                    .map(|(_, text)| *text)
+                    .unwrap();
+                (edits.path.to_string_lossy().to_string(), edits.apply(text))
+            })
+            .collect()
+    }
+
+    fn rename(
+        texts: &[(&str, &str)],
+        old: &str,
+        new: &str,
+    ) -> Vec<(String, String)> {
+        let wikis = wikis(texts.iter().map(|(p, t)| page(p, t)).collect());
+        let edits =
+            rename_page(&wikis, Path::new(old), Path::new(new)).unwrap();
+        apply(texts, edits)
+    }
+
+    #[test]
+    fn rename_page_should_fail_if_old_page_is_missing() {
+        let wikis = wikis(vec![page("/one/a.wiki", "")]);
+        assert!(matches!(
+            rename_page(
+                &wikis,
+                Path::new("/one/b.wiki"),
+                Path::new("/one/c.wiki")
+            ),
+            Err(RenameError::PageNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn rename_page_should_fail_if_new_page_already_exists() {
+        let wikis =
+            wikis(vec![page("/one/a.wiki", ""), page("/one/b.wiki", "")]);
+        assert!(matches!(
+            rename_page(
+                &wikis,
+                Path::new("/one/a.wiki"),
+                Path::new("/one/b.wiki")
+            ),
+            Err(RenameError::PageAlreadyExists { .. })
+        ));
+    }
+
+    #[test]
+    fn rename_page_should_fail_if_new_page_is_outside_wikis() {
+        let wikis = wikis(vec![page("/one/a.wiki", "")]);
+        assert!(matches!(
+            rename_page(
+                &wikis,
+                Path::new("/one/a.wiki"),
+                Path::new("/three/a.wiki")
+            ),
+            Err(RenameError::NotWithinWiki { .. })
+        ));
+    }
+
+    #[test]
+    fn rename_page_should_update_relative_and_absolute_links() {
+        let texts = [
+            ("/one/a.wiki", ""),
+            (
+                "/one/sub/b.wiki",
+                indoc! {"
+                    See [[../a|the page]] and [[/a#Some Header]].
+                    - [[../other]] stays
+                "},
+            ),
+        ];
+
+        assert_eq!(
+            rename(&texts, "/one/a.wiki", "/one/new/c.wiki"),
+            vec![(
+                String::from("/one/sub/b.wiki"),
+                String::from(indoc! {"
+                    See [[../new/c|the page]] and [[/new/c#Some Header]].
+                    - [[../other]] stays
+                "})
+            )]
+        );
+    }
+
+    #[test]
+    fn rename_page_should_update_interwiki_links() {
+        let texts = [
+            ("/one/a.wiki", ""),
+            ("/two/b.wiki", "[[wiki0:a]] [[wiki0:a#x|desc]]"),
+        ];
+
+        assert_eq!(
+            rename(&texts, "/one/a.wiki", "/one/c.wiki"),
+            vec![(
+                String::from("/two/b.wiki"),
+                String::from("[[wiki0:c]] [[wiki0:c#x|desc]]")
+            )]
+        );
+    }
+
+    #[test]
+    fn rename_page_should_switch_link_style_when_moving_between_wikis() {
+        let texts = [
+            ("/one/a.wiki", ""),
+            ("/one/b.wiki", "[[a]]"),
+            ("/two/c.wiki", "[[wiki0:a]]"),
+        ];
+
+        assert_eq!(
+            rename(&texts, "/one/a.wiki", "/two/a.wiki"),
+            vec![
+                (String::from("/one/b.wiki"), String::from("[[wn.two:a]]")),
+                (String::from("/two/c.wiki"), String::from("[[wiki1:a]]")),
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_page_should_update_diary_links() {
+        let texts = [
+            ("/one/diary/2021-01-02.wiki", ""),
+            ("/one/a.wiki", "[[diary:2021-01-02#x]]"),
+        ];
+
+        assert_eq!(
+            rename(
+                &texts,
+                "/one/diary/2021-01-02.wiki",
+                "/one/diary/2021-01-03.wiki"
+            ),
+            vec![(
+                String::from("/one/a.wiki"),
+                String::from("[[diary:2021-01-03#x]]")
+            )]
+        );
+
+        assert_eq!(
+            rename(&texts, "/one/diary/2021-01-02.wiki", "/one/notes.wiki"),
+            vec![(String::from("/one/a.wiki"), String::from("[[/notes#x]]"))]
+        );
+    }
+
+    #[test]
+    fn rename_page_should_update_file_links() {
+        let texts = [
+            ("/one/a.wiki", ""),
+            ("/one/sub/b.wiki", "[[file:../a.wiki]] local:/one/a.wiki"),
+        ];
+
+        assert_eq!(
+            rename(&texts, "/one/a.wiki", "/one/c.wiki"),
+            vec![(
+                String::from("/one/sub/b.wiki"),
+                String::from("[[file:../c.wiki]] local:/one/c.wiki")
+            )]
+        );
+    }
+
+    #[test]
+    fn rename_page_should_keep_links_within_moved_page_working() {
+        let texts = [
+            ("/one/sub/a.wiki", "[[b]] [[/c]] [[#x]] [[a#y]]"),
+            ("/one/sub/b.wiki", ""),
+        ];
+
+        assert_eq!(
+            rename(&texts, "/one/sub/a.wiki", "/one/d.wiki"),
+            vec![(
+                String::from("/one/sub/a.wiki"),
+                String::from("[[sub/b]] [[/c]] [[#x]] [[d#y]]")
+            )]
+        );
+    }
+}
@@ -0,0 +1,416 @@
+use super::{find_wiki_by_path, Wiki};
+use crate::{
+    lang::elements::{Anchor, Link, LinkData},
+    utils::normalize_path,
+};
+use derive_more::{Display, Error, IsVariant};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+use uriparse::Scheme;
+
+/// Represents the kind of resource targeted by a link
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, IsVariant)]
+pub enum LinkTargetKind {
+    /// Link targets a page within a wiki
+    Page,
+
+    /// Link targets a directory
+    Directory,
+
+    /// Link targets some arbitrary file such as an image
+    File,
+}
+
+/// Represents the local resource targeted by some link once resolved against
+/// a collection of wikis
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinkTarget {
+    /// Index of the wiki containing the target, if it is within a wiki
+    pub wiki: Option<usize>,
+
+    /// Kind of resource being targeted
+    pub kind: LinkTargetKind,
+
+    /// Normalized path to the resource being targeted
+    pub path: PathBuf,
+
+    /// Decoded anchor within the resource being targeted
+    pub anchor: Option<Anchor<'static>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+pub enum LinkTargetError {
+    /// Represents an error that occurred when resolving a link to a wiki
+    /// identified by index and determining that there is no loaded wiki with
+    /// the specified index
+    #[display(fmt = "No wiki with index {}", index)]
+    MissingWikiWithIndex {
+        #[error(not(source))]
+        index: usize,
+    },
+
+    /// Represents an error that occurred when resolving a link to a wiki
+    /// identified by name and determining that there is no loaded wiki with
+    /// the specified name
+    #[display(fmt = "No wiki with name {}", name)]
+    MissingWikiWithName {
+        #[error(not(source))]
+        name: String,
+    },
+
+    /// Represents an error that occurred when resolving a link that is
+    /// relative to the root of a wiki, but the file containing the link is
+    /// not within a wiki
+    #[display(fmt = "{:?} is not within a wiki", path)]
+    NotWithinWiki {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+}
+
+impl LinkTarget {
+    /// Resolves the link found within the file at `src` to the local
+    /// resource it targets, returning `None` if the link targets a remote
+    /// resource such as a website
+    pub fn resolve(
+        wikis: &[Wiki<'_>],
+        src: &Path,
+        link: &Link<'_>,
+    ) -> Result<Option<Self>, LinkTargetError> {
+        let src = normalize_path(src);
+        let src_wiki = find_wiki_by_path(wikis, &src);
+        let src_dir = src.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        // Wiki pages without a containing wiki use the extension of the
+        // source file when evaluating links
+        let ext = src_wiki.map(|w| w.ext.as_str()).unwrap_or_else(|| {
+            src.extension().and_then(OsStr::to_str).unwrap_or_default()
+        });
+
+        let src_wiki_or_err = || {
+            src_wiki.ok_or_else(|| LinkTargetError::NotWithinWiki {
+                path: src.to_path_buf(),
+            })
+        };
+
+        let (wiki, kind, path) = match link {
+            Link::Wiki { data } if data.is_local_anchor() => (
+                src_wiki.map(|w| w.index),
+                LinkTargetKind::Page,
+                src.to_path_buf(),
+            ),
+            Link::Wiki { data } => match data.scheme() {
+                None => {
+                    let base = if data.uri_ref.path().is_absolute() {
+                        src_wiki_or_err()?.path.to_path_buf()
+                    } else {
+                        src_dir
+                    };
+
+                    let (kind, path) = make_page_path(&base, data, ext);
+                    (src_wiki.map(|w| w.index), kind, path)
+                }
+                Some(scheme) if is_file_scheme(scheme) => {
+                    let path = make_file_path(&src_dir, data);
+                    (None, LinkTargetKind::File, path)
+                }
+                Some(_) => return Ok(None),
+            },
+            Link::IndexedInterWiki { index, data } => {
+                let index = *index as usize;
+                let wiki = wikis
+                    .iter()
+                    .find(|w| w.index == index)
+                    .ok_or(LinkTargetError::MissingWikiWithIndex { index })?;
+                let (kind, path) = make_page_path(&wiki.path, data, &wiki.ext);
+                (Some(wiki.index), kind, path)
+            }
+            Link::NamedInterWiki { name, data } => {
+                let wiki = wikis
+                    .iter()
+                    .find(|w| w.name.as_deref() == Some(name.as_ref()))
+                    .ok_or_else(|| LinkTargetError::MissingWikiWithName {
+                        name: name.to_string(),
+                    })?;
+                let (kind, path) = make_page_path(&wiki.path, data, &wiki.ext);
+                (Some(wiki.index), kind, path)
+            }
+            Link::Diary { date, .. } => {
+                let wiki = src_wiki_or_err()?;
+                (
+                    Some(wiki.index),
+                    LinkTargetKind::Page,
+                    wiki.make_diary_page_path(*date),
+                )
+            }
+            Link::Raw { data } => match data.scheme() {
+                Some(scheme) if is_file_scheme(scheme) => {
+                    let path = make_file_path(&src_dir, data);
+                    (None, LinkTargetKind::File, path)
+                }
+                _ => return Ok(None),
+            },
+            Link::Transclusion { data } => match data.scheme() {
+                // Transclusions without a scheme are local files where an
+                // absolute path is relative to the root of the wiki
+                None => {
+                    let base = if data.uri_ref.path().is_absolute() {
+                        src_wiki_or_err()?.path.to_path_buf()
+                    } else {
+                        src_dir
+                    };
+                    (None, LinkTargetKind::File, base.join(decode_path(data)))
+                }
+                Some(scheme) if is_file_scheme(scheme) => {
+                    let path = make_file_path(&src_dir, data);
+                    (None, LinkTargetKind::File, path)
+                }
+                Some(_) => return Ok(None),
+            },
+        };
+
+        let path = normalize_path(&path);
+        let wiki =
+            wiki.or_else(|| find_wiki_by_path(wikis, &path).map(|w| w.index));
+        let anchor = link
+            .to_anchor()
+            .filter(|a| a.iter().any(|x| !x.is_empty()))
+            .map(|a| {
+                a.iter()
+                    .map(|x| LinkData::decode_uri(x.as_bytes()))
+                    .collect()
+            });
+
+        Ok(Some(Self {
+            wiki,
+            kind,
+            path,
+            anchor,
+        }))
+    }
+
+    /// Returns true if the target and other target reference the same
+    /// resource and anchor, ignoring how the target was reached
+    pub fn is_same_destination(&self, other: &Self) -> bool {
+        self.path == other.path && self.anchor == other.anchor
+    }
+}
+
+/// Whether or not the scheme is one that points to a local file
+pub(super) fn is_file_scheme(scheme: &Scheme<'_>) -> bool {
+    match scheme {
+        Scheme::File => true,
+        Scheme::Unregistered(x) if x == "local" => true,
+        _ => false,
+    }
+}
+
+/// Produces a relative path from the percent-decoded segments of the link's
+/// uri, dropping any root or trailing separator
+fn decode_path(data: &LinkData<'_>) -> PathBuf {
+    data.uri_ref
+        .path()
+        .segments()
+        .iter()
+        .map(|s| LinkData::decode_uri(s.as_str()))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Produces the path to a page (or directory) relative to the base, adding
+/// the wiki extension when the link does not reference some other file type
+fn make_page_path(
+    base: &Path,
+    data: &LinkData<'_>,
+    ext: &str,
+) -> (LinkTargetKind, PathBuf) {
+    let path = base.join(decode_path(data));
+
+    if data.is_path_dir() {
+        return (LinkTargetKind::Directory, path);
+    }
+
+    // NOTE: Mirroring vimwiki, a link with an extension other than that of
+    //       the wiki is considered a link to an arbitrary file
+    match path.extension().and_then(OsStr::to_str) {
+        Some(x) if x == ext => (LinkTargetKind::Page, path),
+        Some(_) => (LinkTargetKind::File, path),
+        None => {
+            let mut path = path.into_os_string();
+            path.push(".");
+            path.push(ext);
+            (LinkTargetKind::Page, PathBuf::from(path))
+        }
+    }
+}
+
+/// Produces the path to a file referenced by a `file:` or `local:` link,
+/// where relative paths are relative to the directory of the source
+fn make_file_path(src_dir: &Path, data: &LinkData<'_>) -> PathBuf {
+    let path = decode_path(data);
+    if data.uri_ref.path().is_absolute() {
+        Path::new(&std::path::Component::RootDir).join(path)
+    } else {
+        src_dir.join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Located, Page};
+
+    fn wikis() -> Vec<Wiki<'static>> {
+        vec![
+            Wiki {
+                index: 0,
+                path: PathBuf::from("/one"),
+                ..Default::default()
+            },
+            Wiki {
+                index: 1,
+                name: Some(String::from("two")),
+                path: PathBuf::from("/two"),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn resolve(
+        src: &str,
+        text: &str,
+    ) -> Result<Option<LinkTarget>, LinkTargetError> {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let link = super::super::collect_links(&page)
+            .into_iter()
+            .next()
+            .map(Located::into_inner)
+            .expect("Missing link");
+        LinkTarget::resolve(&wikis(), Path::new(src), &link)
+    }
+
+    fn target(
+        wiki: Option<usize>,
+        kind: LinkTargetKind,
+        path: &str,
+        anchor: Option<&str>,
+    ) -> Option<LinkTarget> {
+        Some(LinkTarget {
+            wiki,
+            kind,
+            path: PathBuf::from(path),
+            anchor: anchor.map(|x| x.split('#').map(String::from).collect()),
+        })
+    }
+
+    #[test]
+    fn resolve_should_support_relative_wiki_links() {
+        assert_eq!(
+            resolve("/one/sub/a.wiki", "[[b]]").unwrap(),
+            target(Some(0), LinkTargetKind::Page, "/one/sub/b.wiki", None)
+        );
+        assert_eq!(
+            resolve("/one/sub/a.wiki", "[[../c d#e f]]").unwrap(),
+            target(Some(0), LinkTargetKind::Page, "/one/c d.wiki", Some("e f"))
+        );
+    }
+
+    #[test]
+    fn resolve_should_support_absolute_wiki_links() {
+        assert_eq!(
+            resolve("/one/sub/a.wiki", "[[/b/c]]").unwrap(),
+            target(Some(0), LinkTargetKind::Page, "/one/b/c.wiki", None)
+        );
+    }
+
+    #[test]
+    fn resolve_should_support_local_anchor_links() {
+        assert_eq!(
+            resolve("/one/a.wiki", "[[#b#c]]").unwrap(),
+            target(Some(0), LinkTargetKind::Page, "/one/a.wiki", Some("b#c"))
+        );
+    }
+
+    #[test]
+    fn resolve_should_support_directory_and_file_wiki_links() {
+        assert_eq!(
+            resolve("/one/a.wiki", "[[b/]]").unwrap(),
+            target(Some(0), LinkTargetKind::Directory, "/one/b", None)
+        );
+        assert_eq!(
+            resolve("/one/a.wiki", "[[b.pdf]]").unwrap(),
+            target(Some(0), LinkTargetKind::File, "/one/b.pdf", None)
+        );
+    }
+
+    #[test]
+    fn resolve_should_support_interwiki_links() {
+        assert_eq!(
+            resolve("/one/sub/a.wiki", "[[wiki1:b#c]]").unwrap(),
+            target(Some(1), LinkTargetKind::Page, "/two/b.wiki", Some("c"))
+        );
+        assert_eq!(
+            resolve("/one/sub/a.wiki", "[[wn.two:b]]").unwrap(),
+            target(Some(1), LinkTargetKind::Page, "/two/b.wiki", None)
+        );
+        assert_eq!(
+            resolve("/one/a.wiki", "[[wiki5:b]]").unwrap_err(),
+            LinkTargetError::MissingWikiWithIndex { index: 5 }
+        );
+        assert_eq!(
+            resolve("/one/a.wiki", "[[wn.three:b]]").unwrap_err(),
+            LinkTargetError::MissingWikiWithName {
+                name: String::from("three")
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_should_support_diary_links() {
+        assert_eq!(
+            resolve("/two/sub/a.wiki", "[[diary:2021-03-04]]").unwrap(),
+            target(
+                Some(1),
+                LinkTargetKind::Page,
+                "/two/diary/2021-03-04.wiki",
+                None
+            )
+        );
+        assert_eq!(
+            resolve("/other/a.wiki", "[[diary:2021-03-04]]").unwrap_err(),
+            LinkTargetError::NotWithinWiki {
+                path: PathBuf::from("/other/a.wiki")
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_should_support_local_files() {
+        assert_eq!(
+            resolve("/one/sub/a.wiki", "[[file:../b.txt]]").unwrap(),
+            target(Some(0), LinkTargetKind::File, "/one/b.txt", None)
+        );
+        assert_eq!(
+            resolve("/one/a.wiki", "{{/img/b.png}}").unwrap(),
+            target(Some(0), LinkTargetKind::File, "/one/img/b.png", None)
+        );
+        assert_eq!(
+            resolve("/one/a.wiki", "file:/tmp/b.txt").unwrap(),
+            target(None, LinkTargetKind::File, "/tmp/b.txt", None)
+        );
+    }
+
+    #[test]
+    fn resolve_should_skip_remote_links() {
+        assert_eq!(
+            resolve("/one/a.wiki", "https://example.com").unwrap(),
+            None
+        );
+        assert_eq!(
+            resolve("/one/a.wiki", "{{https://example.com/b.png}}").unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,559 @@
+use super::{collect_elements, Wiki};
+use crate::{
+    lang::elements::{
+        BlockElement, Element, InlineBlockElement, InlineElement, Page, Region,
+    },
+    utils::normalize_path,
+};
+use derive_more::{Display, IsVariant};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+mod query;
+pub use query::{SearchQuery, SearchQueryError};
+
+/// Represents the kind of content where text within a page was found
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    IsVariant,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    #[display(fmt = "header")]
+    Header,
+
+    #[display(fmt = "paragraph")]
+    Paragraph,
+
+    #[display(fmt = "list")]
+    List,
+
+    #[display(fmt = "code")]
+    Code,
+}
+
+impl SearchField {
+    /// Multiplier applied to the score of matches within the field, favoring
+    /// headers and lists over paragraphs and code
+    pub fn weight(self) -> f32 {
+        match self {
+            Self::Header => 3.0,
+            Self::List => 2.0,
+            Self::Paragraph => 1.0,
+            Self::Code => 0.5,
+        }
+    }
+}
+
+impl FromStr for SearchField {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "header" => Ok(Self::Header),
+            "paragraph" => Ok(Self::Paragraph),
+            "list" => Ok(Self::List),
+            "code" => Ok(Self::Code),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Represents a single match of a query within a page
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// Kind of content containing the match
+    pub field: SearchField,
+
+    /// Region within the page covered by the content containing the match
+    pub region: Region,
+}
+
+/// Represents a page matching a query alongside where it matched
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// Path to the page that matched
+    pub path: PathBuf,
+
+    /// Relevance of the page to the query, where higher is more relevant
+    pub score: f32,
+
+    /// Matches within the page ordered by position
+    pub hits: Vec<SearchHit>,
+}
+
+/// Represents a contiguous piece of content within an indexed page
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Segment {
+    field: SearchField,
+    region: Region,
+}
+
+/// Represents the information kept about each page within the index
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedPage {
+    /// Checksum of the page contents when it was indexed, if known
+    checksum: Option<String>,
+
+    /// Tags found anywhere within the page
+    tags: BTreeSet<String>,
+
+    /// Pieces of content that were indexed, referenced by postings
+    segments: Vec<Segment>,
+}
+
+/// Represents the positions of a term within a single segment of a page
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Posting {
+    segment: usize,
+    positions: Vec<usize>,
+}
+
+/// Represents an inverted index over the plain text of pages across one or
+/// more wikis, mapping each lowercase word to where it appears
+///
+/// The index can be updated one page at a time, and keeps an optional
+/// checksum for each page so callers can skip pages that have not changed
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    pages: BTreeMap<PathBuf, IndexedPage>,
+    terms: BTreeMap<String, BTreeMap<PathBuf, Vec<Posting>>>,
+}
+
+impl SearchIndex {
+    /// Creates a new, empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds an index from every page within the provided wikis
+    pub fn from_wikis(wikis: &[Wiki<'_>]) -> Self {
+        let mut index = Self::new();
+        for page in wikis.iter().flat_map(|w| w.pages.iter()) {
+            index.update_page(&page.path, None, &page.page);
+        }
+        index
+    }
+
+    /// Returns true if the index contains no pages
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Returns the total number of pages within the index
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Iterates through the path of each page within the index in sorted
+    /// order
+    pub fn pages(&self) -> impl Iterator<Item = &Path> {
+        self.pages.keys().map(PathBuf::as_path)
+    }
+
+    /// Returns the checksum recorded for the page at the given path, if the
+    /// page is indexed and was given a checksum
+    pub fn checksum(&self, path: &Path) -> Option<&str> {
+        self.pages
+            .get(&normalize_path(path))
+            .and_then(|page| page.checksum.as_deref())
+    }
+
+    /// Replaces everything indexed for the page at the given path with the
+    /// contents of the provided page, recording the checksum of the page
+    pub fn update_page(
+        &mut self,
+        path: &Path,
+        checksum: Option<String>,
+        page: &Page<'_>,
+    ) {
+        let path = normalize_path(path);
+        self.remove_page(&path);
+
+        let mut indexed = IndexedPage {
+            checksum,
+            ..Default::default()
+        };
+        for (idx, (field, region, text)) in
+            make_segments(page, &mut indexed.tags)
+                .into_iter()
+                .enumerate()
+        {
+            let mut positions: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+            for (pos, term) in tokenize(&text) {
+                positions.entry(term).or_default().push(pos);
+            }
+
+            for (term, positions) in positions {
+                self.terms
+                    .entry(term)
+                    .or_default()
+                    .entry(path.to_path_buf())
+                    .or_default()
+                    .push(Posting {
+                        segment: idx,
+                        positions,
+                    });
+            }
+            indexed.segments.push(Segment { field, region });
+        }
+
+        self.pages.insert(path, indexed);
+    }
+
+    /// Removes everything indexed for the page at the given path
+    pub fn remove_page(&mut self, path: &Path) {
+        let path = normalize_path(path);
+        if self.pages.remove(&path).is_some() {
+            self.terms.retain(|_, postings| {
+                postings.remove(&path);
+                !postings.is_empty()
+            });
+        }
+    }
+
+    /// Removes every page whose path does not satisfy the predicate
+    pub fn retain_pages<F: FnMut(&Path) -> bool>(&mut self, mut f: F) {
+        let removed: Vec<PathBuf> =
+            self.pages.keys().filter(|path| !f(path)).cloned().collect();
+        for path in removed {
+            self.remove_page(&path);
+        }
+    }
+
+    /// Searches the index, returning pages that match every word and phrase
+    /// of the query ordered from most to least relevant
+    ///
+    /// Each word contributes its frequency within a page, weighted by the
+    /// field where it appears and by how rare it is across all pages
+    pub fn search(&self, query: &SearchQuery) -> Vec<SearchResult> {
+        let mut results: Vec<SearchResult> = self
+            .pages
+            .iter()
+            .filter(|(path, page)| {
+                let path = path.to_string_lossy();
+                query.tags.iter().all(|tag| page.tags.contains(tag))
+                    && query.paths.iter().all(|p| path.contains(p.as_str()))
+            })
+            .filter_map(|(path, page)| {
+                let mut score = 0.0;
+                let mut segments = BTreeSet::new();
+
+                for phrase in query.phrases.iter() {
+                    let matches: Vec<(usize, usize)> = self
+                        .find_phrase(path, phrase)
+                        .into_iter()
+                        .filter(|(idx, _)| {
+                            query.includes_field(page.segments[*idx].field)
+                        })
+                        .collect();
+                    if matches.is_empty() {
+                        return None;
+                    }
+
+                    let idf = self.idf(&phrase[0]);
+                    for (idx, cnt) in matches {
+                        let field = page.segments[idx].field;
+                        score += cnt as f32 * field.weight() * idf;
+                        segments.insert(idx);
+                    }
+                }
+
+                let mut hits: Vec<SearchHit> = segments
+                    .into_iter()
+                    .map(|idx| SearchHit {
+                        field: page.segments[idx].field,
+                        region: page.segments[idx].region,
+                    })
+                    .collect();
+                hits.sort_by_key(|hit| hit.region.offset());
+
+                Some(SearchResult {
+                    path: path.to_path_buf(),
+                    score,
+                    hits,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        results
+    }
+
+    /// Finds each segment of the page at the given path where the terms of
+    /// the phrase appear one after another, returning the segment index and
+    /// the number of times the phrase appears within it
+    fn find_phrase(
+        &self,
+        path: &Path,
+        phrase: &[String],
+    ) -> Vec<(usize, usize)> {
+        let postings = |term: &str| -> &[Posting] {
+            self.terms
+                .get(term)
+                .and_then(|postings| postings.get(path))
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+        };
+
+        let (first, rest) = match phrase.split_first() {
+            Some(x) => x,
+            None => return Vec::new(),
+        };
+
+        postings(first)
+            .iter()
+            .filter_map(|posting| {
+                let cnt = posting
+                    .positions
+                    .iter()
+                    .filter(|pos| {
+                        rest.iter().enumerate().all(|(i, term)| {
+                            postings(term).iter().any(|p| {
+                                p.segment == posting.segment
+                                    && p.positions.contains(&(*pos + i + 1))
+                            })
+                        })
+                    })
+                    .count();
+                if cnt > 0 {
+                    Some((posting.segment, cnt))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Inverse document frequency of a term, which is larger for terms that
+    /// appear in fewer pages
+    fn idf(&self, term: &str) -> f32 {
+        let df = self.terms.get(term).map(BTreeMap::len).unwrap_or_default();
+        (1.0 + self.pages.len() as f32 / (1.0 + df as f32)).ln()
+    }
+}
+
+/// Splits text into lowercase words made up of alphanumeric characters,
+/// pairing each word with its position within the text
+pub(crate) fn tokenize(text: &str) -> Vec<(usize, String)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .enumerate()
+        .collect()
+}
+
+/// Produces the field, region, and plain text of each piece of content
+/// within a page in document order, collecting the tags of the page along
+/// the way
+///
+/// Paragraphs nested within list items are indexed as part of the list,
+/// while blockquotes, table cells, and definition lists are indexed as
+/// paragraphs
+pub(crate) fn make_segments(
+    page: &Page<'_>,
+    tags: &mut BTreeSet<String>,
+) -> Vec<(SearchField, Region, String)> {
+    let mut segments = Vec::new();
+    let mut list_end = 0;
+
+    for element in collect_elements(page) {
+        let region = element.region();
+        match element.into_inner() {
+            Element::Block(BlockElement::Header(x)) => {
+                segments.push((SearchField::Header, region, x.to_string()));
+            }
+            Element::Block(BlockElement::Paragraph(x)) => {
+                let field = if region.offset() < list_end {
+                    SearchField::List
+                } else {
+                    SearchField::Paragraph
+                };
+                segments.push((field, region, x.to_string()));
+            }
+            Element::Block(BlockElement::Blockquote(x)) => {
+                let lines: Vec<&str> = x
+                    .lines
+                    .iter()
+                    .map(|line| line.trim())
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                segments.push((
+                    SearchField::Paragraph,
+                    region,
+                    lines.join("\n"),
+                ));
+            }
+            Element::Block(BlockElement::CodeBlock(x)) => {
+                segments.push((SearchField::Code, region, x.lines.join("\n")));
+            }
+            Element::Block(BlockElement::Table(x)) => {
+                segments.extend(x.cells().filter_map(|cell| {
+                    cell.as_inner().get_content().map(|content| {
+                        (
+                            SearchField::Paragraph,
+                            cell.region(),
+                            content.to_string(),
+                        )
+                    })
+                }));
+            }
+            Element::InlineBlock(InlineBlockElement::ListItem(_)) => {
+                list_end = list_end.max(region.offset() + region.len());
+            }
+            Element::InlineBlock(InlineBlockElement::Term(x))
+            | Element::InlineBlock(InlineBlockElement::Definition(x)) => {
+                segments.push((SearchField::Paragraph, region, x.to_string()));
+            }
+            Element::Inline(InlineElement::Tags(x)) => {
+                tags.extend(x.into_iter().map(|tag| tag.to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wiki::WikiPage, Language};
+
+    fn parse(text: &str) -> Page<'static> {
+        Language::from_vimwiki_str(text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned()
+    }
+
+    fn make_index(pages: Vec<(&str, &str)>) -> SearchIndex {
+        SearchIndex::from_wikis(&[Wiki {
+            path: PathBuf::from("/wiki"),
+            pages: pages
+                .into_iter()
+                .map(|(path, text)| WikiPage::new(path, parse(text)))
+                .collect(),
+            ..Default::default()
+        }])
+    }
+
+    fn search(index: &SearchIndex, query: &str) -> Vec<PathBuf> {
+        index
+            .search(&query.parse().unwrap())
+            .into_iter()
+            .map(|result| result.path)
+            .collect()
+    }
+
+    #[test]
+    fn search_should_rank_header_matches_above_other_fields() {
+        let index = make_index(vec![
+            ("/wiki/a.wiki", "Some apple text.\n\n{{{\napple\n}}}\n"),
+            ("/wiki/b.wiki", "= Apple =\nOther text.\n"),
+            ("/wiki/c.wiki", "- an apple item\n- another\n"),
+            ("/wiki/d.wiki", "Nothing here.\n"),
+        ]);
+
+        assert_eq!(
+            search(&index, "apple"),
+            vec![
+                PathBuf::from("/wiki/b.wiki"),
+                PathBuf::from("/wiki/c.wiki"),
+                PathBuf::from("/wiki/a.wiki"),
+            ]
+        );
+
+        let results = index.search(&"apple".parse().unwrap());
+        assert_eq!(results[0].hits[0].field, SearchField::Header);
+        assert_eq!(results[1].hits[0].field, SearchField::List);
+        let fields: Vec<SearchField> =
+            results[2].hits.iter().map(|hit| hit.field).collect();
+        assert_eq!(fields, vec![SearchField::Paragraph, SearchField::Code]);
+        assert_eq!(results[2].hits[0].region.offset(), 0);
+
+        assert_eq!(
+            search(&index, "apple in:code"),
+            vec![PathBuf::from("/wiki/a.wiki")]
+        );
+    }
+
+    #[test]
+    fn search_should_match_phrases_and_apply_filters() {
+        let index = make_index(vec![
+            ("/wiki/a.wiki", "The quick brown fox. :animal:\n"),
+            ("/wiki/b.wiki", "The brown quick fox.\n"),
+            ("/wiki/notes/c.wiki", "A quick brown dog. :animal:\n"),
+        ]);
+
+        assert_eq!(
+            search(&index, "\"quick brown\""),
+            vec![
+                PathBuf::from("/wiki/a.wiki"),
+                PathBuf::from("/wiki/notes/c.wiki"),
+            ]
+        );
+        assert_eq!(
+            search(&index, "quick fox"),
+            vec![PathBuf::from("/wiki/a.wiki"), PathBuf::from("/wiki/b.wiki")]
+        );
+        assert_eq!(
+            search(&index, "quick tag:animal path:notes"),
+            vec![PathBuf::from("/wiki/notes/c.wiki")]
+        );
+        assert!(search(&index, "quick tag:missing").is_empty());
+    }
+
+    #[test]
+    fn search_should_match_words_within_blockquotes() {
+        let index = make_index(vec![
+            ("/wiki/a.wiki", "Intro.\n\n    A quoted pear.\n"),
+            ("/wiki/b.wiki", "Nothing here.\n"),
+        ]);
+
+        let results = index.search(&"pear".parse().unwrap());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, PathBuf::from("/wiki/a.wiki"));
+        assert_eq!(results[0].hits[0].field, SearchField::Paragraph);
+        assert_eq!(results[0].hits[0].region.offset(), 8);
+    }
+
+    #[test]
+    fn update_page_should_replace_indexed_content_of_page() {
+        let mut index = make_index(vec![("/wiki/a.wiki", "apple\n")]);
+        assert_eq!(index.checksum(Path::new("/wiki/a.wiki")), None);
+
+        index.update_page(
+            Path::new("/wiki/a.wiki"),
+            Some(String::from("abc")),
+            &parse("banana\n"),
+        );
+        assert!(search(&index, "apple").is_empty());
+        assert_eq!(search(&index, "banana").len(), 1);
+        assert_eq!(index.checksum(Path::new("/wiki/a.wiki")), Some("abc"));
+
+        index.retain_pages(|path| path != Path::new("/wiki/a.wiki"));
+        assert!(index.is_empty());
+        assert!(search(&index, "banana").is_empty());
+    }
+}
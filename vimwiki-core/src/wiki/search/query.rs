@@ -0,0 +1,142 @@
+use super::{tokenize, SearchField};
+use derive_more::{Display, Error};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+pub enum SearchQueryError {
+    /// Represents a query with a phrase missing its closing quote
+    #[display(fmt = "Phrase is missing closing quote")]
+    UnterminatedPhrase,
+
+    /// Represents a query that restricts the search to an unknown field
+    #[display(fmt = "Unknown field {}", name)]
+    UnknownField {
+        #[error(not(source))]
+        name: String,
+    },
+}
+
+/// Represents a query against a [`super::SearchIndex`]
+///
+/// Queries are written as a series of space-separated words, where
+///
+/// * `word` matches pages containing the word
+/// * `"some words"` matches pages containing the words next to each other
+/// * `tag:name` only includes pages containing the tag
+/// * `path:text` only includes pages whose path contains the text
+/// * `in:field` only matches words within a header, paragraph, list, or
+///   code field
+///
+/// A page must match every word and phrase of the query to be a result
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Sequences of terms that must appear next to one another, where a
+    /// single word is a sequence of one term
+    pub phrases: Vec<Vec<String>>,
+
+    /// Tags that a page must contain
+    pub tags: Vec<String>,
+
+    /// Text that the path of a page must contain
+    pub paths: Vec<String>,
+
+    /// Fields where terms can match, or any field if empty
+    pub fields: Vec<SearchField>,
+}
+
+impl SearchQuery {
+    /// Returns true if terms can match within the given field
+    pub fn includes_field(&self, field: SearchField) -> bool {
+        self.fields.is_empty() || self.fields.contains(&field)
+    }
+}
+
+impl FromStr for SearchQuery {
+    type Err = SearchQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Self::default();
+        let mut rest = s.trim_start();
+
+        while !rest.is_empty() {
+            let word = if let Some(phrase) = rest.strip_prefix('"') {
+                let end = phrase
+                    .find('"')
+                    .ok_or(SearchQueryError::UnterminatedPhrase)?;
+                rest = &phrase[end + 1..];
+                &phrase[..end]
+            } else {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let word = &rest[..end];
+                rest = &rest[end..];
+
+                if let Some(tag) = word.strip_prefix("tag:") {
+                    query.tags.push(tag.to_string());
+                    rest = rest.trim_start();
+                    continue;
+                } else if let Some(path) = word.strip_prefix("path:") {
+                    query.paths.push(path.to_string());
+                    rest = rest.trim_start();
+                    continue;
+                } else if let Some(name) = word.strip_prefix("in:") {
+                    query.fields.push(name.parse().map_err(|_| {
+                        SearchQueryError::UnknownField {
+                            name: name.to_string(),
+                        }
+                    })?);
+                    rest = rest.trim_start();
+                    continue;
+                }
+
+                word
+            };
+
+            let terms: Vec<String> =
+                tokenize(word).into_iter().map(|(_, t)| t).collect();
+            if !terms.is_empty() {
+                query.phrases.push(terms);
+            }
+            rest = rest.trim_start();
+        }
+
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_should_support_phrases_and_filters() {
+        let query: SearchQuery =
+            r#"One "two  Three" tag:todo path:notes in:header four-five"#
+                .parse()
+                .unwrap();
+        assert_eq!(
+            query.phrases,
+            vec![
+                vec!["one".to_string()],
+                vec!["two".to_string(), "three".to_string()],
+                vec!["four".to_string(), "five".to_string()],
+            ]
+        );
+        assert_eq!(query.tags, vec!["todo".to_string()]);
+        assert_eq!(query.paths, vec!["notes".to_string()]);
+        assert_eq!(query.fields, vec![SearchField::Header]);
+    }
+
+    #[test]
+    fn from_str_should_fail_if_phrase_is_unterminated_or_field_unknown() {
+        assert_eq!(
+            "\"one two".parse::<SearchQuery>(),
+            Err(SearchQueryError::UnterminatedPhrase)
+        );
+        assert_eq!(
+            "in:table".parse::<SearchQuery>(),
+            Err(SearchQueryError::UnknownField {
+                name: "table".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use super::{collect_elements, Wiki};
+use crate::{
+    lang::elements::{
+        BlockElement, Element, Header, InlineElement, Located, Page, Region,
+        Tag,
+    },
+    utils::normalize_path,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Represents the location of a single tag within a wiki
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagLocation {
+    /// Path to the page containing the tag
+    pub path: PathBuf,
+
+    /// Region within the page covered by the tag set containing the tag
+    pub region: Region,
+
+    /// Nearest header preceding the tag within the page, used to scope the
+    /// tag to a section of the page; none if the tag appears before any
+    /// header and is therefore scoped to the entire page
+    pub header: Option<Located<Header<'static>>>,
+}
+
+impl TagLocation {
+    /// Returns true if the tag is scoped to the entire page rather than
+    /// to a header within the page
+    #[inline]
+    pub fn is_page_scoped(&self) -> bool {
+        self.header.is_none()
+    }
+}
+
+/// Represents an index of every tag across one or more wikis, mapping each
+/// tag to the locations where it appears
+///
+/// The index can be updated one page at a time, so changes to a single page
+/// do not require rebuilding the entire index
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagIndex {
+    tags: BTreeMap<Tag<'static>, Vec<TagLocation>>,
+}
+
+impl TagIndex {
+    /// Creates a new, empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds an index from every page within the provided wikis
+    pub fn from_wikis(wikis: &[Wiki<'_>]) -> Self {
+        let mut index = Self::new();
+        for wiki in wikis {
+            for page in wiki.pages.iter() {
+                index.update_page(&page.path, &page.page);
+            }
+        }
+        index
+    }
+
+    /// Returns true if the index contains no tags
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Returns the total number of unique tags within the index
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Iterates through each tag within the index in sorted order
+    pub fn tags(&self) -> impl Iterator<Item = &Tag<'static>> {
+        self.tags.keys()
+    }
+
+    /// Iterates through each tag within the index in sorted order alongside
+    /// the locations where the tag appears
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&Tag<'static>, &[TagLocation])> {
+        self.tags.iter().map(|(tag, locs)| (tag, locs.as_slice()))
+    }
+
+    /// Finds the locations of the specified tag, returning an empty slice
+    /// if the tag is not within the index
+    pub fn find(&self, tag: &str) -> &[TagLocation] {
+        self.tags
+            .get(&Tag::from(tag.to_string()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Retains only the tags specified by the predicate
+    pub fn retain<F: FnMut(&Tag<'static>) -> bool>(&mut self, mut f: F) {
+        self.tags.retain(|tag, _| f(tag));
+    }
+
+    /// Replaces all tags associated with the page at the given path with
+    /// those found within the provided page
+    pub fn update_page(&mut self, path: &Path, page: &Page<'_>) {
+        let path = normalize_path(path);
+        self.remove_page(&path);
+
+        let mut header: Option<Located<Header<'static>>> = None;
+        for element in collect_elements(page) {
+            let region = element.region();
+            match element.into_inner() {
+                Element::Block(BlockElement::Header(x)) => {
+                    header = Some(Located::new(x.into_owned(), region));
+                }
+                Element::Inline(InlineElement::Tags(tags)) => {
+                    for tag in tags {
+                        self.tags.entry(tag.into_owned()).or_default().push(
+                            TagLocation {
+                                path: path.to_path_buf(),
+                                region,
+                                header: header.clone(),
+                            },
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Removes all tags associated with the page at the given path
+    pub fn remove_page(&mut self, path: &Path) {
+        let path = normalize_path(path);
+        for locations in self.tags.values_mut() {
+            locations.retain(|loc| loc.path != path);
+        }
+        self.tags.retain(|_, locations| !locations.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn parse(text: &str) -> Page<'static> {
+        Language::from_vimwiki_str(text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn update_page_should_scope_tags_to_nearest_preceding_header() {
+        let mut index = TagIndex::new();
+        index.update_page(
+            Path::new("/wiki/a.wiki"),
+            &parse(":top:\n= One =\n:one:shared:\n== Two ==\ntext :shared:\n"),
+        );
+
+        let tags: Vec<&str> = index.tags().map(Tag::as_str).collect();
+        assert_eq!(tags, vec!["one", "shared", "top"]);
+
+        let top = index.find("top");
+        assert_eq!(top.len(), 1);
+        assert!(top[0].is_page_scoped());
+
+        let one = index.find("one");
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].header.as_ref().unwrap().to_string(), "One");
+
+        let headers: Vec<String> = index
+            .find("shared")
+            .iter()
+            .map(|loc| loc.header.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(headers, vec!["One", "Two"]);
+    }
+
+    #[test]
+    fn update_page_should_replace_existing_tags_of_page() {
+        let mut index = TagIndex::new();
+        index.update_page(Path::new("/wiki/a.wiki"), &parse(":old:shared:\n"));
+        index.update_page(Path::new("/wiki/b.wiki"), &parse(":shared:\n"));
+        index.update_page(Path::new("/wiki/a.wiki"), &parse(":new:\n"));
+
+        assert!(index.find("old").is_empty());
+        assert_eq!(index.find("new").len(), 1);
+
+        let paths: Vec<&Path> = index
+            .find("shared")
+            .iter()
+            .map(|loc| loc.path.as_path())
+            .collect();
+        assert_eq!(paths, vec![Path::new("/wiki/b.wiki")]);
+    }
+
+    #[test]
+    fn remove_page_should_drop_tags_no_longer_used() {
+        let mut index = TagIndex::new();
+        index.update_page(Path::new("/wiki/a.wiki"), &parse(":tag:\n"));
+        index.remove_page(Path::new("/wiki/a.wiki"));
+        assert!(index.is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 #![allow(clippy::large_enum_variant)]
 
-use crate::{database::gql_db, utils, Config};
+use crate::{
+    database::{gql_db, search_index, tag_index, wiki_graph, wikis},
+    utils, Config,
+};
 use entity::{TypedPredicate as P, *};
 use entity_async_graphql::*;
 use sha1::{Digest, Sha1};
@@ -19,6 +22,14 @@ pub struct Wiki {
     name: Option<String>,
     path: String,
 
+    /// File extension of the wiki's pages
+    #[ent(field(mutable))]
+    ext: String,
+
+    /// Path of the wiki's diary directory relative to the wiki's path
+    #[ent(field(mutable))]
+    diary_rel_path: String,
+
     #[ent(edge(policy = "deep"))]
     files: Vec<ParsedFile>,
 }
@@ -44,6 +55,7 @@ impl Wiki {
                     &wc.path,
                     wc.name.as_ref(),
                     wc.ext.as_str(),
+                    wc.diary_rel_path.as_path(),
                     before_loading_files,
                     on_file_loaded,
                     after_loading_files,
@@ -65,6 +77,7 @@ impl Wiki {
         path: impl AsRef<Path>,
         name: Option<N>,
         ext: &str,
+        diary_rel_path: &Path,
         before_loading_files: F1,
         on_file_loaded: F2,
         after_loading_files: F3,
@@ -88,7 +101,13 @@ impl Wiki {
             .into_iter()
             .next();
 
-        let mut wiki = if let Some(wiki) = existing_wiki {
+        let diary_rel_path = diary_rel_path.to_string_lossy().to_string();
+        let mut wiki = if let Some(mut wiki) = existing_wiki {
+            wiki.set_ext(ext.to_string());
+            wiki.set_diary_rel_path(diary_rel_path);
+            let _ = wiki
+                .commit()
+                .map_err(|x| async_graphql::Error::new(x.to_string()))?;
             wiki
         } else {
             GraphqlDatabaseError::wrap(
@@ -96,6 +115,8 @@ impl Wiki {
                     .index(index)
                     .name(name.map(|x| x.as_ref().to_string()))
                     .path(c_path.to_string_lossy().to_string())
+                    .ext(ext.to_string())
+                    .diary_rel_path(diary_rel_path)
                     .files(Vec::new())
                     .finish_and_commit(),
             )
@@ -121,6 +142,91 @@ impl Wiki {
 
         Ok(wiki)
     }
+
+    /// Converts into a vimwiki wiki by re-parsing each of the wiki's files
+    /// from disk
+    pub async fn to_vimwiki(&self) -> async_graphql::Result<v::Wiki<'static>> {
+        let files = self
+            .load_files()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        let mut pages = Vec::new();
+        for file in files {
+            pages.push(file.to_vimwiki().await?);
+        }
+
+        Ok(v::Wiki {
+            pages,
+            ..self.to_empty_vimwiki()
+        })
+    }
+
+    /// Converts into a vimwiki wiki without any pages, which is enough to
+    /// resolve links against the wiki
+    pub fn to_empty_vimwiki(&self) -> v::Wiki<'static> {
+        v::Wiki {
+            index: *self.index(),
+            name: self.name().clone(),
+            path: PathBuf::from(self.path()),
+            diary_rel_path: PathBuf::from(self.diary_rel_path()),
+            ext: self.ext().to_string(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// Generates the index of the wiki's diary, updating the generated
+    /// section in place if the index already exists, and loads the resulting
+    /// file
+    pub async fn generate_diary_index(
+        &self,
+        header: &str,
+        captions: bool,
+    ) -> async_graphql::Result<ParsedFile> {
+        let wiki = self.to_vimwiki().await?;
+
+        let path = wiki.make_diary_index_path();
+        let text = if path.exists() {
+            tokio::fs::read_to_string(path.as_path())
+                .await
+                .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        } else {
+            String::new()
+        };
+        let page: v::Page = Language::from_vimwiki_str(&text).parse().map_err(
+            |x: ParseError| async_graphql::Error::new(x.to_string()),
+        )?;
+
+        let fragment = v::make_diary_index(&wiki, header, captions);
+        let edit = v::make_section_edit(
+            &text,
+            &page,
+            &fragment,
+            v::SectionPlacement::End,
+            v::VimwikiConfig::default(),
+        )
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        if let Some(edit) = edit {
+            let new_text = v::FileEdits {
+                path: path.to_path_buf(),
+                edits: vec![edit],
+            }
+            .apply(&text);
+
+            if new_text != text {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await.map_err(|x| {
+                        async_graphql::Error::new(x.to_string())
+                    })?;
+                }
+                tokio::fs::write(path.as_path(), new_text)
+                    .await
+                    .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            }
+        }
+
+        ParsedFile::load(self.id(), path).await
+    }
 }
 
 #[gql_ent]
@@ -203,6 +309,23 @@ impl ParsedFile {
         // it from the database
         let old_wiki_id = if let Some(ent) = maybe_ent {
             if ent.checksum() == &checksum {
+                // Files restored from the database cache still need to be
+                // indexed the first time they are loaded
+                if let Some(wiki_id) = ent.wiki_id() {
+                    if search_index()
+                        .read()
+                        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+                        .checksum(c_path.as_path())
+                        != Some(checksum.as_str())
+                    {
+                        let page: v::Page = Language::from_vimwiki_str(&text)
+                            .parse()
+                            .map_err(|x: ParseError| {
+                                async_graphql::Error::new(x.to_string())
+                            })?;
+                        index_page(wiki_id, c_path.as_path(), checksum, &page)?;
+                    }
+                }
                 return Ok(ent);
             } else {
                 let id = ent.wiki_id();
@@ -218,27 +341,48 @@ impl ParsedFile {
             |x: ParseError| async_graphql::Error::new(x.to_string()),
         )?;
 
-        // Fifth, save the parsed file with a temporary page id
+        // Fifth, index the page if it belongs to a wiki
+        let wiki_id = wiki_id.into().or(old_wiki_id);
+        if let Some(wiki_id) = wiki_id {
+            index_page(wiki_id, c_path.as_path(), checksum.to_string(), &page)?;
+        }
+
+        // Sixth, save the parsed file with a temporary page id
         let mut parsed_file = GraphqlDatabaseError::wrap(
             Self::build()
                 .path(c_path.to_string_lossy().to_string())
                 .checksum(checksum)
-                .wiki(wiki_id.into().or(old_wiki_id))
+                .wiki(wiki_id)
                 .page(EPHEMERAL_ID)
                 .finish_and_commit(),
         )
         .map_err(|x| async_graphql::Error::new(x.to_string()))?;
 
-        // Sixth, save the vimwiki page as a graphql page
+        // Seventh, save the vimwiki page as a graphql page
         let page_id = Page::create_from_vimwiki(parsed_file.id(), page)?.id();
 
-        // Seventh, update the parsed file's page id
+        // Eighth, update the parsed file's page id
         parsed_file.set_page_id(page_id);
         parsed_file.commit()?;
 
         Ok(parsed_file)
     }
 
+    /// Converts into a vimwiki page by re-parsing the file from disk
+    pub async fn to_vimwiki(
+        &self,
+    ) -> async_graphql::Result<v::WikiPage<'static>> {
+        let text = tokio::fs::read_to_string(self.path())
+            .await
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        let page: v::Page = Language::from_vimwiki_str(&text).parse().map_err(
+            |x: ParseError| async_graphql::Error::new(x.to_string()),
+        )?;
+
+        Ok(v::WikiPage::new(self.path(), page.into_owned()))
+    }
+
     pub async fn rename<P1: AsRef<Path>, P2: AsRef<Path>>(
         from_path: P1,
         to_path: P2,
@@ -265,6 +409,26 @@ impl ParsedFile {
             ent.set_path(c_to_path.to_string_lossy().to_string());
             ent.commit()
                 .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+            // The index is keyed by path, so the page is indexed again under
+            // its new path
+            if let Some(wiki_id) = ent.wiki_id() {
+                let text = tokio::fs::read_to_string(c_to_path.as_path())
+                    .await
+                    .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+                let page: v::Page = Language::from_vimwiki_str(&text)
+                    .parse()
+                    .map_err(|x: ParseError| {
+                    async_graphql::Error::new(x.to_string())
+                })?;
+                unindex_page(c_from_path.as_path())?;
+                index_page(
+                    wiki_id,
+                    c_to_path.as_path(),
+                    ent.checksum().to_string(),
+                    &page,
+                )?;
+            }
         }
 
         Ok(())
@@ -285,6 +449,8 @@ impl ParsedFile {
             .await
             .map_err(|x| async_graphql::Error::new(x.to_string()))?;
 
+        unindex_page(c_path.as_path())?;
+
         let ents = gql_db()?
             .find_all_typed::<ParsedFile>(
                 ParsedFile::query()
@@ -301,3 +467,78 @@ impl ParsedFile {
         Ok(())
     }
 }
+
+/// Replaces what is kept for the given path across the parsed pages of every
+/// wiki, the search and tag indexes, and the graph of links with the page
+fn index_page(
+    wiki_id: Id,
+    path: &Path,
+    checksum: String,
+    page: &v::Page<'_>,
+) -> async_graphql::Result<()> {
+    let wiki = gql_db()?
+        .get_typed::<Wiki>(wiki_id)
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        .ok_or_else(|| {
+            async_graphql::Error::new(format!("Wiki {} not found", wiki_id))
+        })?
+        .to_empty_vimwiki();
+
+    let mut wikis = wikis()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+    let idx = match wikis.iter().position(|w| w.path == wiki.path) {
+        Some(idx) => {
+            let pages = std::mem::take(&mut wikis[idx].pages);
+            wikis[idx] = v::Wiki { pages, ..wiki };
+            idx
+        }
+        None => {
+            wikis.push(wiki);
+            wikis.len() - 1
+        }
+    };
+    wikis[idx].pages.retain(|p| p.path != path);
+    wikis[idx]
+        .pages
+        .push(v::WikiPage::new(path, page.clone().into_owned()));
+
+    search_index()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        .update_page(path, Some(checksum), page);
+    tag_index()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        .update_page(path, page);
+    wiki_graph()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        .update_page(&wikis, path, page);
+    Ok(())
+}
+
+/// Removes what is kept for the given path across the parsed pages of every
+/// wiki, the search and tag indexes, and the graph of links
+fn unindex_page(path: &Path) -> async_graphql::Result<()> {
+    let mut wikis = wikis()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+    for wiki in wikis.iter_mut() {
+        wiki.pages.retain(|p| p.path != path);
+    }
+
+    search_index()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        .remove_page(path);
+    tag_index()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        .remove_page(path);
+    wiki_graph()
+        .write()
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?
+        .remove_page(path);
+    Ok(())
+}
@@ -2,8 +2,29 @@ use crate::{data::Wiki, utils, Config, Opt};
 use async_graphql::ErrorExtensions;
 use entity::*;
 use entity_inmemory::InmemoryDatabase;
+use lazy_static::lazy_static;
 use snafu::{ResultExt, Snafu};
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::RwLock};
+use vimwiki::{self as v, SearchIndex, TagIndex, WikiGraph};
+
+lazy_static! {
+    /// Parsed pages of every wiki, kept up to date as files are loaded,
+    /// renamed, and removed
+    static ref WIKIS: RwLock<Vec<v::Wiki<'static>>> = RwLock::new(Vec::new());
+
+    /// Search index over the files of every wiki, kept up to date as files
+    /// are loaded, renamed, and removed
+    static ref SEARCH_INDEX: RwLock<SearchIndex> =
+        RwLock::new(SearchIndex::new());
+
+    /// Tags across the files of every wiki, kept up to date as files are
+    /// loaded, renamed, and removed
+    static ref TAG_INDEX: RwLock<TagIndex> = RwLock::new(TagIndex::new());
+
+    /// Links between the files of every wiki, kept up to date as files are
+    /// loaded, renamed, and removed
+    static ref WIKI_GRAPH: RwLock<WikiGraph> = RwLock::new(WikiGraph::new());
+}
 
 #[derive(Debug, Snafu)]
 pub enum VimwikiDatabaseError {
@@ -46,6 +67,31 @@ pub fn gql_db() -> async_graphql::Result<DatabaseRc> {
         .ok_or_else(|| VimwikiDatabaseError::DatabaseUnavailable.extend())
 }
 
+/// Provides reference to global parsed pages of every wiki
+#[inline]
+pub fn wikis() -> &'static RwLock<Vec<v::Wiki<'static>>> {
+    &WIKIS
+}
+
+/// Provides reference to global search index of the files of every wiki
+#[inline]
+pub fn search_index() -> &'static RwLock<SearchIndex> {
+    &SEARCH_INDEX
+}
+
+/// Provides reference to global tag index of the files of every wiki
+#[inline]
+pub fn tag_index() -> &'static RwLock<TagIndex> {
+    &TAG_INDEX
+}
+
+/// Provides reference to global graph of links between the files of every
+/// wiki
+#[inline]
+pub fn wiki_graph() -> &'static RwLock<WikiGraph> {
+    &WIKI_GRAPH
+}
+
 /// Load database state using given opt
 pub async fn load(
     opt: &Opt,
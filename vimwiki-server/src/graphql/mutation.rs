@@ -1,6 +1,7 @@
-use crate::data::*;
-use entity::Id;
+use crate::{data::*, database::gql_db};
+use entity::*;
 use log::trace;
+use std::path::Path;
 
 pub struct Mutation;
 
@@ -13,6 +14,7 @@ impl Mutation {
         index: u32,
         name: Option<String>,
         #[graphql(default = "wiki")] ext: String,
+        #[graphql(default = "diary")] diary_rel_path: String,
     ) -> async_graphql::Result<Wiki> {
         trace!(
             "import_wiki(path: {:?}, index: {}, name: {:?})",
@@ -25,6 +27,7 @@ impl Mutation {
             path,
             name,
             ext.as_str(),
+            Path::new(&diary_rel_path),
             |_| {},
             |_, _, _| {},
             |_| {},
@@ -61,4 +64,28 @@ impl Mutation {
         );
         ParsedFile::create(wiki, path, contents, overwrite).await
     }
+
+    /// Generates the index of a wiki's diary, grouping its diary pages by
+    /// year and month. If the index already exists, its generated section
+    /// will be updated in place.
+    async fn generate_diary_index(
+        &self,
+        wiki: Id,
+        #[graphql(default = "Diary")] header: String,
+        #[graphql(default)] captions: bool,
+    ) -> async_graphql::Result<ParsedFile> {
+        trace!(
+            "generate_diary_index(wiki: {}, header: {:?}, captions: {})",
+            wiki,
+            header,
+            captions
+        );
+        let wiki = gql_db()?
+            .get_typed::<Wiki>(wiki)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?
+            .ok_or_else(|| {
+                async_graphql::Error::new(format!("Wiki {} not found", wiki))
+            })?;
+        wiki.generate_diary_index(header.as_str(), captions).await
+    }
 }
@@ -1,5 +1,6 @@
-use crate::data::Element;
+use crate::data::{Element, Region};
 use entity::{TypedPredicate as P, *};
+use vimwiki::{self as v, Language, ParseError};
 
 #[derive(Default)]
 pub struct MiscQuery;
@@ -31,4 +32,51 @@ impl MiscQuery {
             .map(|x| x.into_iter().next())
             .map_err(|x| async_graphql::Error::new(x.to_string()))
     }
+
+    /// Formats the block elements of the file at the given path that overlap
+    /// the byte range from start (inclusive) to end (exclusive), returning the
+    /// smallest edit needed or none if those elements are already formatted
+    async fn format_range(
+        &self,
+        path: String,
+        start: usize,
+        end: usize,
+    ) -> async_graphql::Result<Option<TextEdit>> {
+        let text = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        let page: v::Page = Language::from_vimwiki_str(&text).parse().map_err(
+            |x: ParseError| async_graphql::Error::new(x.to_string()),
+        )?;
+
+        let edit = v::format_range(
+            &text,
+            &page,
+            start..end,
+            v::VimwikiConfig::default(),
+        )
+        .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        Ok(edit.map(TextEdit::from))
+    }
+}
+
+/// Represents a replacement of the text found within some region of a file
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct TextEdit {
+    /// Segment of the original text to be replaced
+    region: Region,
+
+    /// Text to put in place of the region
+    new_text: String,
+}
+
+impl From<v::TextEdit> for TextEdit {
+    fn from(edit: v::TextEdit) -> Self {
+        Self {
+            region: Region::from(edit.region),
+            new_text: edit.new_text,
+        }
+    }
 }
@@ -1,9 +1,11 @@
 mod misc;
 mod obj;
+mod wiki;
 
 pub use misc::MiscQuery;
 pub use obj::ObjQuery;
+pub use wiki::WikiQuery;
 
 /// Represents the query-portion of the GraphQL schema
 #[derive(async_graphql::MergedObject, Default)]
-pub struct Query(ObjQuery, MiscQuery);
+pub struct Query(ObjQuery, MiscQuery, WikiQuery);
@@ -0,0 +1,330 @@
+use crate::{
+    data::Region,
+    database::{search_index, tag_index, wiki_graph, wikis},
+};
+use std::path::Path;
+use vimwiki as v;
+
+#[derive(Default)]
+pub struct WikiQuery;
+
+#[async_graphql::Object]
+impl WikiQuery {
+    /// Lists every tag found across all wikis in sorted order
+    async fn tags(&self) -> async_graphql::Result<Vec<String>> {
+        let index = tag_index()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        Ok(index.tags().map(|tag| tag.as_str().to_string()).collect())
+    }
+
+    /// Searches all wikis for the locations where the specified tag appears
+    async fn tag_locations(
+        &self,
+        tag: String,
+    ) -> async_graphql::Result<Vec<TagLocation>> {
+        let index = tag_index()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        Ok(index
+            .find(tag.as_str())
+            .iter()
+            .map(TagLocation::from)
+            .collect())
+    }
+
+    /// Lists the links from other files that target the file at the given
+    /// path
+    async fn backlinks(
+        &self,
+        path: String,
+    ) -> async_graphql::Result<Vec<GraphEdge>> {
+        let graph = wiki_graph()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        Ok(graph
+            .backlinks(Path::new(&path))
+            .into_iter()
+            .map(GraphEdge::from)
+            .collect())
+    }
+
+    /// Lists the links within the file at the given path that target other
+    /// files
+    async fn outgoing_links(
+        &self,
+        path: String,
+    ) -> async_graphql::Result<Vec<GraphEdge>> {
+        let graph = wiki_graph()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        Ok(graph
+            .outgoing(Path::new(&path))
+            .iter()
+            .map(GraphEdge::from)
+            .collect())
+    }
+
+    /// Lists the paths of files across all wikis that no other file links to
+    async fn orphans(&self) -> async_graphql::Result<Vec<String>> {
+        let graph = wiki_graph()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        Ok(graph
+            .orphans()
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Checks all wikis against the builtin lint rules, skipping any rules
+    /// whose names are provided as disabled
+    async fn diagnostics(
+        &self,
+        #[graphql(default)] disabled: Vec<String>,
+    ) -> async_graphql::Result<Vec<Diagnostic>> {
+        let wikis = wikis()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        let config = v::LintConfig {
+            disabled,
+            ..Default::default()
+        };
+        Ok(v::LintRegistry::default()
+            .lint(&wikis, &config)
+            .iter()
+            .map(Diagnostic::from)
+            .collect())
+    }
+
+    /// Lists the metadata described by the placeholders of each file across
+    /// all wikis, only including files matching every filter (written as
+    /// name, !name, name=value, or name!=value) and ordered by the entry
+    /// with the given sort name or otherwise by path
+    async fn file_metadata(
+        &self,
+        #[graphql(default)] filters: Vec<String>,
+        sort: Option<String>,
+    ) -> async_graphql::Result<Vec<FileMetadata>> {
+        let filters = filters
+            .iter()
+            .map(|x| x.parse::<v::MetadataFilter>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let wikis = wikis()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        let mut files: Vec<(&Path, v::PageMetadata)> = wikis
+            .iter()
+            .flat_map(|w| w.pages.iter())
+            .map(|p| (p.path.as_path(), v::PageMetadata::from_page(&p.page)))
+            .filter(|(_, metadata)| filters.iter().all(|f| f.matches(metadata)))
+            .collect();
+
+        files.sort_by(|a, b| match sort.as_deref() {
+            Some(name) => a.1.cmp_by(&b.1, name).then_with(|| a.0.cmp(b.0)),
+            None => a.0.cmp(b.0),
+        });
+
+        Ok(files
+            .into_iter()
+            .map(|(path, metadata)| FileMetadata {
+                path: path.to_string_lossy().to_string(),
+                entries: metadata
+                    .iter()
+                    .map(|(name, value)| MetadataEntry {
+                        name: name.to_string(),
+                        kind: value.kind().to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Searches the text of all files across all wikis, returning files that
+    /// match every word and phrase of the query from most to least relevant
+    ///
+    /// Queries support "quoted phrases", tag:NAME and path:TEXT filters, and
+    /// in:FIELD to only match within a header, paragraph, list, or code
+    async fn search(
+        &self,
+        query: String,
+    ) -> async_graphql::Result<Vec<SearchResult>> {
+        let query: v::SearchQuery = query.parse()?;
+        let index = search_index()
+            .read()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        Ok(index
+            .search(&query)
+            .iter()
+            .map(SearchResult::from)
+            .collect())
+    }
+}
+
+/// Represents a location within a wiki where a tag appears
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct TagLocation {
+    /// Path to the file containing the tag
+    path: String,
+
+    /// Segment of the file covered by the tags containing the tag
+    region: Region,
+
+    /// Text of the nearest header preceding the tag, or none if the tag
+    /// is scoped to the entire file
+    header: Option<String>,
+
+    /// Level of the nearest header preceding the tag, or none if the tag
+    /// is scoped to the entire file
+    header_level: Option<usize>,
+}
+
+impl<'a> From<&'a v::TagLocation> for TagLocation {
+    fn from(location: &'a v::TagLocation) -> Self {
+        Self {
+            path: location.path.to_string_lossy().to_string(),
+            region: Region::from(location.region),
+            header: location.header.as_ref().map(ToString::to_string),
+            header_level: location.header.as_ref().map(|x| x.level),
+        }
+    }
+}
+
+/// Represents a link from one file to another within a wiki
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct GraphEdge {
+    /// Path to the file containing the link
+    src: String,
+
+    /// Path to the file targeted by the link
+    dst: String,
+
+    /// Segment of the source file covered by the link
+    region: Region,
+
+    /// Anchor within the targeted file in the form of #one#two
+    anchor: Option<String>,
+
+    /// Description of the link
+    description: Option<String>,
+}
+
+impl<'a> From<&'a v::GraphEdge> for GraphEdge {
+    fn from(edge: &'a v::GraphEdge) -> Self {
+        Self {
+            src: edge.src.to_string_lossy().to_string(),
+            dst: edge.dst.to_string_lossy().to_string(),
+            region: Region::from(edge.region),
+            anchor: edge.anchor.as_ref().map(ToString::to_string),
+            description: edge.description.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+/// Represents a problem reported by a lint rule for a file within a wiki
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct Diagnostic {
+    /// Name of the rule that reported the problem
+    rule: String,
+
+    /// How serious the problem is (hint, info, warning, or error)
+    severity: String,
+
+    /// Path to the file containing the problem
+    path: String,
+
+    /// Segment of the file where the problem was found
+    region: Region,
+
+    /// Description of the problem
+    message: String,
+
+    /// Text that resolves the problem when put in place of the segment of
+    /// the file described by fix_region
+    fix_text: Option<String>,
+
+    /// Segment of the file to replace with fix_text
+    fix_region: Option<Region>,
+}
+
+impl<'a> From<&'a v::Diagnostic> for Diagnostic {
+    fn from(diagnostic: &'a v::Diagnostic) -> Self {
+        Self {
+            rule: diagnostic.rule.to_string(),
+            severity: diagnostic.severity.to_string(),
+            path: diagnostic.path.to_string_lossy().to_string(),
+            region: Region::from(diagnostic.region),
+            message: diagnostic.message.to_string(),
+            fix_text: diagnostic.fix.as_ref().map(|x| x.new_text.to_string()),
+            fix_region: diagnostic.fix.as_ref().map(|x| Region::from(x.region)),
+        }
+    }
+}
+
+/// Represents a file matching a search query
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct SearchResult {
+    /// Path to the file that matched
+    path: String,
+
+    /// Relevance of the file to the query, where higher is more relevant
+    score: f32,
+
+    /// Matches within the file ordered by position
+    hits: Vec<SearchHit>,
+}
+
+impl<'a> From<&'a v::SearchResult> for SearchResult {
+    fn from(result: &'a v::SearchResult) -> Self {
+        Self {
+            path: result.path.to_string_lossy().to_string(),
+            score: result.score,
+            hits: result.hits.iter().map(SearchHit::from).collect(),
+        }
+    }
+}
+
+/// Represents a single match of a search query within a file
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct SearchHit {
+    /// Kind of content containing the match (header, paragraph, list, or
+    /// code)
+    field: String,
+
+    /// Segment of the file covered by the content containing the match
+    region: Region,
+}
+
+impl<'a> From<&'a v::SearchHit> for SearchHit {
+    fn from(hit: &'a v::SearchHit) -> Self {
+        Self {
+            field: hit.field.to_string(),
+            region: Region::from(hit.region),
+        }
+    }
+}
+
+/// Represents the metadata described by the placeholders of a file
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct FileMetadata {
+    /// Path to the file
+    path: String,
+
+    /// Entries of the metadata ordered by name
+    entries: Vec<MetadataEntry>,
+}
+
+/// Represents a single named entry of a file's metadata
+#[derive(async_graphql::SimpleObject, Clone, Debug)]
+pub struct MetadataEntry {
+    /// Name of the entry, such as title or author
+    name: String,
+
+    /// Kind of value held by the entry (text, bool, integer, date, or list)
+    kind: String,
+
+    /// Value of the entry as text, where lists are separated by commas
+    value: String,
+}
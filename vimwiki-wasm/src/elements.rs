@@ -1736,6 +1736,13 @@ macro_rules! impl_convert {
                 JsValue::from_serde(&self.0).unwrap()
             }
 
+            /// Convert to a JavaScript value of the form
+            /// `{ version, data }`, where version is the schema version
+            /// describing the data
+            pub fn to_versioned_js(&self) -> JsValue {
+                JsValue::from_serde(&v::Versioned::new(&self.0)).unwrap()
+            }
+
             /// Convert to a debug string
             pub fn to_debug_str(&self) -> String {
                 format!("{:?}", self.0)
@@ -16,3 +16,10 @@ pub fn parse_vimwiki_str(s: &str) -> Result<Page, JsValue> {
         Err(x) => Err(x.to_string().into()),
     }
 }
+
+/// Returns the version of the schema describing serialized pages and
+/// elements, matching the version of `to_versioned_js` output
+#[wasm_bindgen]
+pub fn schema_version() -> u32 {
+    v::SCHEMA_VERSION
+}